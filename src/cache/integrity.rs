@@ -0,0 +1,162 @@
+//! This module reports foreign key violations in terms of the resources involved.
+
+use anyhow::Result;
+use std::fmt;
+use thiserror::Error;
+
+use super::{params, Transaction};
+
+/// A row referring to a resource that does not exist.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Violation {
+    pub resource_type: String,
+    pub resource_id: String,
+    pub missing_type: String,
+    pub missing_id: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} '{}' refers to {} '{}' which does not exist",
+            &self.resource_type, &self.resource_id, &self.missing_type, &self.missing_id
+        )
+    }
+}
+
+/// The set of violations found in a transaction.
+#[derive(Debug, Clone, Error)]
+pub struct IntegrityError {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} broken reference(s):", self.violations.len())?;
+
+        for violation in &self.violations {
+            write!(f, "\n  - {}", violation)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks every foreign key in the transaction, failing with an [`IntegrityError`] if any is broken.
+pub fn check_integrity(tx: &Transaction) -> Result<()> {
+    let mut violations = Vec::new();
+    let mut stmt = tx.prepare("PRAGMA foreign_key_check;")?;
+    let mut rows = stmt.query(params![])?;
+
+    while let Some(row) = rows.next()? {
+        let table: String = row.get(0)?;
+        let rowid: i64 = row.get(1)?;
+        let parent: String = row.get(2)?;
+        let fkid: i64 = row.get(3)?;
+
+        violations.push(describe(tx, &table, rowid, &parent, fkid)?);
+    }
+
+    // The pragma order depends on the table layout.
+    violations.sort();
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(IntegrityError { violations }.into())
+    }
+}
+
+fn describe(
+    tx: &Transaction,
+    table: &str,
+    rowid: i64,
+    parent: &str,
+    fkid: i64,
+) -> Result<Violation> {
+    let column: String = tx.query_row(
+        &format!(
+            "SELECT \"from\" FROM pragma_foreign_key_list('{}') WHERE id = ?;",
+            table
+        ),
+        params![fkid],
+        |row| row.get(0),
+    )?;
+    let (resource_type, id_column) = identify(table);
+    let (resource_id, missing_id): (String, String) = tx.query_row(
+        &format!(
+            "SELECT {}, {} FROM {} WHERE rowid = ?;",
+            id_column, column, table
+        ),
+        params![rowid],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(Violation {
+        resource_type: resource_type.to_string(),
+        resource_id,
+        missing_type: parent.to_string(),
+        missing_id,
+    })
+}
+
+/// The resource type and the column identifying a row for the given table.
+///
+/// Auxiliary tables are reported as the resource they belong to.
+fn identify(table: &str) -> (&str, &str) {
+    match table {
+        "sketch_tool" | "sketch_tag" => ("sketch", "sketch_id"),
+        "note_tag" => ("note", "note_id"),
+        "project_tag" => ("project", "project_id"),
+        "bulletin_entry" => ("bulletin_entry", "url"),
+        "bulletin_mention" | "bulletin_entry_author" | "bulletin_entry_tag" => {
+            ("bulletin_entry", "entry_url")
+        }
+        table => (table, "id"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::records::{AuxRecord, NoteRecord, Record, SketchToolRecord};
+    use crate::cache::Cache;
+
+    #[test]
+    fn report_missing_references() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        let note = NoteRecord {
+            id: "a-note".to_string(),
+            checksum: "x".to_string(),
+            title: "A note".to_string(),
+            summary: "A summary.".to_string(),
+            publication_date: "2021-07-07".to_string(),
+            author_id: "bob".to_string(),
+            body: "A body.".to_string(),
+            draft: false,
+        };
+        note.insert(&tx)?;
+        let sketch_tool = SketchToolRecord {
+            sketch_id: "calm-dragon".to_string(),
+            tool_id: "pencil".to_string(),
+        };
+        sketch_tool.insert(&tx)?;
+
+        let err = check_integrity(&tx).expect_err("integrity to be broken");
+        let err = err
+            .downcast::<IntegrityError>()
+            .expect("an integrity error");
+
+        assert_eq!(
+            err.violations[0].to_string(),
+            "note 'a-note' refers to person 'bob' which does not exist"
+        );
+        assert_eq!(err.violations.len(), 3);
+        assert!(err.to_string().starts_with("3 broken reference(s):\n"));
+        assert!(tx.commit().is_err());
+
+        Ok(())
+    }
+}
@@ -1,15 +1,36 @@
 use anyhow::{self, Result};
 use chrono::{DateTime, Utc};
+use log::info;
 use rusqlite::{self, Connection};
 pub use rusqlite::{params, Row, Transaction};
 use std::str::FromStr;
 
-use crate::Resource;
+use crate::{Publication, Resource};
 
+mod integrity;
 pub mod records;
+mod schema;
 mod strategy;
+pub use integrity::{check_integrity, IntegrityError, Violation};
+pub use schema::{SchemaError, SCHEMA_VERSION};
 pub use strategy::Strategy;
 
+/// The resource types recorded in the session trail paired with the table they are stored in.
+const TRAILED_TABLES: [(&str, &str); 12] = [
+    ("asset", "asset"),
+    ("bulletin", "bulletin_issue"),
+    ("bulletin_entry", "bulletin_entry"),
+    ("entrance", "entrance"),
+    ("note", "note"),
+    ("person", "person"),
+    ("project", "project"),
+    ("section", "section"),
+    ("service_account", "service_account"),
+    ("settings", "settings"),
+    ("sketch", "sketch"),
+    ("tool", "tool"),
+];
+
 /// A Cache storage.
 #[derive(Debug)]
 pub struct Cache {
@@ -21,26 +42,53 @@ pub struct Cache {
 impl Cache {
     pub fn connect_with_strategy(strategy: Strategy) -> Result<Cache> {
         let timestamp = Utc::now();
-        let conn = match &strategy {
+        let mut conn = match &strategy {
             Strategy::Disk(path) => {
                 let conn = Connection::open(path)?;
-                conn.pragma_update(None, "journal_mode", &"wal")?;
+                conn.pragma_update(None, "journal_mode", "wal")?;
                 conn
             }
             Strategy::Memory => Connection::open_in_memory()?,
         };
-        // TODO 2021-07: enable to check referential integrity.
-        conn.pragma_update(None, "foreign_keys", &"off")?;
-
-        let bootstrap = include_str!("../sql/cache.sql");
+        conn.pragma_update(None, "foreign_keys", "on")?;
 
-        conn.execute_batch(bootstrap)?;
+        schema::setup(&mut conn, &strategy.to_string())?;
+        conn.execute(
+            "INSERT INTO temp.session VALUES (?);",
+            params![timestamp.to_rfc3339()],
+        )?;
 
-        Ok(Cache {
+        let cache = Cache {
             timestamp,
             conn,
             strategy,
-        })
+        };
+        cache.publish(Publication::default())?;
+
+        Ok(cache)
+    }
+
+    /// Sets what the output stages see for the rest of the session.
+    pub fn publish(&self, publication: Publication) -> Result<()> {
+        self.conn.execute("DELETE FROM temp.publication;", [])?;
+        self.conn.execute(
+            "INSERT INTO temp.publication VALUES (?, ?);",
+            params![publication.as_of.to_string(), publication.drafts],
+        )?;
+
+        Ok(())
+    }
+
+    /// Starts a new session on the same connection so the next prune only keeps what gets touched from now on.
+    pub fn renew(&mut self) -> Result<()> {
+        let timestamp = Utc::now();
+        self.conn.execute(
+            "UPDATE temp.session SET timestamp = ?;",
+            params![timestamp.to_rfc3339()],
+        )?;
+        self.timestamp = timestamp;
+
+        Ok(())
     }
 
     pub fn connect(path: &str) -> Result<Cache> {
@@ -50,23 +98,103 @@ impl Cache {
 
     pub fn disconnect(&self) -> Result<()> {
         if let Strategy::Disk(_) = self.strategy {
-            self.conn
-                .pragma_update(None, "wal_checkpoint", &"restart")?;
-            self.conn.pragma_update(None, "journal_mode", &"delete")?;
+            self.conn.pragma_update(None, "wal_checkpoint", "restart")?;
+            self.conn.pragma_update(None, "journal_mode", "delete")?;
         }
 
         Ok(())
     }
 
-    pub fn transaction(&mut self) -> Result<Transaction> {
+    /// Starts a transaction where foreign keys are only enforced on commit.
+    ///
+    /// Use [`check_integrity`] before committing to get a readable report of any violation.
+    pub fn transaction(&mut self) -> Result<Transaction<'_>> {
         let tx = self.conn.transaction()?;
+        tx.pragma_update(None, "defer_foreign_keys", "on")?;
 
         Ok(tx)
     }
 
     /// Remove all stale records for the given session.
+    ///
+    /// A record is stale when its checksum was not added to the session trail during the current session. Auxiliary
+    /// records without a checksum are removed when their resource is gone.
     pub fn prune(&mut self) -> Result<()> {
-        unimplemented!();
+        let timestamp = self.timestamp.to_rfc3339();
+        let tx = self.transaction()?;
+
+        for (resource_type, table) in &TRAILED_TABLES {
+            let query = format!(
+                r#"
+                DELETE FROM
+                    {}
+                WHERE
+                    checksum NOT IN (
+                        SELECT
+                            resource_checksum
+                        FROM
+                            session_trail
+                        WHERE
+                            resource_type = ?
+                        AND
+                            timestamp = ?
+                    );
+                "#,
+                table
+            );
+            let count = tx.execute(&query, params![resource_type, &timestamp])?;
+
+            if count > 0 {
+                info!("prune({}): {} stale records", resource_type, count);
+            }
+        }
+
+        tx.execute_batch(
+            r#"
+            DELETE FROM
+                sketch_tool
+            WHERE
+                sketch_id NOT IN (SELECT id FROM sketch);
+
+            DELETE FROM
+                sketch_tag
+            WHERE
+                sketch_id NOT IN (SELECT id FROM sketch);
+
+            DELETE FROM
+                note_tag
+            WHERE
+                note_id NOT IN (SELECT id FROM note);
+
+            DELETE FROM
+                project_tag
+            WHERE
+                project_id NOT IN (SELECT id FROM project);
+
+            DELETE FROM
+                bulletin_mention
+            WHERE
+                entry_url NOT IN (SELECT url FROM bulletin_entry);
+
+            DELETE FROM
+                bulletin_entry_author
+            WHERE
+                entry_url NOT IN (SELECT url FROM bulletin_entry);
+
+            DELETE FROM
+                bulletin_entry_tag
+            WHERE
+                entry_url NOT IN (SELECT url FROM bulletin_entry);
+            "#,
+        )?;
+        tx.execute(
+            "DELETE FROM session_trail WHERE timestamp != ?;",
+            params![&timestamp],
+        )?;
+        check_integrity(&tx)?;
+        tx.commit()?;
+
+        Ok(())
     }
 }
 
@@ -93,5 +221,120 @@ where
 
     fn remove(tx: &Transaction, id: &str) -> Result<()>;
 
+    /// Records the resource in the session trail when the cache already holds it unchanged.
+    ///
+    /// Returns `false` when the resource is new or has changed, in which case it must be added.
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool>;
+
     // fn bulk(&self, tx: &Transaction) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{Note, NoteSet, Person, PersonSet};
+    use temp_testdir::TempDir;
+
+    fn note(id: &str) -> Result<Note> {
+        let raw = format!(
+            r#"---
+type: note
+id: {}
+publication_date: 2021-07-07
+author: arnau
+---
+# A note
+
+A summary.
+
+<!-- body -->
+
+A body."#,
+            id
+        );
+
+        Note::from_str(&raw)
+    }
+
+    fn person() -> Result<Person> {
+        let raw = r#"type = "person"
+id = "arnau"
+name = "Arnau Siches"
+guest = false
+accounts = []
+"#;
+
+        Person::from_str(raw)
+    }
+
+    #[test]
+    fn prune_unseen() -> Result<()> {
+        let dir = TempDir::default();
+        let path = dir.join("cache.db");
+
+        let mut cache = Cache::connect_with_strategy(Strategy::Disk(path.clone()))?;
+        let tx = cache.transaction()?;
+        PersonSet::add(&tx, person()?)?;
+        NoteSet::add(&tx, note("first")?)?;
+        NoteSet::add(&tx, note("second")?)?;
+        tx.commit()?;
+        cache.prune()?;
+        cache.disconnect()?;
+
+        let mut cache = Cache::connect_with_strategy(Strategy::Disk(path))?;
+        let tx = cache.transaction()?;
+        PersonSet::add(&tx, person()?)?;
+        NoteSet::add(&tx, note("first")?)?;
+        tx.commit()?;
+        cache.prune()?;
+
+        let tx = cache.transaction()?;
+        let notes = NoteSet::amass(&tx)?;
+        let trail: usize =
+            tx.query_row("SELECT count(*) FROM session_trail", [], |row| row.get(0))?;
+        tx.commit()?;
+
+        assert_eq!(
+            notes
+                .into_iter()
+                .map(|n| n.id().clone())
+                .collect::<Vec<_>>(),
+            vec!["first"]
+        );
+        assert_eq!(trail, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn prune_renewed() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        PersonSet::add(&tx, person()?)?;
+        NoteSet::add(&tx, note("first")?)?;
+        NoteSet::add(&tx, note("second")?)?;
+        tx.commit()?;
+        cache.prune()?;
+
+        cache.renew()?;
+        let tx = cache.transaction()?;
+        PersonSet::add(&tx, person()?)?;
+        NoteSet::add(&tx, note("second")?)?;
+        tx.commit()?;
+        cache.prune()?;
+
+        let tx = cache.transaction()?;
+        let notes = NoteSet::amass(&tx)?;
+        tx.commit()?;
+
+        assert_eq!(
+            notes
+                .into_iter()
+                .map(|n| n.id().clone())
+                .collect::<Vec<_>>(),
+            vec!["second"]
+        );
+
+        Ok(())
+    }
+}
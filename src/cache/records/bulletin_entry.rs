@@ -14,6 +14,7 @@ pub struct BulletinEntryRecord {
     pub(crate) summary: String,
     pub(crate) content_type: String,
     pub(crate) issue_id: Option<String>,
+    pub(crate) duration: Option<String>,
 }
 
 impl BulletinEntryRecord {
@@ -49,13 +50,14 @@ impl AuxRecord for BulletinEntryRecord {
             &self.summary,
             &self.content_type,
             &self.issue_id,
+            &self.duration,
         ];
         let mut stmt = tx.prepare(
             r#"
             INSERT OR REPLACE INTO
                 bulletin_entry
             VALUES
-                (?, ?, ?, ?, ?, ?);
+                (?, ?, ?, ?, ?, ?, ?);
             "#,
         )?;
 
@@ -76,6 +78,7 @@ impl TryFrom<&Row<'_>> for BulletinEntryRecord {
             summary: row.get(3)?,
             content_type: row.get(4)?,
             issue_id: row.get(5)?,
+            duration: row.get(6)?,
         };
 
         Ok(record)
@@ -138,6 +141,7 @@ impl AuxRecordSet for BulletinEntryRecordSet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::records::{BulletinRecord, Record};
     use crate::cache::Cache;
 
     #[test]
@@ -149,6 +153,7 @@ mod tests {
             summary: "".into(),
             content_type: "".into(),
             issue_id: None,
+            duration: None,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
@@ -173,6 +178,7 @@ mod tests {
             summary: "".into(),
             content_type: "".into(),
             issue_id: None,
+            duration: None,
         };
         let record2 = BulletinEntryRecord {
             url: "entry2".into(),
@@ -181,12 +187,21 @@ mod tests {
             summary: "".into(),
             content_type: "".into(),
             issue_id: Some("bulletin1".into()),
+            duration: Some("1:02:30".into()),
+        };
+        let issue = BulletinRecord {
+            id: "bulletin1".into(),
+            checksum: "bulletin1".into(),
+            summary: "".into(),
+            publication_date: "2021-01-10".into(),
+            draft: false,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
 
         record1.insert(&tx)?;
         record2.insert(&tx)?;
+        issue.insert(&tx)?;
 
         let cached = BulletinEntryRecordSet::select(&tx, None)?;
 
@@ -0,0 +1,109 @@
+//! This module defines the bulletin entry author for the SQLite storage.
+
+use anyhow::Result;
+use std::convert::TryFrom;
+
+use super::{AuxRecord, AuxRecordSet};
+use crate::cache::{params, Row, Transaction};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulletinEntryAuthorRecord {
+    pub(crate) entry_url: String,
+    pub(crate) name: String,
+}
+
+impl AuxRecord for BulletinEntryAuthorRecord {
+    fn insert(&self, tx: &Transaction) -> Result<()> {
+        let values = params![&self.entry_url, &self.name];
+        let mut stmt = tx.prepare(
+            r#"
+            INSERT OR REPLACE INTO
+                bulletin_entry_author
+            VALUES
+                (?, ?);
+            "#,
+        )?;
+
+        stmt.execute(values)?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&Row<'_>> for BulletinEntryAuthorRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(row: &Row) -> Result<Self> {
+        let record = Self {
+            entry_url: row.get(0)?,
+            name: row.get(1)?,
+        };
+
+        Ok(record)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BulletinEntryAuthorRecordSet {
+    inner: Vec<BulletinEntryAuthorRecord>,
+}
+
+impl BulletinEntryAuthorRecordSet {
+    /// Deletes all authors for the given bulletin entry.
+    pub fn delete(tx: &Transaction, entry_url: &str) -> Result<()> {
+        let mut stmt = tx.prepare(
+            r#"
+            DELETE FROM
+                bulletin_entry_author
+            WHERE
+                entry_url = ?;
+            "#,
+        )?;
+
+        stmt.execute(params![entry_url])?;
+
+        Ok(())
+    }
+}
+
+impl IntoIterator for BulletinEntryAuthorRecordSet {
+    type Item = BulletinEntryAuthorRecord;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl AuxRecordSet for BulletinEntryAuthorRecordSet {
+    type Item = BulletinEntryAuthorRecord;
+    type ResourceId = String;
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn select(tx: &Transaction, id: Self::ResourceId) -> Result<Self> {
+        let mut inner = Vec::new();
+        let mut stmt = tx.prepare(
+            r#"
+            SELECT
+                *
+            FROM
+                bulletin_entry_author
+            WHERE
+                entry_url = ?
+            ORDER BY
+                rowid;
+            "#,
+        )?;
+        let mut rows = stmt.query(params![id])?;
+
+        while let Some(row) = rows.next()? {
+            let record = Self::Item::try_from(row)?;
+            inner.push(record);
+        }
+
+        Ok(Self { inner })
+    }
+}
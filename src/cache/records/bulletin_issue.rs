@@ -12,6 +12,7 @@ pub struct BulletinRecord {
     pub(crate) checksum: String,
     pub(crate) summary: String,
     pub(crate) publication_date: String,
+    pub(crate) draft: bool,
 }
 
 impl Record for BulletinRecord {
@@ -43,13 +44,14 @@ impl Record for BulletinRecord {
             &self.checksum,
             &self.summary,
             &self.publication_date,
+            &self.draft,
         ];
         let mut stmt = tx.prepare(
             r#"
             INSERT OR REPLACE INTO
                 bulletin_issue
             VALUES
-                (?, ?, ?, ?);
+                (?, ?, ?, ?, ?);
             "#,
         )?;
 
@@ -83,6 +85,7 @@ impl TryFrom<&Row<'_>> for BulletinRecord {
             checksum: row.get(1)?,
             summary: row.get(2)?,
             publication_date: row.get(3)?,
+            draft: row.get(4)?,
         };
 
         Ok(record)
@@ -171,6 +174,7 @@ mod tests {
             checksum: "bulletin1".into(),
             summary: "".into(),
             publication_date: "2021-02-03".into(),
+            draft: false,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
@@ -199,12 +203,14 @@ mod tests {
             checksum: "bulletin1".into(),
             summary: "".into(),
             publication_date: "2021-02-03".into(),
+            draft: false,
         };
         let record2 = BulletinRecord {
             id: "bulletin2".into(),
             checksum: "bulletin2".into(),
             summary: "".into(),
             publication_date: "2021-02-03".into(),
+            draft: false,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
@@ -48,6 +48,24 @@ pub struct BulletinMentionRecordSet {
     inner: Vec<BulletinMentionRecord>,
 }
 
+impl BulletinMentionRecordSet {
+    /// Deletes all mentions for the given bulletin entry.
+    pub fn delete(tx: &Transaction, entry_url: &str) -> Result<()> {
+        let mut stmt = tx.prepare(
+            r#"
+            DELETE FROM
+                bulletin_mention
+            WHERE
+                entry_url = ?;
+            "#,
+        )?;
+
+        stmt.execute(params![entry_url])?;
+
+        Ok(())
+    }
+}
+
 impl IntoIterator for BulletinMentionRecordSet {
     type Item = BulletinMentionRecord;
     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -74,7 +92,9 @@ impl AuxRecordSet for BulletinMentionRecordSet {
             FROM
                 bulletin_mention
             WHERE
-                entry_url = ?;
+                entry_url = ?
+            ORDER BY
+                rowid;
             "#,
         )?;
         let mut rows = stmt.query(params![id])?;
@@ -91,6 +111,7 @@ impl AuxRecordSet for BulletinMentionRecordSet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::records::BulletinEntryRecord;
     use crate::cache::Cache;
 
     #[test]
@@ -103,11 +124,21 @@ mod tests {
             mention_url: "mention2".into(),
             entry_url: "entry1".into(),
         };
+        let entry = BulletinEntryRecord {
+            url: "entry1".into(),
+            checksum: "entry1".into(),
+            title: "".into(),
+            summary: "".into(),
+            content_type: "".into(),
+            issue_id: None,
+            duration: None,
+        };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
 
         record1.insert(&tx)?;
         record2.insert(&tx)?;
+        entry.insert(&tx)?;
 
         let cached = BulletinMentionRecordSet::select(&tx, "entry1".to_string())?;
 
@@ -2,6 +2,7 @@ use anyhow::Result;
 
 mod asset;
 mod bulletin_entry;
+mod bulletin_entry_author;
 mod bulletin_issue;
 mod bulletin_mention;
 mod entrance;
@@ -10,14 +11,17 @@ mod person;
 mod project;
 mod section;
 mod service_account;
+mod session_trail;
 mod settings;
 mod sketch;
 mod sketch_tool;
+mod tag;
 mod tool;
 mod update;
 
 pub use asset::*;
 pub use bulletin_entry::*;
+pub use bulletin_entry_author::*;
 pub use bulletin_issue::*;
 pub use bulletin_mention::*;
 pub use entrance::*;
@@ -26,9 +30,11 @@ pub use person::*;
 pub use project::*;
 pub use section::*;
 pub use service_account::*;
+pub use session_trail::*;
 pub use settings::*;
 pub use sketch::*;
 pub use sketch_tool::*;
+pub use tag::*;
 pub use tool::*;
 pub use update::*;
 
@@ -15,6 +15,7 @@ pub struct NoteRecord {
     pub(crate) publication_date: String,
     pub(crate) author_id: String,
     pub(crate) body: String,
+    pub(crate) draft: bool,
 }
 
 impl Record for NoteRecord {
@@ -49,13 +50,14 @@ impl Record for NoteRecord {
             &self.publication_date,
             &self.author_id,
             &self.body,
+            &self.draft,
         ];
         let mut stmt = tx.prepare(
             r#"
               INSERT OR REPLACE INTO
                 note
               VALUES
-                (?, ?, ?, ?, ?, ?, ?);
+                (?, ?, ?, ?, ?, ?, ?, ?);
             "#,
         )?;
 
@@ -92,6 +94,7 @@ impl TryFrom<&Row<'_>> for NoteRecord {
             publication_date: row.get(4)?,
             author_id: row.get(5)?,
             body: row.get(6)?,
+            draft: row.get(7)?,
         };
 
         Ok(record)
@@ -183,6 +186,7 @@ mod tests {
             publication_date: "2021-07-09".into(),
             author_id: "bobsponge".into(),
             body: "A long note that _turns out_ to be…\n\n**quite short!**.".into(),
+            draft: false,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
@@ -214,6 +218,7 @@ mod tests {
             publication_date: "2021-07-09".into(),
             author_id: "bobsponge".into(),
             body: "".into(),
+            draft: false,
         };
         let record2 = NoteRecord {
             id: "note2".into(),
@@ -223,6 +228,7 @@ mod tests {
             publication_date: "2021-07-09".into(),
             author_id: "bobsponge".into(),
             body: "".into(),
+            draft: false,
         };
 
         let mut cache = Cache::connect(":memory:")?;
@@ -17,6 +17,7 @@ pub struct ProjectRecord {
     pub(crate) start_date: String,
     pub(crate) end_date: Option<String>,
     pub(crate) source_url: Option<String>,
+    pub(crate) draft: bool,
 }
 
 impl Record for ProjectRecord {
@@ -53,13 +54,14 @@ impl Record for ProjectRecord {
             &self.start_date,
             &self.end_date,
             &self.source_url,
+            &self.draft,
         ];
         let mut stmt = tx.prepare(
             r#"
               INSERT OR REPLACE INTO
                 project
               VALUES
-                (?, ?, ?, ?, ?, ?, ?, ?, ?);
+                (?, ?, ?, ?, ?, ?, ?, ?, ?, ?);
             "#,
         )?;
 
@@ -98,6 +100,7 @@ impl TryFrom<&Row<'_>> for ProjectRecord {
             start_date: row.get(6)?,
             end_date: row.get(7)?,
             source_url: row.get(8)?,
+            draft: row.get(9)?,
         };
 
         Ok(record)
@@ -191,6 +194,7 @@ mod tests {
             start_date: "2021-07-09".into(),
             end_date: None,
             source_url: Some("https://foo.bar".into()),
+            draft: false,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
@@ -224,6 +228,7 @@ mod tests {
             start_date: "2021-07-09".into(),
             end_date: None,
             source_url: None,
+            draft: false,
         };
         let record2 = ProjectRecord {
             id: "project2".into(),
@@ -235,6 +240,7 @@ mod tests {
             start_date: "2020-01-01".into(),
             end_date: Some("2021-02-03".into()),
             source_url: None,
+            draft: false,
         };
 
         let mut cache = Cache::connect(":memory:")?;
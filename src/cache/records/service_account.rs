@@ -106,6 +106,7 @@ impl AuxRecordSet for ServiceAccountRecordSet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::records::{PersonRecord, Record};
     use crate::cache::Cache;
 
     #[test]
@@ -126,11 +127,18 @@ mod tests {
             username: "".into(),
             url: "".into(),
         };
+        let person = PersonRecord {
+            id: "person1".into(),
+            checksum: "person1".into(),
+            name: "".into(),
+            guest: false,
+        };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
 
         record1.insert(&tx)?;
         record2.insert(&tx)?;
+        person.insert(&tx)?;
 
         let cached = ServiceAccountRecordSet::select(&tx, "person1".to_string())?;
 
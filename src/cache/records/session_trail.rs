@@ -0,0 +1,69 @@
+//! This module defines the session trail record for the SQLite storage.
+
+use anyhow::Result;
+
+use super::AuxRecord;
+use crate::cache::{params, Transaction};
+
+/// A trace of a resource seen during the current session.
+///
+/// The timestamp is taken from the session the connection was open with. See [`crate::Cache::prune`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionTrailRecord {
+    pub(crate) resource_checksum: String,
+    pub(crate) resource_type: String,
+}
+
+impl SessionTrailRecord {
+    pub fn new(resource_checksum: &str, resource_type: &str) -> Self {
+        Self {
+            resource_checksum: resource_checksum.to_string(),
+            resource_type: resource_type.to_string(),
+        }
+    }
+}
+
+impl AuxRecord for SessionTrailRecord {
+    fn insert(&self, tx: &Transaction) -> Result<()> {
+        let values = params![&self.resource_checksum, &self.resource_type];
+        let mut stmt = tx.prepare(
+            r#"
+            INSERT OR IGNORE INTO
+                session_trail
+            SELECT
+                ?, ?, timestamp
+            FROM
+                temp.session;
+            "#,
+        )?;
+
+        stmt.execute(values)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+
+    #[test]
+    fn insert_once() -> Result<()> {
+        let record = SessionTrailRecord::new("checksum1", "note");
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+
+        record.insert(&tx)?;
+        record.insert(&tx)?;
+
+        let count: usize =
+            tx.query_row("SELECT count(*) FROM session_trail", [], |row| row.get(0))?;
+
+        tx.commit()?;
+
+        assert_eq!(count, 1);
+
+        Ok(())
+    }
+}
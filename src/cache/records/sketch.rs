@@ -15,6 +15,7 @@ pub struct SketchRecord {
     pub(crate) author_id: String,
     pub(crate) publication_date: String,
     pub(crate) summary: Option<String>,
+    pub(crate) draft: bool,
 }
 
 impl Record for SketchRecord {
@@ -49,13 +50,14 @@ impl Record for SketchRecord {
             &self.author_id,
             &self.publication_date,
             &self.summary,
+            &self.draft,
         ];
         let mut stmt = tx.prepare(
             r#"
             INSERT OR REPLACE INTO
                 sketch
             VALUES
-                (?, ?, ?, ?, ?, ?, ?);
+                (?, ?, ?, ?, ?, ?, ?, ?);
             "#,
         )?;
 
@@ -92,6 +94,7 @@ impl TryFrom<&Row<'_>> for SketchRecord {
             author_id: row.get(4)?,
             publication_date: row.get(5)?,
             summary: row.get(6)?,
+            draft: row.get(7)?,
         };
 
         Ok(record)
@@ -183,6 +186,7 @@ mod tests {
             author_id: "".into(),
             publication_date: "2021-02-03".into(),
             summary: None,
+            draft: false,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
@@ -214,6 +218,7 @@ mod tests {
             author_id: "".into(),
             publication_date: "2021-02-03".into(),
             summary: None,
+            draft: false,
         };
         let record2 = SketchRecord {
             id: "sketch2".into(),
@@ -223,6 +228,7 @@ mod tests {
             author_id: "".into(),
             publication_date: "2021-02-03".into(),
             summary: Some("".into()),
+            draft: false,
         };
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
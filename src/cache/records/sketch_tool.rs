@@ -48,6 +48,24 @@ pub struct SketchToolRecordSet {
     inner: Vec<SketchToolRecord>,
 }
 
+impl SketchToolRecordSet {
+    /// Deletes all tools for the given sketch.
+    pub fn delete(tx: &Transaction, sketch_id: &str) -> Result<()> {
+        let mut stmt = tx.prepare(
+            r#"
+            DELETE FROM
+                sketch_tool
+            WHERE
+                sketch_id = ?;
+            "#,
+        )?;
+
+        stmt.execute(params![sketch_id])?;
+
+        Ok(())
+    }
+}
+
 impl IntoIterator for SketchToolRecordSet {
     type Item = SketchToolRecord;
     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -91,8 +109,50 @@ impl AuxRecordSet for SketchToolRecordSet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::records::{AssetRecord, PersonRecord, Record, SketchRecord, ToolRecord};
     use crate::cache::Cache;
 
+    fn insert_parents(tx: &Transaction) -> Result<()> {
+        for id in &["tool1", "tool2"] {
+            ToolRecord {
+                id: id.to_string(),
+                checksum: id.to_string(),
+                name: "".into(),
+                summary: None,
+                url: None,
+            }
+            .insert(tx)?;
+        }
+
+        PersonRecord {
+            id: "person1".into(),
+            checksum: "person1".into(),
+            name: "".into(),
+            guest: false,
+        }
+        .insert(tx)?;
+        AssetRecord {
+            id: "asset1".into(),
+            checksum: "asset1".into(),
+            content_type: "png".into(),
+            content: vec![],
+        }
+        .insert(tx)?;
+        SketchRecord {
+            id: "sketch1".into(),
+            checksum: "sketch1".into(),
+            title: "".into(),
+            asset_id: "asset1".into(),
+            author_id: "person1".into(),
+            publication_date: "2021-01-10".into(),
+            summary: None,
+            draft: false,
+        }
+        .insert(tx)?;
+
+        Ok(())
+    }
+
     #[test]
     fn set_full_cycle() -> Result<()> {
         let record1 = SketchToolRecord {
@@ -108,6 +168,7 @@ mod tests {
 
         record1.insert(&tx)?;
         record2.insert(&tx)?;
+        insert_parents(&tx)?;
 
         let cached = SketchToolRecordSet::select(&tx, "sketch1".to_string())?;
 
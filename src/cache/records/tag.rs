@@ -0,0 +1,195 @@
+//! This module defines the tag for the SQLite storage.
+//!
+//! Each resource type keeps its tags in a table of its own, e.g. `note_tag`, holding the resource id and the tag.
+
+use anyhow::Result;
+
+use super::{AuxRecord, AuxRecordSet};
+use crate::cache::{params, Transaction};
+
+/// The table holding the tags for a resource type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TagTable {
+    Note,
+    Sketch,
+    Project,
+    BulletinEntry,
+}
+
+impl TagTable {
+    pub fn table(&self) -> &'static str {
+        match self {
+            TagTable::Note => "note_tag",
+            TagTable::Sketch => "sketch_tag",
+            TagTable::Project => "project_tag",
+            TagTable::BulletinEntry => "bulletin_entry_tag",
+        }
+    }
+
+    /// The column holding the id of the tagged resource.
+    pub fn column(&self) -> &'static str {
+        match self {
+            TagTable::Note => "note_id",
+            TagTable::Sketch => "sketch_id",
+            TagTable::Project => "project_id",
+            TagTable::BulletinEntry => "entry_url",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagRecord {
+    pub(crate) table: TagTable,
+    pub(crate) resource_id: String,
+    pub(crate) tag: String,
+}
+
+impl AuxRecord for TagRecord {
+    fn insert(&self, tx: &Transaction) -> Result<()> {
+        let values = params![&self.resource_id, &self.tag];
+        let mut stmt = tx.prepare(&format!(
+            r#"
+            INSERT OR REPLACE INTO
+                {}
+            VALUES
+                (?, ?);
+            "#,
+            self.table.table()
+        ))?;
+
+        stmt.execute(values)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TagRecordSet {
+    inner: Vec<TagRecord>,
+}
+
+impl TagRecordSet {
+    /// Deletes all tags for the given resource.
+    pub fn delete(tx: &Transaction, table: TagTable, resource_id: &str) -> Result<()> {
+        let mut stmt = tx.prepare(&format!(
+            r#"
+            DELETE FROM
+                {}
+            WHERE
+                {} = ?;
+            "#,
+            table.table(),
+            table.column()
+        ))?;
+
+        stmt.execute(params![resource_id])?;
+
+        Ok(())
+    }
+}
+
+impl IntoIterator for TagRecordSet {
+    type Item = TagRecord;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl AuxRecordSet for TagRecordSet {
+    type Item = TagRecord;
+    type ResourceId = (TagTable, String);
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn select(tx: &Transaction, (table, id): Self::ResourceId) -> Result<Self> {
+        let mut inner = Vec::new();
+        let mut stmt = tx.prepare(&format!(
+            r#"
+            SELECT
+                {column},
+                tag
+            FROM
+                {table}
+            WHERE
+                {column} = ?
+            ORDER BY
+                rowid;
+            "#,
+            table = table.table(),
+            column = table.column()
+        ))?;
+        let mut rows = stmt.query(params![id])?;
+
+        while let Some(row) = rows.next()? {
+            inner.push(TagRecord {
+                table,
+                resource_id: row.get(0)?,
+                tag: row.get(1)?,
+            });
+        }
+
+        Ok(Self { inner })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::records::{NoteRecord, PersonRecord, Record};
+    use crate::cache::Cache;
+
+    #[test]
+    fn set_full_cycle() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+
+        PersonRecord {
+            id: "person1".into(),
+            checksum: "person1".into(),
+            name: "".into(),
+            guest: false,
+        }
+        .insert(&tx)?;
+        NoteRecord {
+            id: "note1".into(),
+            checksum: "note1".into(),
+            title: "".into(),
+            summary: "".into(),
+            publication_date: "2021-01-10".into(),
+            author_id: "person1".into(),
+            body: "".into(),
+            draft: false,
+        }
+        .insert(&tx)?;
+
+        for tag in &["rdf", "csv"] {
+            TagRecord {
+                table: TagTable::Note,
+                resource_id: "note1".into(),
+                tag: tag.to_string(),
+            }
+            .insert(&tx)?;
+        }
+
+        let key = (TagTable::Note, "note1".to_string());
+        let tags: Vec<String> = TagRecordSet::select(&tx, key.clone())?
+            .into_iter()
+            .map(|record| record.tag)
+            .collect();
+
+        assert_eq!(tags, vec!["rdf", "csv"]);
+        assert!(TagRecordSet::select(&tx, (TagTable::Sketch, "note1".to_string()))?.is_empty());
+
+        TagRecordSet::delete(&tx, TagTable::Note, "note1")?;
+
+        assert!(TagRecordSet::select(&tx, key)?.is_empty());
+
+        tx.commit()?;
+
+        Ok(())
+    }
+}
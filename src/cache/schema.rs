@@ -0,0 +1,291 @@
+//! This module keeps the cache schema up to date.
+//!
+//! The schema version is stored in the SQLite `user_version`. A fresh cache is bootstrapped straight into the latest
+//! version whereas an existing one is upgraded by applying every migration after its version, in order.
+
+use anyhow::Result;
+use rusqlite::Connection;
+use thiserror::Error;
+
+use super::check_integrity;
+
+/// The schema version `cache.sql` describes.
+pub const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+/// The migrations in order. The migration at index `n` upgrades a cache from version `n` to `n + 1`.
+///
+/// Version 0 is any cache created before the schema was versioned.
+const MIGRATIONS: [&str; 5] = [
+    include_str!("../sql/migrations/001.sql"),
+    include_str!("../sql/migrations/002.sql"),
+    include_str!("../sql/migrations/003.sql"),
+    include_str!("../sql/migrations/004.sql"),
+    include_str!("../sql/migrations/005.sql"),
+];
+
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("the cache at {location} has schema version {found} but this version of aquarium only knows up to {supported}. Remove the cache and rebuild")]
+    Unsupported {
+        location: String,
+        found: usize,
+        supported: usize,
+    },
+    #[error("the cache at {location} could not be upgraded from schema version {from} to {to}. Remove the cache and rebuild")]
+    Migration {
+        location: String,
+        from: usize,
+        to: usize,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+/// Brings the schema to [`SCHEMA_VERSION`] and runs the bootstrap for the session.
+///
+/// The location is only used to name the cache in errors.
+pub fn setup(conn: &mut Connection, location: &str) -> Result<()> {
+    let version = version(conn)?;
+
+    if version > SCHEMA_VERSION {
+        return Err(SchemaError::Unsupported {
+            location: location.to_string(),
+            found: version,
+            supported: SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    if is_empty(conn)? {
+        conn.execute_batch(include_str!("../sql/cache.sql"))?;
+        set_version(conn, SCHEMA_VERSION)?;
+
+        return Ok(());
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        migrate(conn, migration, from).map_err(|source| SchemaError::Migration {
+            location: location.to_string(),
+            from,
+            to: SCHEMA_VERSION,
+            source: source.into(),
+        })?;
+    }
+
+    conn.execute_batch(include_str!("../sql/cache.sql"))?;
+
+    Ok(())
+}
+
+/// Applies a single migration.
+///
+/// Foreign keys are switched off while tables are rebuilt, as SQLite recommends, and checked before committing so a
+/// broken reference is reported as an [`IntegrityError`](super::IntegrityError).
+fn migrate(conn: &mut Connection, migration: &str, from: usize) -> Result<()> {
+    conn.pragma_update(None, "foreign_keys", "off")?;
+
+    let result = (|| {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", from + 1)?;
+        check_integrity(&tx)?;
+        tx.commit()?;
+
+        Ok(())
+    })();
+
+    conn.pragma_update(None, "foreign_keys", "on")?;
+
+    result
+}
+
+fn version(conn: &Connection) -> Result<usize> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    Ok(version)
+}
+
+fn set_version(conn: &Connection, version: usize) -> Result<()> {
+    conn.pragma_update(None, "user_version", version)?;
+
+    Ok(())
+}
+
+fn is_empty(conn: &Connection) -> Result<bool> {
+    let count: usize = conn.query_row(
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table';",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(count == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The relevant subset of the tables an unversioned cache had.
+    ///
+    /// Foreign keys are off as the old `asset` key could not be referenced.
+    const UNVERSIONED: &str = r#"
+        PRAGMA foreign_keys = off;
+        CREATE TABLE asset (
+          id            text NOT NULL,
+          checksum      text NOT NULL,
+          content_type  text NOT NULL,
+          content       blob NOT NULL,
+
+          UNIQUE (id, content_type)
+        );
+        CREATE TABLE person (
+          id       text NOT NULL PRIMARY KEY,
+          checksum text NOT NULL,
+          name     text NOT NULL,
+          guest    boolean NOT NULL
+        );
+        CREATE TABLE service_account (
+          id        text NOT NULL,
+          person_id text NOT NULL,
+          checksum  text NOT NULL,
+          name      text NOT NULL,
+          username  text NOT NULL,
+          url       text NOT NULL,
+
+          UNIQUE (id, person_id),
+          FOREIGN KEY (person_id) REFERENCES person (id) ON DELETE CASCADE
+        );
+        CREATE TABLE sketch_tool (
+          sketch_id text NOT NULL,
+          tool_id   text NOT NULL,
+
+          UNIQUE (sketch_id, tool_id),
+          FOREIGN KEY (tool_id) REFERENCES tool (id) ON DELETE CASCADE,
+          FOREIGN KEY (sketch_id) REFERENCES sketch (id) ON DELETE CASCADE
+        );
+        CREATE TABLE sketch (
+          id               text NOT NULL PRIMARY KEY,
+          checksum         text NOT NULL,
+          title            text NOT NULL,
+          asset_id         text NOT NULL,
+          author_id        text NOT NULL,
+          publication_date date NOT NULL,
+          summary          text,
+
+          FOREIGN KEY (asset_id) REFERENCES asset (id) ON DELETE CASCADE
+        );
+        CREATE TABLE note (
+          id               text NOT NULL PRIMARY KEY,
+          checksum         text NOT NULL,
+          title            text NOT NULL,
+          summary          text NOT NULL,
+          publication_date date NOT NULL,
+          author_id        text NOT NULL,
+          body             text NOT NULL
+        );
+        CREATE TABLE project (
+          id         text NOT NULL PRIMARY KEY,
+          checksum   text NOT NULL,
+          name       text NOT NULL,
+          summary    text NOT NULL,
+          body       text NOT NULL,
+          status     text NOT NULL,
+          start_date date NOT NULL,
+          end_date   date,
+          source_url text
+        );
+        CREATE TABLE bulletin_issue (
+          id               text NOT NULL PRIMARY KEY,
+          checksum         text NOT NULL,
+          summary          text NOT NULL,
+          publication_date date NOT NULL
+        );
+        CREATE TABLE bulletin_entry (
+          url          text NOT NULL PRIMARY KEY,
+          checksum     text NOT NULL,
+          title        text NOT NULL,
+          summary      text NOT NULL,
+          content_type text NOT NULL,
+          issue_id     text
+        );
+        CREATE TABLE bulletin_mention (
+          mention_url text NOT NULL,
+          entry_url   text NOT NULL,
+
+          UNIQUE (mention_url, entry_url),
+          FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url) ON DELETE CASCADE
+        );
+        CREATE VIEW news AS SELECT id FROM sketch;
+
+        INSERT INTO asset VALUES ('calm-dragon.png', 'x', 'png', x'00');
+        INSERT INTO person VALUES ('arnau', 'x', 'Arnau Siches', false);
+        INSERT INTO sketch VALUES ('calm-dragon', 'x', 'Calm dragon', 'calm-dragon.png', 'arnau', '2017-09-29', NULL);
+    "#;
+
+    #[test]
+    fn bootstrap_fresh() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+
+        setup(&mut conn, ":memory:")?;
+
+        assert_eq!(version(&conn)?, SCHEMA_VERSION);
+
+        Ok(())
+    }
+
+    #[test]
+    fn upgrade_unversioned() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        conn.execute_batch(UNVERSIONED)?;
+
+        setup(&mut conn, ":memory:")?;
+
+        let sketches: usize =
+            conn.query_row("SELECT count(*) FROM sketch", [], |row| row.get(0))?;
+        let asset_pk: usize = conn.query_row(
+            "SELECT pk FROM pragma_table_info('asset') WHERE name = 'id'",
+            [],
+            |row| row.get(0),
+        )?;
+        let news: usize = conn.query_row("SELECT count(*) FROM news", [], |row| row.get(0))?;
+
+        assert_eq!(version(&conn)?, SCHEMA_VERSION);
+        assert_eq!(sketches, 1);
+        assert_eq!(asset_pk, 1);
+        assert_eq!(news, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_broken_references() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        conn.execute_batch(UNVERSIONED)?;
+        conn.execute_batch(
+            "INSERT INTO sketch VALUES ('lost', 'x', 'Lost', 'calm-dragon.png', 'nobody', '2017-09-29', NULL);",
+        )?;
+
+        let err = setup(&mut conn, "cache.db").expect_err("a broken reference to be reported");
+
+        assert!(format!("{:#}", err)
+            .contains("sketch 'lost' refers to person 'nobody' which does not exist"));
+        assert_eq!(version(&conn)?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_newer() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        set_version(&conn, SCHEMA_VERSION + 1)?;
+
+        let err = setup(&mut conn, "cache.db").expect_err("a newer cache to be rejected");
+
+        assert!(err.to_string().starts_with(&format!(
+            "the cache at cache.db has schema version {}",
+            SCHEMA_VERSION + 1
+        )));
+
+        Ok(())
+    }
+}
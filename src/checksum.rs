@@ -77,6 +77,13 @@ impl Digest for str {
     }
 }
 
+impl Digest for bool {
+    fn digest(&self, hasher: &mut Hasher) {
+        hasher.update(&Tag::Bool.to_bytes());
+        hasher.update(if *self { b"1" } else { b"0" });
+    }
+}
+
 impl Digest for u32 {
     fn digest(&self, hasher: &mut Hasher) {
         hasher.update(&Tag::Integer.to_bytes());
@@ -1,11 +1,17 @@
 use anyhow::Result;
 use clap::Parser;
+use log::info;
+use std::fs;
 use std::path::PathBuf;
 
 use crate::cache::{Cache, Strategy};
+use crate::datapackage;
 use crate::feed;
+use crate::markdown;
 use crate::source;
+use crate::stamp::Date;
 use crate::zola;
+use crate::{Mode, Publication};
 
 /// Manages bulletins
 #[derive(Debug, Parser)]
@@ -19,15 +25,91 @@ pub struct Cmd {
     /// The path to the sink to build into.
     #[clap(long, short = 'o', value_name = "path")]
     output_path: PathBuf,
+    /// The feed formats to write into `static`.
+    #[clap(
+        long = "feed",
+        value_name = "format",
+        value_delimiter = ',',
+        default_value = "rss,atom,json"
+    )]
+    feed_formats: Vec<feed::Format>,
+    /// The path to write the data package into. Defaults to `static/datapackage` in the output path.
+    #[clap(long, value_name = "path")]
+    datapackage_path: Option<PathBuf>,
+    /// Skips unchanged resources and only rewrites the files that changed. Best used with a disk cache.
+    #[clap(long)]
+    incremental: bool,
+    /// How to treat a bulletin entry found in more than one bulletin or stash.
+    #[clap(long, value_name = "severity", default_value = "warning")]
+    duplicate_entries: source::Severity,
+    /// How to treat a bulletin published outside the ISO week it covers.
+    #[clap(long, value_name = "severity", default_value = "warning")]
+    misdated_issues: source::Severity,
+    /// Publishes drafts and scheduled resources, marked as drafts.
+    #[clap(long)]
+    drafts: bool,
+    /// Publishes as if it was the given date, including anything scheduled up to then. Defaults to today.
+    #[clap(long, value_name = "date")]
+    as_of: Option<Date>,
+    /// Highlights code blocks at build time instead of leaving them to Zola, with the stylesheet in `static`.
+    #[clap(long)]
+    highlight: bool,
 }
 
 impl Cmd {
     pub fn run(&self) -> Result<()> {
         let mut cache = Cache::connect_with_strategy(self.cache_path.clone())?;
+        let mode = if self.incremental {
+            Mode::Incremental
+        } else {
+            Mode::Full
+        };
 
-        source::read(&self.input_path, &mut cache)?;
-        zola::write(&self.output_path.join("content"), &mut cache)?;
-        feed::write(&self.output_path.join("static"), &mut cache)?;
+        source::read(
+            &self.input_path,
+            &mut cache,
+            mode,
+            self.duplicate_entries,
+            self.misdated_issues,
+        )?;
+        cache.prune()?;
+
+        let mut publication = Publication {
+            drafts: self.drafts,
+            ..Publication::default()
+        };
+        if let Some(date) = self.as_of {
+            publication.as_of = date;
+        }
+        cache.publish(publication)?;
+
+        zola::write(
+            &self.output_path.join("content"),
+            &mut cache,
+            mode,
+            self.highlight,
+        )?;
+        feed::write(
+            &self.output_path.join("static"),
+            &mut cache,
+            &self.feed_formats,
+            self.highlight,
+        )?;
+
+        if self.highlight {
+            let path = self
+                .output_path
+                .join("static")
+                .join(markdown::STYLESHEET_FILENAME);
+            fs::write(&path, markdown::stylesheet())?;
+            info!("highlight: {}", path.display());
+        }
+
+        let datapackage_path = self
+            .datapackage_path
+            .clone()
+            .unwrap_or_else(|| self.output_path.join("static").join("datapackage"));
+        datapackage::write(&datapackage_path, &mut cache)?;
 
         Ok(())
     }
@@ -0,0 +1,70 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::source::{bulletin_stash, BulletinStash};
+use crate::stamp::Date;
+use crate::Resource;
+
+/// Manages bulletins.
+#[derive(Debug, Parser)]
+pub struct Cmd {
+    #[clap(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(Debug, Parser)]
+enum Subcommand {
+    New(NewCmd),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<()> {
+        match &self.subcommand {
+            Subcommand::New(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Promotes the stash into a new bulletin issue for the ISO week of the given date.
+#[derive(Debug, Parser)]
+pub struct NewCmd {
+    /// The path to the source holding the stash.
+    #[clap(long, short = 'i', value_name = "path")]
+    input_path: PathBuf,
+    /// Any date within the week of the issue. Defaults to today.
+    #[clap(long, value_name = "date")]
+    date: Option<Date>,
+    /// The URL of an entry to move into the issue. Repeat it to move several. Defaults to every entry.
+    #[clap(long = "entry", value_name = "url")]
+    entries: Vec<String>,
+    /// The issue summary.
+    #[clap(long, default_value = "")]
+    summary: String,
+}
+
+impl NewCmd {
+    pub fn run(&self) -> Result<()> {
+        let stash_path = match bulletin_stash::find(&self.input_path)? {
+            Some(path) => path,
+            None => bail!("no bulletin stash found in {}", self.input_path.display()),
+        };
+        let mut stash = BulletinStash::from_str(&fs::read_to_string(&stash_path)?)?;
+        let date = self.date.unwrap_or_else(Date::today);
+        let bulletin = stash.promote(&date, &self.summary, &self.entries)?;
+        let bulletin_path = stash_path.with_file_name(format!("{}.toml", bulletin.id()));
+
+        if bulletin_path.exists() {
+            bail!("the issue {} already exists", bulletin_path.display());
+        }
+
+        fs::write(&bulletin_path, bulletin.to_string())?;
+        fs::write(&stash_path, stash.to_string())?;
+
+        println!("{}", bulletin_path.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use std::path::PathBuf;
+
+use crate::source;
+
+/// Checks the source for problems without building anything.
+#[derive(Debug, Parser)]
+pub struct Cmd {
+    /// The path to the source to check.
+    #[clap(long, short = 'i', value_name = "path")]
+    input_path: PathBuf,
+    /// How to treat a bulletin entry found in more than one bulletin or stash.
+    #[clap(long, value_name = "severity", default_value = "warning")]
+    duplicate_entries: source::Severity,
+    /// How to treat a bulletin published outside the ISO week it covers.
+    #[clap(long, value_name = "severity", default_value = "warning")]
+    misdated_issues: source::Severity,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<()> {
+        let report = source::check(
+            &self.input_path,
+            self.duplicate_entries,
+            self.misdated_issues,
+        )?;
+
+        for problem in report.problems() {
+            println!("{}", problem);
+        }
+
+        if !report.is_ok() {
+            bail!("{} problem(s) found", report.problems().len());
+        }
+
+        Ok(())
+    }
+}
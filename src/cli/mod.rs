@@ -1,2 +1,40 @@
+use crate::source::SourceError;
+
 pub mod build;
+pub mod bulletin;
+pub mod check;
 pub mod clean;
+pub mod watch;
+
+/// Renders an error for the terminal, pointing at the offending snippet when the error comes from a source file.
+pub fn render_error(err: &anyhow::Error) -> String {
+    let source_error = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<SourceError>());
+
+    match source_error {
+        Some(SourceError::Parse {
+            path,
+            resource_type,
+            location: Some(location),
+            message,
+        }) => {
+            let gutter = " ".repeat(location.line.to_string().len());
+
+            format!(
+                "error: failed to parse {}: {}\n{}--> {}:{}\n{} |\n{} | {}\n{} | {}^",
+                resource_type,
+                message,
+                gutter,
+                path.display(),
+                location,
+                gutter,
+                location.line,
+                &location.snippet,
+                gutter,
+                " ".repeat(location.column - 1),
+            )
+        }
+        _ => format!("error: {:#}", err),
+    }
+}
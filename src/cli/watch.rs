@@ -0,0 +1,503 @@
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use log::info;
+use notify::event::{EventKind, ModifyKind};
+use notify::{Event, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+use crate::cache::{Cache, Strategy};
+use crate::cli::render_error;
+use crate::feed;
+use crate::markdown;
+use crate::resource_type::ResourceType;
+use crate::source;
+use crate::stamp::Date;
+use crate::zola;
+use crate::{Mode, Publication};
+
+/// Watches the source and rebuilds whatever changes
+#[derive(Debug, Parser)]
+pub struct Cmd {
+    /// Cache path. The cache is kept for the whole session so a disk cache only helps across sessions.
+    #[clap(long, value_name = "path", default_value = ":memory:")]
+    cache_path: Strategy,
+    /// The path to the source to watch.
+    #[clap(long, short = 'i', value_name = "path")]
+    input_path: PathBuf,
+    /// The path to the sink to build into.
+    #[clap(long, short = 'o', value_name = "path")]
+    output_path: PathBuf,
+    /// The feed formats to write into `static`.
+    #[clap(
+        long = "feed",
+        value_name = "format",
+        value_delimiter = ',',
+        default_value = "rss,atom,json"
+    )]
+    feed_formats: Vec<feed::Format>,
+    /// How long to wait, in milliseconds, for a burst of changes to settle before rebuilding.
+    #[clap(long, value_name = "milliseconds", default_value = "200")]
+    debounce: u64,
+    /// How to treat a bulletin entry found in more than one bulletin or stash.
+    #[clap(long, value_name = "severity", default_value = "warning")]
+    duplicate_entries: source::Severity,
+    /// How to treat a bulletin published outside the ISO week it covers.
+    #[clap(long, value_name = "severity", default_value = "warning")]
+    misdated_issues: source::Severity,
+    /// Publishes drafts and scheduled resources, marked as drafts.
+    #[clap(long)]
+    drafts: bool,
+    /// Publishes as if it was the given date, including anything scheduled up to then. Defaults to today.
+    #[clap(long, value_name = "date")]
+    as_of: Option<Date>,
+    /// Highlights code blocks at build time instead of leaving them to Zola, with the stylesheet in `static`.
+    #[clap(long)]
+    highlight: bool,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<()> {
+        let input_path = fs::canonicalize(&self.input_path)
+            .with_context(|| format!("failed to find {}", self.input_path.display()))?;
+        fs::create_dir_all(&self.output_path)?;
+        let output_path = fs::canonicalize(&self.output_path)?;
+
+        let mut cache = Cache::connect_with_strategy(self.cache_path.clone())?;
+        let mut manifest = source::read(
+            &input_path,
+            &mut cache,
+            Mode::Incremental,
+            self.duplicate_entries,
+            self.misdated_issues,
+        )?;
+        cache.prune()?;
+
+        let mut publication = Publication {
+            drafts: self.drafts,
+            ..Publication::default()
+        };
+        if let Some(date) = self.as_of {
+            publication.as_of = date;
+        }
+        cache.publish(publication)?;
+
+        let changed = self.write(&output_path, &mut cache)?;
+        info!("watch: initial build changed {} files", changed.len());
+
+        // The stylesheet does not depend on the source so it is only written once.
+        if self.highlight {
+            let path = output_path
+                .join("static")
+                .join(markdown::STYLESHEET_FILENAME);
+            fs::write(&path, markdown::stylesheet())?;
+            info!("highlight: {}", path.display());
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&input_path, RecursiveMode::Recursive)?;
+        println!("Watching {}", input_path.display());
+
+        let debounce = Duration::from_millis(self.debounce);
+
+        // The loop only ends when the watcher goes away.
+        while let Ok(event) = receiver.recv() {
+            let mut batch = Batch::default();
+            batch.record(&event?, &input_path, &output_path);
+
+            loop {
+                match receiver.recv_timeout(debounce) {
+                    Ok(event) => batch.record(&event?, &input_path, &output_path),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        return Err(anyhow!("the watcher stopped unexpectedly"))
+                    }
+                }
+            }
+
+            if batch.is_empty() {
+                continue;
+            }
+
+            // A broken source file must not end the session, it is reported until fixed.
+            match self.rebuild(&input_path, &output_path, &mut cache, &mut manifest, &batch) {
+                Ok(changed) if changed.is_empty() => println!("No output changed"),
+                Ok(changed) => {
+                    println!("Changed {} files:", changed.len());
+                    for path in changed {
+                        let path = path.strip_prefix(&output_path).unwrap_or(&path);
+                        println!("  {}", path.display());
+                    }
+                }
+                Err(err) => eprintln!("{}", render_error(&err)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Caches the changed files and writes the output again.
+    ///
+    /// Removals, renames, files whose resource changed its id and bulletins or stashes fall back to reading the whole
+    /// source in a new session so the cache can be pruned of what is gone. Bulletin entries need every file to be read
+    /// for duplicates to be found and for entries removed from an issue to be pruned.
+    fn rebuild(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        cache: &mut Cache,
+        manifest: &mut source::Manifest,
+        batch: &Batch,
+    ) -> Result<Vec<PathBuf>> {
+        let mut renew =
+            batch.removed || batch.paths.iter().any(|path| holds_entries(path, manifest));
+
+        if !renew {
+            let paths: Vec<PathBuf> = batch.paths.iter().cloned().collect();
+            let found = source::read_files(
+                input_path,
+                &paths,
+                cache,
+                Mode::Incremental,
+                self.duplicate_entries,
+                self.misdated_issues,
+            )?;
+
+            // A resource saved under a new id leaves the old one cached, and its page written, until pruned.
+            renew = found
+                .iter()
+                .any(|(path, resource)| manifest.get(path).is_some_and(|known| known != resource));
+            manifest.extend(found);
+        }
+
+        if renew {
+            cache.renew()?;
+            *manifest = source::read(
+                input_path,
+                cache,
+                Mode::Incremental,
+                self.duplicate_entries,
+                self.misdated_issues,
+            )?;
+            cache.prune()?;
+        }
+
+        self.write(output_path, cache)
+    }
+
+    fn write(&self, output_path: &Path, cache: &mut Cache) -> Result<Vec<PathBuf>> {
+        let static_path = output_path.join("static");
+        fs::create_dir_all(&static_path)?;
+
+        let mut changed = zola::write(
+            &output_path.join("content"),
+            cache,
+            Mode::Incremental,
+            self.highlight,
+        )?;
+        changed.extend(feed::write(
+            &static_path,
+            cache,
+            &self.feed_formats,
+            self.highlight,
+        )?);
+
+        Ok(changed)
+    }
+}
+
+/// Whether the file holds bulletin entries, either now or when it was last read.
+fn holds_entries(path: &Path, manifest: &source::Manifest) -> bool {
+    let is_bulletin =
+        |typ: &ResourceType| matches!(typ, ResourceType::Bulletin | ResourceType::BulletinStash);
+    let known = manifest.get(path).is_some_and(|(typ, _)| is_bulletin(typ));
+    let current = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| ResourceType::from_hint(&contents).ok())
+        .is_some_and(|typ| is_bulletin(&typ));
+
+    known || current
+}
+
+/// The source files touched by a burst of events.
+#[derive(Debug, Default)]
+struct Batch {
+    paths: BTreeSet<PathBuf>,
+    /// Whether any file went away, either removed or renamed.
+    removed: bool,
+}
+
+impl Batch {
+    /// Keeps the paths from the event that the source stage would read, ignoring anything under the sink.
+    fn record(&mut self, event: &Event, source_dir: &Path, sink_dir: &Path) {
+        let paths: Vec<&PathBuf> = event
+            .paths
+            .iter()
+            .filter(|path| !path.starts_with(sink_dir) && source::is_reachable(source_dir, path))
+            .collect();
+
+        if paths.is_empty() {
+            return;
+        }
+
+        match event.kind {
+            EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Data(_))
+            | EventKind::Modify(ModifyKind::Any) => {
+                self.paths.extend(paths.into_iter().cloned());
+            }
+            EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_)) => {
+                self.paths.extend(paths.into_iter().cloned());
+                self.removed = true;
+            }
+            _ => (),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::Severity;
+    use notify::event::{AccessKind, CreateKind, DataChange, RemoveKind, RenameMode};
+    use temp_testdir::TempDir;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn batch_changes() {
+        let source_dir = Path::new("/corpus");
+        let sink_dir = Path::new("/corpus/site");
+        let mut batch = Batch::default();
+
+        batch.record(
+            &event(EventKind::Create(CreateKind::File), "/corpus/notes/a.md"),
+            source_dir,
+            sink_dir,
+        );
+        batch.record(
+            &event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                "/corpus/notes/a.md",
+            ),
+            source_dir,
+            sink_dir,
+        );
+        batch.record(
+            &event(EventKind::Access(AccessKind::Any), "/corpus/notes/b.md"),
+            source_dir,
+            sink_dir,
+        );
+        batch.record(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/corpus/notes/.a.md.swp",
+            ),
+            source_dir,
+            sink_dir,
+        );
+        batch.record(
+            &event(EventKind::Create(CreateKind::File), "/corpus/.git/index"),
+            source_dir,
+            sink_dir,
+        );
+        batch.record(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/corpus/site/content/a.md",
+            ),
+            source_dir,
+            sink_dir,
+        );
+
+        assert_eq!(
+            batch.paths.into_iter().collect::<Vec<_>>(),
+            vec![PathBuf::from("/corpus/notes/a.md")]
+        );
+        assert!(!batch.removed);
+    }
+
+    #[test]
+    fn batch_removals() {
+        let source_dir = Path::new("/corpus");
+        let sink_dir = Path::new("/site");
+        let mut batch = Batch::default();
+
+        batch.record(
+            &event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+                "/corpus/notes/a.md",
+            ),
+            source_dir,
+            sink_dir,
+        );
+        assert!(batch.removed);
+
+        let mut batch = Batch::default();
+
+        batch.record(
+            &event(EventKind::Remove(RemoveKind::File), "/corpus/notes/a.md"),
+            source_dir,
+            sink_dir,
+        );
+        assert!(batch.removed);
+        assert!(!batch.is_empty());
+    }
+
+    fn note(id: &str) -> String {
+        format!(
+            "---\ntype: note\nid: {}\npublication_date: 2021-07-07\nauthor: arnau\n---\n# A note\n\nA summary.\n\n<!-- body -->\n\nA body.\n",
+            id
+        )
+    }
+
+    fn issue(id: &str, date: &str, urls: &[&str]) -> String {
+        let mut raw = format!(
+            "type = \"bulletin\"\nid = \"{}\"\npublication_date = {}\nsummary = \"A summary.\"\n",
+            id, date
+        );
+
+        for url in urls {
+            raw.push_str(&format!(
+                "\n[[entries]]\nurl = \"{}\"\ntitle = \"A title\"\nsummary = \"A summary.\"\ncontent_type = \"text\"\n",
+                url
+            ));
+        }
+
+        raw
+    }
+
+    /// Writes the resources every build needs, plus a section for notes and one for bulletins.
+    fn corpus(input_path: &Path) -> Result<()> {
+        fs::create_dir_all(input_path.join("notes"))?;
+        fs::create_dir_all(input_path.join("bulletins"))?;
+        fs::write(
+            input_path.join("settings.toml"),
+            r#"type = "settings"
+id = "main"
+title = "Aquarium example"
+description = "An example for Aquarium"
+url = "https://aquarium.netlify.app/"
+copyright = "2021, Arnau Siches"
+navigation = []
+
+[licence]
+url = "http://creativecommons.org/licenses/by-nc/4.0/"
+name = "CC BY-NC 4.0"
+"#,
+        )?;
+        fs::write(
+            input_path.join("index.md"),
+            "---\ntype: entrance\nid: entrance\n---\nWelcome.\n",
+        )?;
+        fs::write(
+            input_path.join("notes.md"),
+            "---\ntype: section\nid: notes\ntitle: Notes\nresource_type: note\n---\nNotes.\n",
+        )?;
+        fs::write(
+            input_path.join("bulletins.md"),
+            "---\ntype: section\nid: bulletins\ntitle: Bulletins\nresource_type: bulletin\n---\nBulletins.\n",
+        )?;
+        fs::write(
+            input_path.join("arnau.toml"),
+            "type = \"person\"\nid = \"arnau\"\nname = \"Arnau Siches\"\nguest = false\naccounts = []\n",
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn rebuild_changed_id() -> Result<()> {
+        let dir = TempDir::default();
+        let input_path = dir.join("corpus");
+        let output_path = dir.join("site");
+        let note_path = input_path.join("notes").join("a-note.md");
+        corpus(&input_path)?;
+        fs::write(&note_path, note("a-note"))?;
+
+        let cmd = Cmd::try_parse_from(["watch", "-i", "corpus", "-o", "site"])?;
+        let mut cache = Cache::connect(":memory:")?;
+        let mut manifest = source::read(
+            &input_path,
+            &mut cache,
+            Mode::Incremental,
+            Severity::Warning,
+            Severity::Warning,
+        )?;
+        cmd.write(&output_path, &mut cache)?;
+
+        assert!(output_path.join("content/notes/a-note.md").exists());
+
+        fs::write(&note_path, note("another-note"))?;
+        let batch = Batch {
+            paths: vec![note_path].into_iter().collect(),
+            removed: false,
+        };
+        cmd.rebuild(&input_path, &output_path, &mut cache, &mut manifest, &batch)?;
+
+        assert!(output_path.join("content/notes/another-note.md").exists());
+        assert!(!output_path.join("content/notes/a-note.md").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rebuild_bulletin() -> Result<()> {
+        let dir = TempDir::default();
+        let input_path = dir.join("corpus");
+        let output_path = dir.join("site");
+        let first_path = input_path.join("bulletins").join("2020-W01.toml");
+        let second_path = input_path.join("bulletins").join("2021-W02.toml");
+        let shared = "https://napi.rs/";
+        let unique = "https://unique.example/";
+        corpus(&input_path)?;
+        fs::write(&first_path, issue("2020-W01", "2020-01-05", &[shared]))?;
+        fs::write(
+            &second_path,
+            issue("2021-W02", "2021-01-17", &[shared, unique]),
+        )?;
+
+        let cmd = Cmd::try_parse_from(["watch", "-i", "corpus", "-o", "site"])?;
+        let mut cache = Cache::connect(":memory:")?;
+        let mut manifest = source::read(
+            &input_path,
+            &mut cache,
+            Mode::Incremental,
+            Severity::Warning,
+            Severity::Warning,
+        )?;
+        cmd.write(&output_path, &mut cache)?;
+
+        let first_page = output_path.join("content/bulletins/2020/2020-W01.md");
+        let second_page = output_path.join("content/bulletins/2021/2021-W02.md");
+
+        assert!(fs::read_to_string(&second_page)?.contains(unique));
+
+        // The shared entry stays in the first issue and the removed one goes away.
+        fs::write(&second_path, issue("2021-W02", "2021-01-17", &[shared]))?;
+        let batch = Batch {
+            paths: vec![second_path].into_iter().collect(),
+            removed: false,
+        };
+        cmd.rebuild(&input_path, &output_path, &mut cache, &mut manifest, &batch)?;
+
+        let first = fs::read_to_string(&first_page)?;
+        let second = fs::read_to_string(&second_page)?;
+
+        assert!(first.contains(shared));
+        assert!(!second.contains(shared));
+        assert!(!second.contains(unique));
+
+        Ok(())
+    }
+}
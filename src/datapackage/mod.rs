@@ -0,0 +1,465 @@
+//! This module covers the [Tabular Data Package] representation of the cache.
+//!
+//! Each table is written as a CSV file described by a [Table Schema] in `datapackage.json`.
+//!
+//! [Tabular Data Package]: https://specs.frictionlessdata.io/tabular-data-package/
+//! [Table Schema]: https://specs.frictionlessdata.io/table-schema/
+
+use anyhow::Result;
+use log::{debug, info};
+use rusqlite::types::ValueRef;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::cache::{params, Cache, Transaction};
+// TODO: Decouple
+use crate::zola::settings;
+
+/// Writes the data package to the given directory.
+///
+/// Files already holding the exact same content are left untouched.
+pub fn write(sink_dir: &Path, cache: &mut Cache) -> Result<()> {
+    let tx = cache.transaction()?;
+    let package = build(&tx)?;
+
+    fs::create_dir_all(sink_dir)?;
+
+    for resource in &package.resources {
+        let buffer = write_csv(&resource.schema, &tx)?;
+        write_file(&sink_dir.join(&resource.path), &buffer)?;
+    }
+
+    let json = serde_json::to_string_pretty(&package)?;
+    write_file(&sink_dir.join("datapackage.json"), json.as_bytes())?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+fn build(tx: &Transaction) -> Result<DataPackage> {
+    let settings = settings::find(tx, "main")?.expect("Missing main settings.");
+    let resources = tables().into_iter().map(DataResource::from).collect();
+    let package = DataPackage {
+        profile: "tabular-data-package".to_string(),
+        name: slug::slugify(&settings.title),
+        title: settings.title,
+        description: settings.description,
+        homepage: settings.url,
+        licenses: vec![License {
+            path: settings.licence.url,
+            title: settings.licence.name,
+        }],
+        resources,
+    };
+
+    Ok(package)
+}
+
+/// Writes the file unless it already has the exact same content.
+fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if fs::read(path)
+        .map(|current| current == contents)
+        .unwrap_or(false)
+    {
+        debug!("datapackage: unchanged {}", path.display());
+
+        return Ok(());
+    }
+
+    fs::write(path, contents)?;
+    info!("datapackage: {}", path.display());
+
+    Ok(())
+}
+
+/// Writes the rows of the table described by the schema as CSV, in the same order as the schema fields.
+///
+/// Rows belonging to unpublished resources are left out.
+fn write_csv(schema: &Schema, tx: &Transaction) -> Result<Vec<u8>> {
+    let columns: Vec<&str> = schema
+        .fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    let clause = schema
+        .filter
+        .as_ref()
+        .map(|filter| format!(" WHERE {}", filter))
+        .unwrap_or_default();
+    let query = format!(
+        "SELECT {} FROM {}{} ORDER BY {};",
+        columns.join(", "),
+        schema.table,
+        clause,
+        schema.primary_key.join(", ")
+    );
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut stmt = tx.prepare(&query)?;
+    let mut rows = stmt.query(params![])?;
+
+    writer.write_record(&columns)?;
+
+    while let Some(row) = rows.next()? {
+        let mut record = Vec::with_capacity(columns.len());
+
+        for (idx, field) in schema.fields.iter().enumerate() {
+            let value = match (row.get_ref(idx)?, &field.kind) {
+                (ValueRef::Null, _) => String::new(),
+                (ValueRef::Integer(n), FieldType::Boolean) => (n != 0).to_string(),
+                (ValueRef::Integer(n), _) => n.to_string(),
+                (ValueRef::Real(n), _) => n.to_string(),
+                (ValueRef::Text(s), _) => String::from_utf8_lossy(s).to_string(),
+                (ValueRef::Blob(_), _) => {
+                    anyhow::bail!("blobs are not supported in a data package")
+                }
+            };
+
+            record.push(value);
+        }
+
+        writer.write_record(&record)?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// A filter keeping the rows where the column does not reference an excluded resource of the given type.
+fn published(column: &str, resource_type: &str) -> String {
+    format!(
+        "{} NOT IN (SELECT id FROM excluded WHERE resource_type = '{}')",
+        column, resource_type
+    )
+}
+
+/// A filter keeping the rows for bulletin entries in the stash or in a published bulletin.
+fn published_entry(column: &str) -> String {
+    format!(
+        "{} NOT IN (SELECT url FROM bulletin_entry WHERE issue_id IN (SELECT id FROM excluded WHERE resource_type = 'bulletin'))",
+        column
+    )
+}
+
+/// Describes the exported tables. Keep in sync with `cache.sql`.
+fn tables() -> Vec<Schema> {
+    vec![
+        Schema::new("person", &["id"])
+            .field("id", FieldType::String, true)
+            .field("name", FieldType::String, true)
+            .field("guest", FieldType::Boolean, true),
+        Schema::new("tool", &["id"])
+            .field("id", FieldType::String, true)
+            .field("name", FieldType::String, true)
+            .field("summary", FieldType::String, false)
+            .field("url", FieldType::String, false),
+        Schema::new("note", &["id"])
+            .field("id", FieldType::String, true)
+            .field("title", FieldType::String, true)
+            .field("summary", FieldType::String, true)
+            .field("publication_date", FieldType::Date, true)
+            .field("author_id", FieldType::String, true)
+            .field("body", FieldType::String, true)
+            .field("draft", FieldType::Boolean, true)
+            .foreign_key("author_id", "person", "id")
+            .filter(published("id", "note")),
+        Schema::new("note_tag", &["note_id", "tag"])
+            .field("note_id", FieldType::String, true)
+            .field("tag", FieldType::String, true)
+            .foreign_key("note_id", "note", "id")
+            .filter(published("note_id", "note")),
+        Schema::new("sketch", &["id"])
+            .field("id", FieldType::String, true)
+            .field("title", FieldType::String, true)
+            .field("asset_id", FieldType::String, true)
+            .field("author_id", FieldType::String, true)
+            .field("publication_date", FieldType::Date, true)
+            .field("summary", FieldType::String, false)
+            .field("draft", FieldType::Boolean, true)
+            .foreign_key("author_id", "person", "id")
+            .filter(published("id", "sketch")),
+        Schema::new("sketch_tool", &["sketch_id", "tool_id"])
+            .field("sketch_id", FieldType::String, true)
+            .field("tool_id", FieldType::String, true)
+            .foreign_key("sketch_id", "sketch", "id")
+            .foreign_key("tool_id", "tool", "id")
+            .filter(published("sketch_id", "sketch")),
+        Schema::new("sketch_tag", &["sketch_id", "tag"])
+            .field("sketch_id", FieldType::String, true)
+            .field("tag", FieldType::String, true)
+            .foreign_key("sketch_id", "sketch", "id")
+            .filter(published("sketch_id", "sketch")),
+        Schema::new("project", &["id"])
+            .field("id", FieldType::String, true)
+            .field("name", FieldType::String, true)
+            .field("summary", FieldType::String, true)
+            .field("body", FieldType::String, true)
+            .field("status", FieldType::String, true)
+            .field("start_date", FieldType::Date, true)
+            .field("end_date", FieldType::Date, false)
+            .field("source_url", FieldType::String, false)
+            .field("draft", FieldType::Boolean, true)
+            .filter(published("id", "project")),
+        Schema::new("project_tag", &["project_id", "tag"])
+            .field("project_id", FieldType::String, true)
+            .field("tag", FieldType::String, true)
+            .foreign_key("project_id", "project", "id")
+            .filter(published("project_id", "project")),
+        Schema::new("bulletin_issue", &["id"])
+            .field("id", FieldType::String, true)
+            .field("summary", FieldType::String, true)
+            .field("publication_date", FieldType::Date, true)
+            .field("draft", FieldType::Boolean, true)
+            .filter(published("id", "bulletin")),
+        Schema::new("bulletin_entry", &["url"])
+            .field("url", FieldType::String, true)
+            .field("title", FieldType::String, true)
+            .field("summary", FieldType::String, true)
+            .field("content_type", FieldType::String, true)
+            .field("issue_id", FieldType::String, false)
+            .field("duration", FieldType::String, false)
+            .foreign_key("issue_id", "bulletin_issue", "id")
+            .filter(format!(
+                "issue_id IS NULL OR {}",
+                published("issue_id", "bulletin")
+            )),
+        Schema::new("bulletin_entry_author", &["entry_url", "name"])
+            .field("entry_url", FieldType::String, true)
+            .field("name", FieldType::String, true)
+            .foreign_key("entry_url", "bulletin_entry", "url")
+            .filter(published_entry("entry_url")),
+        Schema::new("bulletin_mention", &["mention_url", "entry_url"])
+            .field("mention_url", FieldType::String, true)
+            .field("entry_url", FieldType::String, true)
+            .foreign_key("entry_url", "bulletin_entry", "url")
+            .filter(published_entry("entry_url")),
+        Schema::new("bulletin_entry_tag", &["entry_url", "tag"])
+            .field("entry_url", FieldType::String, true)
+            .field("tag", FieldType::String, true)
+            .foreign_key("entry_url", "bulletin_entry", "url")
+            .filter(published_entry("entry_url")),
+    ]
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataPackage {
+    profile: String,
+    name: String,
+    title: String,
+    description: String,
+    homepage: String,
+    licenses: Vec<License>,
+    resources: Vec<DataResource>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct License {
+    path: String,
+    title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataResource {
+    profile: String,
+    name: String,
+    path: String,
+    format: String,
+    mediatype: String,
+    encoding: String,
+    schema: Schema,
+}
+
+impl From<Schema> for DataResource {
+    fn from(schema: Schema) -> Self {
+        Self {
+            profile: "tabular-data-resource".to_string(),
+            name: schema.table.clone(),
+            path: format!("{}.csv", &schema.table),
+            format: "csv".to_string(),
+            mediatype: "text/csv".to_string(),
+            encoding: "utf-8".to_string(),
+            schema,
+        }
+    }
+}
+
+/// A [Table Schema](https://specs.frictionlessdata.io/table-schema/).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    #[serde(skip)]
+    table: String,
+    fields: Vec<Field>,
+    primary_key: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    foreign_keys: Vec<ForeignKey>,
+    /// The SQL condition rows must meet to be exported.
+    #[serde(skip)]
+    filter: Option<String>,
+}
+
+impl Schema {
+    fn new(table: &str, primary_key: &[&str]) -> Self {
+        Self {
+            table: table.to_string(),
+            fields: Vec::new(),
+            primary_key: primary_key.iter().map(|s| s.to_string()).collect(),
+            foreign_keys: Vec::new(),
+            filter: None,
+        }
+    }
+
+    fn field(mut self, name: &str, kind: FieldType, required: bool) -> Self {
+        self.fields.push(Field {
+            name: name.to_string(),
+            kind,
+            constraints: Constraints { required },
+        });
+
+        self
+    }
+
+    fn filter(mut self, clause: String) -> Self {
+        self.filter = Some(clause);
+
+        self
+    }
+
+    fn foreign_key(mut self, field: &str, resource: &str, resource_field: &str) -> Self {
+        self.foreign_keys.push(ForeignKey {
+            fields: vec![field.to_string()],
+            reference: Reference {
+                resource: resource.to_string(),
+                fields: vec![resource_field.to_string()],
+            },
+        });
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Field {
+    name: String,
+    #[serde(rename = "type")]
+    kind: FieldType,
+    constraints: Constraints,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Boolean,
+    Date,
+    String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Constraints {
+    required: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForeignKey {
+    fields: Vec<String>,
+    reference: Reference,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reference {
+    resource: String,
+    fields: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::WriteCache;
+    use crate::source::{Person, PersonSet, Settings, SettingsSet};
+    use std::str::FromStr;
+    use temp_testdir::TempDir;
+
+    #[test]
+    fn write_package() -> Result<()> {
+        let raw_settings = r#"type = "settings"
+id = "main"
+title = "Aquarium example"
+description = "An example for Aquarium"
+url = "https://aquarium.netlify.app/"
+copyright = "2021, Arnau Siches"
+navigation = []
+
+[licence]
+url = "http://creativecommons.org/licenses/by-nc/4.0/"
+name = "CC BY-NC 4.0"
+"#;
+        let raw_person = r#"type = "person"
+id = "bobsponge"
+name = "Bob, Sponge"
+guest = true
+accounts = []
+"#;
+        let dir = TempDir::default();
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        SettingsSet::add(&tx, Settings::from_str(raw_settings)?)?;
+        PersonSet::add(&tx, Person::from_str(raw_person)?)?;
+        tx.commit()?;
+
+        write(&dir, &mut cache)?;
+
+        let people = fs::read_to_string(dir.join("person.csv"))?;
+        let package: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("datapackage.json"))?)?;
+
+        assert_eq!(people, "id,name,guest\nbobsponge,\"Bob, Sponge\",true\n");
+        assert_eq!(package["name"], "aquarium-example");
+        assert_eq!(package["resources"].as_array().map(|r| r.len()), Some(14));
+        assert!(dir.join("bulletin_entry.csv").exists());
+
+        let modified = fs::metadata(dir.join("person.csv"))?.modified()?;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        write(&dir, &mut cache)?;
+
+        assert_eq!(fs::metadata(dir.join("person.csv"))?.modified()?, modified);
+
+        Ok(())
+    }
+
+    #[test]
+    fn foreign_keys_match_cache() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        let schemas = tables();
+        let exported: Vec<&str> = schemas.iter().map(|schema| schema.table.as_str()).collect();
+
+        for schema in &schemas {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT \"from\", \"table\", \"to\" FROM pragma_foreign_key_list('{}')",
+                schema.table
+            ))?;
+            let mut expected = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<Result<Vec<(String, String, String)>, _>>()?;
+            expected.retain(|(_, table, _)| exported.contains(&table.as_str()));
+            expected.sort();
+
+            let mut actual: Vec<(String, String, String)> = schema
+                .foreign_keys
+                .iter()
+                .map(|key| {
+                    (
+                        key.fields.join(","),
+                        key.reference.resource.clone(),
+                        key.reference.fields.join(","),
+                    )
+                })
+                .collect();
+            actual.sort();
+
+            assert_eq!(actual, expected, "foreign keys for {}", schema.table);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! This module covers the Atom 1.0 ([RFC 4287](https://www.rfc-editor.org/rfc/rfc4287)) representation of the feed.
+//!
+//! Entry ids are the same permalinks used as RSS guids so they stay stable across builds.
+//!
+//! RFC 4287 §4.1.1 requires an author for every entry. Entries without one rely on the feed authors, which fall back
+//! to the feed title when there are no people other than guests.
+
+use anyhow::Result;
+use atom_syndication::{
+    ContentBuilder, EntryBuilder, FeedBuilder, Generator, LinkBuilder, Person, PersonBuilder, Text,
+};
+use std::io::Write;
+
+use super::{Feed, Format};
+use crate::markdown;
+
+pub fn write<W: Write>(feed: &Feed, writer: W) -> Result<()> {
+    let mut entries = Vec::new();
+
+    for item in &feed.items {
+        let entry = EntryBuilder::default()
+            .title(Text::plain(&item.title))
+            .id(&item.url)
+            .updated(item.date)
+            .published(Some(item.date.into()))
+            .authors(
+                item.author
+                    .iter()
+                    .map(|name| person(name))
+                    .collect::<Vec<_>>(),
+            )
+            .link(LinkBuilder::default().href(&item.url).build())
+            .summary(
+                item.summary
+                    .as_ref()
+                    .map(|s| Text::html(markdown::to_html(s))),
+            )
+            .content(item.content.as_ref().map(|html| {
+                ContentBuilder::default()
+                    .value(Some(html.clone()))
+                    .content_type(Some("html".to_string()))
+                    .build()
+            }))
+            .build();
+
+        entries.push(entry);
+    }
+
+    let generator = Generator {
+        value: "Aquarium".to_string(),
+        ..Default::default()
+    };
+    let self_link = LinkBuilder::default()
+        .href(feed.self_url(Format::Atom))
+        .rel("self")
+        .build();
+    let alternate_link = LinkBuilder::default().href(&feed.url).build();
+    let authors = if feed.authors.is_empty() {
+        vec![person(&feed.title)]
+    } else {
+        feed.authors.iter().map(|name| person(name)).collect()
+    };
+
+    let atom = FeedBuilder::default()
+        .title(Text::plain(&feed.title))
+        .id(&feed.url)
+        .updated(feed.updated())
+        .authors(authors)
+        .link(alternate_link)
+        .link(self_link)
+        .subtitle(Some(Text::plain(&feed.description)))
+        .rights(Some(Text::plain(&feed.copyright)))
+        .generator(Some(generator))
+        .lang(Some("en".to_string()))
+        .entries(entries)
+        .build();
+
+    atom.write_to(writer)?;
+
+    Ok(())
+}
+
+fn person(name: &str) -> Person {
+    PersonBuilder::default().name(name).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::Item;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn author_fallback() -> Result<()> {
+        let feed = Feed {
+            title: "Aquarium example".to_string(),
+            url: "https://aquarium.netlify.app/".to_string(),
+            description: "An example for Aquarium".to_string(),
+            copyright: "2021, Arnau Siches".to_string(),
+            authors: Vec::new(),
+            items: vec![Item {
+                title: "2021-W02".to_string(),
+                summary: None,
+                content: None,
+                url: "https://aquarium.netlify.app/bulletins/2021/2021-W02".to_string(),
+                date: Utc.with_ymd_and_hms(2021, 1, 17, 0, 0, 0).unwrap(),
+                author: None,
+            }],
+        };
+        let mut buffer = Vec::new();
+        write(&feed, &mut buffer)?;
+        let atom = String::from_utf8(buffer)?;
+
+        assert!(atom.contains("<author><name>Aquarium example</name></author>"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+//! This module covers the [JSON Feed 1.1](https://www.jsonfeed.org/version/1.1/) representation of the feed.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+
+use super::{Feed, Format};
+use crate::markdown;
+
+pub fn write<W: Write>(feed: &Feed, writer: W) -> Result<()> {
+    let items = feed
+        .items
+        .iter()
+        .map(|item| JsonItem {
+            id: item.url.clone(),
+            url: item.url.clone(),
+            title: item.title.clone(),
+            content_html: item.content.clone().unwrap_or_else(|| {
+                item.summary
+                    .as_ref()
+                    .map(|s| markdown::to_html(s))
+                    .unwrap_or_default()
+            }),
+            summary: item.summary.clone(),
+            date_published: item.date.to_rfc3339(),
+            authors: item.author.iter().map(|name| Author::new(name)).collect(),
+        })
+        .collect();
+    let json = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1".to_string(),
+        title: feed.title.clone(),
+        home_page_url: feed.url.clone(),
+        feed_url: feed.self_url(Format::Json),
+        description: feed.description.clone(),
+        language: "en".to_string(),
+        authors: feed.authors.iter().map(|name| Author::new(name)).collect(),
+        items,
+    };
+
+    serde_json::to_writer_pretty(writer, &json)?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct JsonFeed {
+    version: String,
+    title: String,
+    home_page_url: String,
+    feed_url: String,
+    description: String,
+    language: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<Author>,
+    items: Vec<JsonItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct JsonItem {
+    id: String,
+    url: String,
+    title: String,
+    content_html: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    date_published: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<Author>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Author {
+    name: String,
+}
+
+impl Author {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+}
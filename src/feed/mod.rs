@@ -1,76 +1,707 @@
-//! This module covers both the general feed.
+//! This module covers the general feed in RSS 2.0, Atom 1.0 and JSON Feed 1.1.
+//!
+//! The items are amassed once from the cache and then written in each of the requested formats.
 
-use anyhow::Result;
-use chrono::{NaiveDate, Utc, TimeZone};
-use rss::{Channel, ChannelBuilder, Guid, ItemBuilder};
-use std::fs::File;
-use std::path::Path;
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, TimeZone, Utc};
+use log::{debug, info};
+use pulldown_cmark::escape::{escape_href, escape_html};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use crate::cache::records::{AuxRecordSet, BulletinEntryAuthorRecordSet, BulletinMentionRecordSet};
 use crate::cache::{params, Cache, Transaction};
 use crate::markdown;
-// TODO: Decouple
-use crate::zola::settings;
+use crate::resource_type::ResourceType;
+use crate::stamp::DateTime;
+use crate::zola::bulletin_year::{self, BulletinYear};
+use crate::zola::section::{self, Section};
+use crate::zola::settings::{self, Settings};
+use crate::zola::ZolaResource;
 
-pub fn write(sink_dir: &Path, cache: &mut Cache) -> Result<()> {
-    let file = File::create(sink_dir.join("rss.xml"))?;
+mod atom;
+mod json;
+mod rss;
+
+/// Writes the site feed plus a feed for each section holding resources and for each bulletin year.
+///
+/// Section feeds are written in the same relative directory Zola uses for the section so, once Zola copies the
+/// static files over, `/notes/atom.xml` sits next to `/notes/`.
+///
+/// Code blocks in the content are highlighted at build time when `highlight` is set, as they are for the notes.
+///
+/// Files already holding the exact same feed are left untouched. Returns the files that were written.
+pub fn write(
+    sink_dir: &Path,
+    cache: &mut Cache,
+    formats: &[Format],
+    highlight: bool,
+) -> Result<Vec<PathBuf>> {
     let tx = cache.transaction()?;
-    let channel = build(&tx)?;
+    let settings = settings::find(&tx, "main")?.expect("Missing main settings.");
+    let mut changed = Vec::new();
+
+    write_feed(
+        sink_dir,
+        &build(&tx, &settings, &Scope::site(), highlight)?,
+        formats,
+        &mut changed,
+    )?;
+
+    for section in section::amass(&tx)? {
+        let section_dir = sink_dir.join(section.path());
+
+        match section.resource_type() {
+            Some(ResourceType::Note) | Some(ResourceType::Sketch) | Some(ResourceType::Project) => {
+                let scope = Scope::section(&section);
+
+                fs::create_dir_all(&section_dir)?;
+                write_feed(
+                    &section_dir,
+                    &build(&tx, &settings, &scope, highlight)?,
+                    formats,
+                    &mut changed,
+                )?;
+            }
+            Some(ResourceType::Bulletin) => {
+                let scope = Scope::section(&section);
+
+                fs::create_dir_all(&section_dir)?;
+                write_feed(
+                    &section_dir,
+                    &build(&tx, &settings, &scope, highlight)?,
+                    formats,
+                    &mut changed,
+                )?;
+
+                for year in bulletin_year::amass(&tx)? {
+                    let year_dir = section_dir.join(year.path());
+                    let scope = Scope::bulletin_year(&section, &year);
+
+                    fs::create_dir_all(&year_dir)?;
+                    write_feed(
+                        &year_dir,
+                        &build(&tx, &settings, &scope, highlight)?,
+                        formats,
+                        &mut changed,
+                    )?;
+                }
+            }
+            _ => (),
+        }
+    }
 
-    channel.write_to(file)?;
     tx.commit()?;
 
+    Ok(changed)
+}
+
+fn write_feed(
+    sink_dir: &Path,
+    feed: &Feed,
+    formats: &[Format],
+    changed: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for format in formats {
+        let path = sink_dir.join(format.filename());
+        let mut buffer = Vec::new();
+
+        match format {
+            Format::Rss => rss::write(feed, &mut buffer)?,
+            Format::Atom => atom::write(feed, &mut buffer)?,
+            Format::Json => json::write(feed, &mut buffer)?,
+        }
+
+        if fs::read(&path)
+            .map(|current| current == buffer)
+            .unwrap_or(false)
+        {
+            debug!("feed({}): unchanged {}", format, path.display());
+            continue;
+        }
+
+        fs::write(&path, buffer)?;
+        changed.push(path.clone());
+        info!("feed({}): {}", format, path.display());
+    }
+
     Ok(())
 }
 
-fn build(tx: &Transaction) -> Result<Channel> {
-    let settings = settings::find(tx, "main")?.expect("Missing main settings.");
+/// The portion of the site a feed covers.
+#[derive(Debug, Clone, PartialEq)]
+struct Scope {
+    /// The path the feed is published under. Empty for the whole site.
+    path: String,
+    /// The section the items are published under. `None` for the whole site.
+    section: Option<String>,
+    /// The type of resource the items are selected by. `None` for the whole site.
+    resource_type: Option<ResourceType>,
+    /// The bulletin year the items are selected by.
+    year: Option<String>,
+    title: Option<String>,
+}
+
+impl Scope {
+    fn site() -> Self {
+        Self {
+            path: String::new(),
+            section: None,
+            resource_type: None,
+            year: None,
+            title: None,
+        }
+    }
+
+    fn section(section: &Section) -> Self {
+        Self {
+            path: section.id().to_string(),
+            section: Some(section.id().to_string()),
+            resource_type: section.resource_type().cloned(),
+            year: None,
+            title: Some(section.metadata.title.clone()),
+        }
+    }
+
+    fn bulletin_year(section: &Section, year: &BulletinYear) -> Self {
+        Self {
+            path: format!("{}/{}", section.id(), year.id()),
+            section: Some(section.id().to_string()),
+            resource_type: section.resource_type().cloned(),
+            year: Some(year.id().to_string()),
+            title: Some(format!("{} {}", &section.metadata.title, year.id())),
+        }
+    }
+}
+
+/// The feed formats available.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// RSS 2.0, written as `rss.xml`.
+    Rss,
+    /// Atom 1.0 (RFC 4287), written as `atom.xml`.
+    Atom,
+    /// JSON Feed 1.1, written as `feed.json`.
+    Json,
+}
+
+impl Format {
+    pub fn all() -> Vec<Format> {
+        vec![Format::Rss, Format::Atom, Format::Json]
+    }
+
+    pub fn filename(&self) -> &'static str {
+        match self {
+            Format::Rss => "rss.xml",
+            Format::Atom => "atom.xml",
+            Format::Json => "feed.json",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rss" => Ok(Format::Rss),
+            "atom" => Ok(Format::Atom),
+            "json" => Ok(Format::Json),
+            s => bail!(
+                "unknown feed format '{}'. Expected one of: rss, atom, json",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Format::Rss => "rss",
+            Format::Atom => "atom",
+            Format::Json => "json",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+/// A format-agnostic feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    pub title: String,
+    /// The home page for the feed. Either the site or the section the feed covers.
+    pub url: String,
+    pub description: String,
+    pub copyright: String,
+    /// The people behind the site, as opposed to guests.
+    pub authors: Vec<String>,
+    pub items: Vec<Item>,
+}
+
+impl Feed {
+    /// The URL where the feed is published in the given format.
+    pub fn self_url(&self, format: Format) -> String {
+        join_url(&self.url, format.filename())
+    }
+
+    /// The date of the most recent item.
+    pub fn updated(&self) -> DateTime {
+        self.items
+            .iter()
+            .map(|item| item.date)
+            .max()
+            .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub title: String,
+    pub summary: Option<String>,
+    /// The full content rendered as HTML.
+    pub content: Option<String>,
+    /// The permalink. It doubles as the identifier for the item.
+    pub url: String,
+    pub date: DateTime,
+    pub author: Option<String>,
+}
+
+/// Builds the feed for the given scope.
+///
+/// Items are selected by resource type and linked under the section holding them, whatever its id. Resources of a
+/// type no section holds are linked under the conventional section for the type.
+fn build(tx: &Transaction, settings: &Settings, scope: &Scope, highlight: bool) -> Result<Feed> {
     let mut items = Vec::new();
     let query = r#"
         SELECT
-            *
+            feed.id,
+            feed.title,
+            feed.summary,
+            coalesce(
+                ?3,
+                (
+                    SELECT id FROM section
+                    WHERE section.resource_type = feed.resource_type
+                    ORDER BY id
+                    LIMIT 1
+                ),
+                feed.section
+            ),
+            feed.year,
+            feed.date,
+            person.name,
+            coalesce(note.body, project.body),
+            bulletin_issue.id
         FROM
             feed
+        LEFT JOIN
+            note ON feed.resource_type = 'note' AND note.id = feed.id
+        LEFT JOIN
+            sketch ON feed.resource_type = 'sketch' AND sketch.id = feed.id
+        LEFT JOIN
+            project ON feed.resource_type = 'project' AND project.id = feed.id
+        LEFT JOIN
+            bulletin_issue ON feed.resource_type = 'bulletin' AND bulletin_issue.id = feed.id
+        LEFT JOIN
+            person ON person.id = coalesce(note.author_id, sketch.author_id)
+        WHERE
+            (?1 IS NULL OR feed.resource_type = ?1)
+            AND (?2 IS NULL OR feed.year = ?2)
         ORDER BY
-            date DESC
+            feed.date DESC
         LIMIT 10
     "#;
     let mut stmt = tx.prepare(query)?;
-    let mut rows = stmt.query(params![])?;
+    let resource_type = scope.resource_type.as_ref().map(ToString::to_string);
+    let mut rows = stmt.query(params![&resource_type, &scope.year, &scope.section])?;
 
     while let Some(row) = rows.next()? {
         let id: String = row.get(0)?;
         let title: String = row.get(1)?;
         let summary: Option<String> = row.get(2)?;
         let section: String = row.get(3)?;
-        let date: String = row.get(4)?;
-        // let pub_date = DateTime::<Utc>::from_utc(NaiveDate::from_str(&date)?.and_hms(0, 0, 0), Utc);
-        let pub_date = Utc.from_utc_datetime(&NaiveDate::from_str(&date)?.and_hms_opt(0, 0, 0).unwrap());
-        let url = format!("{}/{}/{}", &settings.url, &section, &id);
-        let mut guid = Guid::default();
-        guid.set_value(&url);
-        guid.set_permalink(true);
-
-        let item = ItemBuilder::default()
-            .title(title)
-            .description(summary.map(|s| markdown::to_html(&s)))
-            .link(url.clone())
-            .guid(guid)
-            .pub_date(pub_date.to_rfc2822())
-            .build();
-
-        items.push(item);
-    }
-
-    let channel = ChannelBuilder::default()
-        .title(&settings.title)
-        .link(&settings.url)
-        .description(&settings.description)
-        .copyright(settings.copyright)
-        .language("en".to_string())
-        .generator("Aquarium".to_string())
-        .items(items)
-        .build();
-
-    Ok(channel)
+        let year: Option<String> = row.get(4)?;
+        let date: String = row.get(5)?;
+        let author: Option<String> = row.get(6)?;
+        let body: Option<String> = row.get(7)?;
+        let issue_id: Option<String> = row.get(8)?;
+        let content = match (body, issue_id) {
+            (Some(body), _) => Some(
+                render_body(summary.as_deref(), &body, highlight)
+                    .with_context(|| format!("failed to render the body of '{}'", &id))?,
+            ),
+            (None, Some(issue_id)) => Some(render_bulletin(tx, summary.as_deref(), &issue_id)?),
+            (None, None) => None,
+        };
+        let date =
+            Utc.from_utc_datetime(&NaiveDate::from_str(&date)?.and_hms_opt(0, 0, 0).unwrap());
+        let path = match &year {
+            Some(year) => format!("{}/{}/{}", &section, year, &id),
+            None => format!("{}/{}", &section, &id),
+        };
+        let url = join_url(&settings.url, &path);
+
+        items.push(Item {
+            title,
+            summary,
+            content,
+            url,
+            date,
+            author,
+        });
+    }
+
+    let mut authors = Vec::new();
+    let mut stmt = tx.prepare("SELECT name FROM person WHERE guest = false ORDER BY id")?;
+    let mut rows = stmt.query(params![])?;
+
+    while let Some(row) = rows.next()? {
+        authors.push(row.get(0)?);
+    }
+
+    let (title, url) = match &scope.title {
+        Some(title) => (
+            format!("{}: {}", &settings.title, title),
+            join_url(&settings.url, &format!("{}/", &scope.path)),
+        ),
+        None => (settings.title.clone(), settings.url.clone()),
+    };
+    let feed = Feed {
+        title,
+        url,
+        description: settings.description.clone(),
+        copyright: settings.copyright.clone(),
+        authors,
+        items,
+    };
+
+    Ok(feed)
+}
+
+/// Renders the summary followed by the body, as Zola would.
+///
+/// Code blocks are highlighted the same way they are for the notes.
+fn render_body(summary: Option<&str>, body: &str, highlight: bool) -> Result<String> {
+    let mut html = summary.map(markdown::to_html).unwrap_or_default();
+    html.push_str(&markdown::to_html(&markdown::enrich(body, highlight)?));
+
+    Ok(html)
+}
+
+/// Renders the summary followed by every entry in the issue, in the order they were given, crediting where each entry
+/// was found.
+fn render_bulletin(tx: &Transaction, summary: Option<&str>, issue_id: &str) -> Result<String> {
+    let mut html = summary.map(markdown::to_html).unwrap_or_default();
+    let mut stmt = tx.prepare(
+        r#"
+        SELECT
+            url,
+            title,
+            summary,
+            content_type,
+            duration
+        FROM
+            bulletin_entry
+        WHERE
+            issue_id = ?
+        ORDER BY
+            rowid
+        "#,
+    )?;
+    let mut rows = stmt.query(params![issue_id])?;
+
+    while let Some(row) = rows.next()? {
+        let url: String = row.get(0)?;
+        let title: String = row.get(1)?;
+        let summary: String = row.get(2)?;
+        let content_type: String = row.get(3)?;
+        let duration: Option<String> = row.get(4)?;
+
+        html.push_str("<h2><a href=\"");
+        escape_href(&mut html, &url)?;
+        html.push_str("\">");
+        escape_html(&mut html, &title)?;
+        html.push_str("</a>");
+        // Text is the norm so only other content types are badged.
+        if content_type != "text" {
+            html.push_str(" (");
+            escape_html(&mut html, &content_type)?;
+            if let Some(duration) = &duration {
+                html.push_str(", ");
+                escape_html(&mut html, duration)?;
+            }
+            html.push(')');
+        }
+        html.push_str("</h2>\n");
+
+        let authors: Vec<String> = BulletinEntryAuthorRecordSet::select(tx, url.clone())?
+            .into_iter()
+            .map(|author| author.name)
+            .collect();
+
+        if !authors.is_empty() {
+            html.push_str("<p>By ");
+            escape_html(&mut html, &authors.join(", "))?;
+            html.push_str("</p>\n");
+        }
+
+        html.push_str(&markdown::to_html(&summary));
+
+        let mentions: Vec<String> = BulletinMentionRecordSet::select(tx, url.clone())?
+            .into_iter()
+            .map(|mention| mention.mention_url)
+            .collect();
+
+        if !mentions.is_empty() {
+            html.push_str("<p>Via ");
+            for (i, mention) in mentions.iter().enumerate() {
+                if i > 0 {
+                    html.push_str(", ");
+                }
+                html.push_str("<a href=\"");
+                escape_href(&mut html, mention)?;
+                html.push_str("\">");
+                escape_html(&mut html, mention)?;
+                html.push_str("</a>");
+            }
+            html.push_str("</p>\n");
+        }
+    }
+
+    Ok(html)
+}
+
+fn join_url(base: &str, path: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::WriteCache;
+    use crate::source;
+    use crate::source::{
+        Bulletin, BulletinSet, Note, NoteSet, Person, PersonSet, SectionSet, SettingsSet,
+    };
+    use temp_testdir::TempDir;
+
+    #[test]
+    fn write_all_formats() -> Result<()> {
+        let raw_settings = r#"type = "settings"
+id = "main"
+title = "Aquarium example"
+description = "An example for Aquarium"
+url = "https://aquarium.netlify.app/"
+copyright = "2021, Arnau Siches"
+navigation = []
+
+[licence]
+url = "http://creativecommons.org/licenses/by-nc/4.0/"
+name = "CC BY-NC 4.0"
+"#;
+        let raw_person = r#"type = "person"
+id = "arnau"
+name = "Arnau Siches"
+guest = false
+accounts = []
+"#;
+        let raw_note = r#"---
+type: note
+id: a-note
+publication_date: 2021-07-07
+author: arnau
+---
+# A simple note
+
+A summary.
+
+<!-- body -->
+
+A body."#;
+        let dir = TempDir::default();
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        SettingsSet::add(&tx, source::Settings::from_str(raw_settings)?)?;
+        PersonSet::add(&tx, Person::from_str(raw_person)?)?;
+        NoteSet::add(&tx, Note::from_str(raw_note)?)?;
+        tx.commit()?;
+
+        write(&dir, &mut cache, &Format::all(), false)?;
+
+        let atom = fs::read_to_string(dir.join("atom.xml"))?;
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("feed.json"))?)?;
+
+        let rss = fs::read_to_string(dir.join("rss.xml"))?;
+
+        assert!(rss.contains("xmlns:content=\"http://purl.org/rss/1.0/modules/content/\""));
+        assert!(rss.contains("<content:encoded><![CDATA[<p>A summary.</p>\n<p>A body.</p>"));
+        assert!(rss.contains(">https://aquarium.netlify.app/notes/a-note</guid>"));
+        assert!(!rss.contains("app//") && !atom.contains("app//"));
+        assert!(atom.contains("<id>https://aquarium.netlify.app/notes/a-note</id>"));
+        assert!(atom.contains("<updated>2021-07-07T00:00:00+00:00</updated>"));
+        assert!(atom.contains("<author><name>Arnau Siches</name></author>"));
+        assert_eq!(json["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(
+            json["items"][0]["id"],
+            "https://aquarium.netlify.app/notes/a-note"
+        );
+        assert_eq!(json["items"][0]["authors"][0]["name"], "Arnau Siches");
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_section_feeds() -> Result<()> {
+        let raw_settings = r#"type = "settings"
+id = "main"
+title = "Aquarium example"
+description = "An example for Aquarium"
+url = "https://aquarium.netlify.app/"
+copyright = "2021, Arnau Siches"
+navigation = []
+
+[licence]
+url = "http://creativecommons.org/licenses/by-nc/4.0/"
+name = "CC BY-NC 4.0"
+"#;
+        let raw_notes = r#"---
+type: section
+id: notes
+title: Notes
+resource_type: note
+---
+Notes, reflections and reviews."#;
+        let raw_bulletins = r#"---
+type: section
+id: bulletins
+title: Bulletins
+resource_type: bulletin
+---
+Weekly bulletins."#;
+        let raw_bulletin = r#"type = "bulletin"
+id = "2021-W02"
+publication_date = "2021-01-17"
+summary = "A summary."
+
+[[entries]]
+url = "https://calcolor.co/"
+title = "calcolor"
+summary = "A service to inspect and learn about _colours_."
+content_type = "text"
+via = ["https://news.ycombinator.com/item?id=1&a=b"]
+
+[[entries]]
+url = "https://www.youtube.com/watch?v=rHIkrotSwcc"
+title = "Stop Writing Classes"
+summary = "A talk."
+content_type = "video"
+duration = "27:29"
+"#;
+        let dir = TempDir::default();
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        SettingsSet::add(&tx, source::Settings::from_str(raw_settings)?)?;
+        SectionSet::add(&tx, source::Section::from_str(raw_notes)?)?;
+        SectionSet::add(&tx, source::Section::from_str(raw_bulletins)?)?;
+        BulletinSet::add(&tx, Bulletin::from_str(raw_bulletin)?)?;
+        tx.commit()?;
+
+        write(&dir, &mut cache, &[Format::Json], false)?;
+
+        let notes: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("notes/feed.json"))?)?;
+        let year: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("bulletins/2021/feed.json"))?)?;
+
+        assert!(dir.join("feed.json").exists());
+        assert!(!dir.join("notes/rss.xml").exists());
+        assert_eq!(notes["title"], "Aquarium example: Notes");
+        assert_eq!(
+            notes["feed_url"],
+            "https://aquarium.netlify.app/notes/feed.json"
+        );
+        assert_eq!(notes["items"].as_array().map(|i| i.len()), Some(0));
+        assert_eq!(year["title"], "Aquarium example: Bulletins 2021");
+        assert_eq!(year["items"][0]["title"], "2021-W02");
+        assert_eq!(
+            year["items"][0]["content_html"],
+            "<p>A summary.</p>\n<h2><a href=\"https://calcolor.co/\">calcolor</a></h2>\n<p>A service to inspect and learn about <em>colours</em>.</p>\n<p>Via <a href=\"https://news.ycombinator.com/item?id=1&amp;a=b\">https://news.ycombinator.com/item?id=1&amp;a=b</a></p>\n<h2><a href=\"https://www.youtube.com/watch?v=rHIkrotSwcc\">Stop Writing Classes</a> (video, 27:29)</h2>\n<p>A talk.</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_renamed_section_feed() -> Result<()> {
+        let raw_settings = r#"type = "settings"
+id = "main"
+title = "Aquarium example"
+description = "An example for Aquarium"
+url = "https://aquarium.netlify.app/"
+copyright = "2021, Arnau Siches"
+navigation = []
+
+[licence]
+url = "http://creativecommons.org/licenses/by-nc/4.0/"
+name = "CC BY-NC 4.0"
+"#;
+        let raw_person = r#"type = "person"
+id = "arnau"
+name = "Arnau Siches"
+guest = false
+accounts = []
+"#;
+        let raw_section = r#"---
+type: section
+id: writing
+title: Writing
+resource_type: note
+---
+Notes, reflections and reviews."#;
+        let raw_note = r#"---
+type: note
+id: a-note
+publication_date: 2021-07-07
+author: arnau
+---
+# A simple note
+
+A summary.
+
+<!-- body -->
+
+A body."#;
+        let dir = TempDir::default();
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        SettingsSet::add(&tx, source::Settings::from_str(raw_settings)?)?;
+        PersonSet::add(&tx, Person::from_str(raw_person)?)?;
+        SectionSet::add(&tx, source::Section::from_str(raw_section)?)?;
+        NoteSet::add(&tx, Note::from_str(raw_note)?)?;
+        tx.commit()?;
+
+        write(&dir, &mut cache, &[Format::Json], false)?;
+
+        let site: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("feed.json"))?)?;
+        let writing: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("writing/feed.json"))?)?;
+
+        assert_eq!(writing["items"].as_array().map(|i| i.len()), Some(1));
+        assert_eq!(
+            writing["items"][0]["id"],
+            "https://aquarium.netlify.app/writing/a-note"
+        );
+        assert_eq!(
+            site["items"][0]["id"],
+            "https://aquarium.netlify.app/writing/a-note"
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,51 @@
+//! This module covers the RSS 2.0 representation of the feed.
+
+use anyhow::Result;
+use rss::{ChannelBuilder, Guid, ItemBuilder};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use super::Feed;
+use crate::markdown;
+
+pub fn write<W: Write>(feed: &Feed, writer: W) -> Result<()> {
+    let mut items = Vec::new();
+
+    for item in &feed.items {
+        let mut guid = Guid::default();
+        guid.set_value(&item.url);
+        guid.set_permalink(true);
+
+        let item = ItemBuilder::default()
+            .title(item.title.clone())
+            .description(item.summary.as_ref().map(|s| markdown::to_html(s)))
+            .content(item.content.clone())
+            .link(item.url.clone())
+            .guid(guid)
+            .pub_date(item.date.to_rfc2822())
+            .build();
+
+        items.push(item);
+    }
+
+    let mut namespaces = BTreeMap::new();
+    namespaces.insert(
+        "content".to_string(),
+        "http://purl.org/rss/1.0/modules/content/".to_string(),
+    );
+
+    let channel = ChannelBuilder::default()
+        .title(&feed.title)
+        .link(&feed.url)
+        .description(&feed.description)
+        .copyright(feed.copyright.clone())
+        .language("en".to_string())
+        .generator("Aquarium".to_string())
+        .namespaces(namespaces)
+        .items(items)
+        .build();
+
+    channel.write_to(writer)?;
+
+    Ok(())
+}
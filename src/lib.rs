@@ -1,6 +1,7 @@
 pub mod cache;
 pub mod checksum;
 pub mod cli;
+pub mod datapackage;
 pub mod feed;
 pub mod markdown;
 pub mod resource_type;
@@ -21,6 +22,34 @@ pub trait Resource: checksum::Digest {
 /// A resource set for any stage.
 pub trait ResourceSet: IntoIterator {}
 
+/// How a build treats what previous builds left behind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Rebuilds everything from scratch.
+    Full,
+    /// Skips resources whose checksum is already cached and only rewrites the files that changed.
+    Incremental,
+}
+
+/// What a build publishes.
+///
+/// Notes, sketches, projects and bulletins dated after `as_of` are scheduled. Drafts and scheduled resources are kept
+/// out of the output unless `drafts` is set, in which case they are published marked as drafts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Publication {
+    pub as_of: stamp::Date,
+    pub drafts: bool,
+}
+
+impl Default for Publication {
+    fn default() -> Self {
+        Self {
+            as_of: stamp::Date::today(),
+            drafts: false,
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub(crate) fn to_hex(buffer: &[u8]) -> String {
     let mut s = String::new();
@@ -1,5 +1,6 @@
 use clap::Parser;
 use std::io::Write;
+use std::process;
 
 use aquarium::cli;
 
@@ -7,7 +8,10 @@ use aquarium::cli;
 enum Subcommand {
     #[clap(alias = "b")]
     Build(cli::build::Cmd),
+    Bulletin(cli::bulletin::Cmd),
+    Check(cli::check::Cmd),
     Clean(cli::clean::Cmd),
+    Watch(cli::watch::Cmd),
 }
 
 #[derive(Debug, Parser)]
@@ -28,22 +32,16 @@ fn main() {
 
     let cli: Cli = Cli::parse();
 
-    match cli.subcommand {
-        Subcommand::Build(cmd) => match cmd.run() {
-            Ok(_msg) => {
-                // println!("{}", msg);
-            }
-            Err(err) => {
-                eprintln!("{:?}", err);
-            }
-        },
-        Subcommand::Clean(cmd) => match cmd.run() {
-            Ok(_msg) => {
-                // println!("{}", msg);
-            }
-            Err(err) => {
-                eprintln!("{:?}", err);
-            }
-        },
+    let result = match cli.subcommand {
+        Subcommand::Build(cmd) => cmd.run(),
+        Subcommand::Bulletin(cmd) => cmd.run(),
+        Subcommand::Check(cmd) => cmd.run(),
+        Subcommand::Clean(cmd) => cmd.run(),
+        Subcommand::Watch(cmd) => cmd.run(),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", cli::render_error(&err));
+        process::exit(1);
     }
 }
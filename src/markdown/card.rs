@@ -0,0 +1,198 @@
+//! This module renders CSV and TOML blocks as cards.
+
+use anyhow::{anyhow, bail, Result};
+use pulldown_cmark::escape::{escape_href, escape_html};
+
+use super::info::Info;
+
+/// A record to render as a card, with its fields in source order.
+type Record = Vec<(String, String)>;
+
+/// Renders each record in the text as a card.
+///
+/// In CSV each row after the header is a record. In TOML it is either the whole document or, when the document
+/// only holds an array of tables, each of its tables.
+///
+/// The info string accepts:
+///
+/// - `title=<field>` for the field to use as the card title. Defaults to `title` and is required in every record.
+/// - `link=<field>` for the field with the URL the title links to. Defaults to `url` and is optional.
+///
+/// Any other non-empty field is listed as a key/value pair.
+pub fn render(text: &str, info: &Info) -> Result<String> {
+    let records = match info.lang.as_str() {
+        "csv" => read_csv(text)?,
+        "toml" => read_toml(text)?,
+        lang => bail!("cards are not supported for '{}'", lang),
+    };
+    let title_field = info.attribute("title").unwrap_or("title");
+    let link_field = info.attribute("link").unwrap_or("url");
+    let mut html = String::new();
+
+    for (index, record) in records.iter().enumerate() {
+        let title = find(record, title_field)
+            .ok_or_else(|| anyhow!("card {} has no '{}' field", index + 1, title_field))?;
+
+        html.push_str("<div class=\"card\">\n<div class=\"card-title\">");
+        match find(record, link_field) {
+            Some(link) => {
+                html.push_str("<a href=\"");
+                escape_href(&mut html, link)?;
+                html.push_str("\">");
+                escape_html(&mut html, title)?;
+                html.push_str("</a>");
+            }
+            None => escape_html(&mut html, title)?,
+        }
+        html.push_str("</div>\n");
+
+        let fields: Vec<_> = record
+            .iter()
+            .filter(|(key, value)| key != title_field && key != link_field && !value.is_empty())
+            .collect();
+
+        if !fields.is_empty() {
+            html.push_str("<dl>\n");
+            for (key, value) in fields {
+                html.push_str("   <dt>");
+                escape_html(&mut html, key)?;
+                html.push_str("</dt>\n   <dd>");
+                escape_html(&mut html, value)?;
+                html.push_str("</dd>\n");
+            }
+            html.push_str("</dl>\n");
+        }
+
+        html.push_str("</div>\n");
+    }
+
+    Ok(html)
+}
+
+fn find<'a>(record: &'a [(String, String)], field: &str) -> Option<&'a str> {
+    record
+        .iter()
+        .find(|(key, value)| key == field && !value.is_empty())
+        .map(|(_, value)| value.as_str())
+}
+
+fn read_csv(text: &str) -> Result<Vec<Record>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|err| anyhow!("malformed CSV card: {}", err))?
+        .clone();
+    let mut records = Vec::new();
+
+    for result in reader.records() {
+        let record = result.map_err(|err| anyhow!("malformed CSV card: {}", err))?;
+
+        records.push(
+            headers
+                .iter()
+                .zip(record.iter())
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        );
+    }
+
+    Ok(records)
+}
+
+fn read_toml(text: &str) -> Result<Vec<Record>> {
+    let document: toml::Table = text
+        .parse()
+        .map_err(|err| anyhow!("malformed TOML card: {}", err))?;
+    let mut values = document.values();
+
+    if let (Some(toml::Value::Array(tables)), None) = (values.next(), values.next()) {
+        if tables.iter().all(|value| value.is_table()) {
+            return Ok(tables
+                .iter()
+                .filter_map(|value| value.as_table())
+                .map(toml_record)
+                .collect());
+        }
+    }
+
+    Ok(vec![toml_record(&document)])
+}
+
+fn toml_record(table: &toml::Table) -> Record {
+    table
+        .iter()
+        .map(|(key, value)| (key.clone(), toml_value(value)))
+        .collect()
+}
+
+/// Flattens the value into text. Arrays are joined with commas.
+fn toml_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Array(values) => values.iter().map(toml_value).collect::<Vec<_>>().join(", "),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_csv() -> Result<()> {
+        let info = Info::parse("csv target=card title=name");
+        let actual = render(
+            "name,url,role\nCod & co,https://example.org/?a=1&b=2,\n",
+            &info,
+        )?;
+
+        assert_eq!(
+            actual,
+            "<div class=\"card\">\n<div class=\"card-title\"><a href=\"https://example.org/?a=1&amp;b=2\">Cod &amp; co</a></div>\n</div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_toml() -> Result<()> {
+        let info = Info::parse("toml target=card");
+        let text = r#"
+[[tool]]
+title = "Zola"
+url = "https://www.getzola.org/"
+language = "Rust"
+tags = ["ssg", "web"]
+
+[[tool]]
+title = "SQLite"
+language = "C"
+"#;
+        let actual = render(text, &info)?;
+
+        assert_eq!(actual.matches("<div class=\"card\">").count(), 2);
+        assert!(actual.contains(
+            "<dl>\n   <dt>language</dt>\n   <dd>Rust</dd>\n   <dt>tags</dt>\n   <dd>ssg, web</dd>\n</dl>"
+        ));
+        assert!(actual.contains("<div class=\"card-title\">SQLite</div>"));
+
+        let single = render("title = \"Zola\"\nversion = 17\n", &info)?;
+
+        assert!(single.contains("<dt>version</dt>\n   <dd>17</dd>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_without_title() {
+        let info = Info::parse("csv target=card");
+        let actual = render("name\nZola\n", &info);
+
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "card 1 has no 'title' field"
+        );
+    }
+}
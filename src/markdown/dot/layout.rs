@@ -0,0 +1,1171 @@
+//! This module lays out a [`Graph`] in ranks, in the spirit of the `dot` engine but far simpler.
+//!
+//! Nodes are ranked by their longest path from a source once the edges closing a cycle are reversed. Edges spanning
+//! more than one rank go through virtual points, ranks are ordered with a few barycentre sweeps and, finally, nodes
+//! are nudged towards their neighbours. Every step is deterministic: ties are broken by order of appearance.
+//!
+//! The layout is computed top to bottom in a frame where `x` runs across ranks and `y` along them, and transformed
+//! into the requested `rankdir` at the end.
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use super::{Attributes, Edge, Graph, Node};
+
+pub const FONT_SIZE: f64 = 14.0;
+/// The average glyph width relative to the font size. Good enough for the default serif font.
+const GLYPH_RATIO: f64 = 0.6;
+const LINE_RATIO: f64 = 1.2;
+const MARGIN: f64 = 4.0;
+/// Points per inch, the unit for sizes and separations in DOT.
+const POINTS: f64 = 72.0;
+const NODE_SEPARATION: f64 = 18.0;
+const RANK_SEPARATION: f64 = 36.0;
+const LABEL_GAP: f64 = 6.0;
+const MULTI_EDGE_SEPARATION: f64 = 24.0;
+const LOOP_REACH: f64 = 24.0;
+const DUMMY_BREADTH: f64 = 8.0;
+const ARROW_LENGTH: f64 = 10.0;
+const ARROW_WIDTH: f64 = 7.0;
+const ORDER_SWEEPS: usize = 8;
+const POSITION_SWEEPS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+
+    fn scale(self, factor: f64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+
+    fn midpoint(self, other: Point) -> Point {
+        self.add(other).scale(0.5)
+    }
+
+    /// The unit vector in the same direction, or zero for a zero vector.
+    fn unit(self) -> Point {
+        let length = self.x.hypot(self.y);
+
+        if length == 0.0 {
+            self
+        } else {
+            self.scale(1.0 / length)
+        }
+    }
+
+    /// The vector rotated a quarter turn.
+    fn perpendicular(self) -> Point {
+        Point::new(-self.y, self.x)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankDir {
+    TopBottom,
+    BottomTop,
+    LeftRight,
+    RightLeft,
+}
+
+impl RankDir {
+    fn from_attribute(value: Option<&str>) -> Self {
+        match value.map(|s| s.to_ascii_uppercase()).as_deref() {
+            Some("BT") => RankDir::BottomTop,
+            Some("LR") => RankDir::LeftRight,
+            Some("RL") => RankDir::RightLeft,
+            _ => RankDir::TopBottom,
+        }
+    }
+
+    fn is_horizontal(&self) -> bool {
+        matches!(self, RankDir::LeftRight | RankDir::RightLeft)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Box,
+    Ellipse,
+    Circle,
+    Diamond,
+    Point,
+    Plain,
+}
+
+impl Shape {
+    /// Any shape outside the supported ones is drawn as an ellipse, the default in DOT.
+    pub fn from_attribute(value: Option<&str>) -> Self {
+        match value.map(|s| s.to_ascii_lowercase()).as_deref() {
+            Some("box") | Some("rect") | Some("rectangle") | Some("square") => Shape::Box,
+            Some("circle") | Some("doublecircle") => Shape::Circle,
+            Some("diamond") => Shape::Diamond,
+            Some("point") => Shape::Point,
+            Some("plaintext") | Some("plain") | Some("none") => Shape::Plain,
+            _ => Shape::Ellipse,
+        }
+    }
+
+    /// The point where the segment from the centre towards the given direction meets the outline.
+    fn boundary(&self, center: Point, half_x: f64, half_y: f64, direction: Point) -> Point {
+        let d = direction.unit();
+
+        if d.x == 0.0 && d.y == 0.0 {
+            return center;
+        }
+
+        let t = match self {
+            Shape::Ellipse | Shape::Circle | Shape::Point => {
+                1.0 / ((d.x / half_x).powi(2) + (d.y / half_y).powi(2)).sqrt()
+            }
+            Shape::Diamond => 1.0 / (d.x.abs() / half_x + d.y.abs() / half_y),
+            Shape::Box | Shape::Plain => {
+                let tx = if d.x == 0.0 {
+                    f64::INFINITY
+                } else {
+                    half_x / d.x.abs()
+                };
+                let ty = if d.y == 0.0 {
+                    f64::INFINITY
+                } else {
+                    half_y / d.y.abs()
+                };
+
+                tx.min(ty)
+            }
+        };
+
+        center.add(d.scale(t))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeBox {
+    pub center: Point,
+    pub width: f64,
+    pub height: f64,
+    pub shape: Shape,
+}
+
+/// A block of text, centred.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBox {
+    pub center: Point,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Line(Point),
+    Quadratic(Point, Point),
+    Cubic(Point, Point, Point),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgePath {
+    pub start: Point,
+    pub segments: Vec<Segment>,
+    /// Arrowheads as triangles, tip first.
+    pub arrows: Vec<[Point; 3]>,
+    pub label: Option<TextBox>,
+}
+
+/// The geometry for every node and edge in a graph, in the order the graph has them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    pub width: f64,
+    pub height: f64,
+    pub nodes: Vec<NodeBox>,
+    pub edges: Vec<EdgePath>,
+    pub label: Option<TextBox>,
+}
+
+/// A node or a virtual point in a rank.
+#[derive(Debug, Clone)]
+struct Vertex {
+    /// The extent across the rank.
+    breadth: f64,
+    /// The extent along the rank.
+    depth: f64,
+}
+
+impl Layout {
+    pub fn new(graph: &Graph) -> Self {
+        let rank_dir = RankDir::from_attribute(graph.attribute("rankdir"));
+        let horizontal = rank_dir.is_horizontal();
+        let node_separation = inches(graph.attribute("nodesep")).unwrap_or(NODE_SEPARATION);
+        let rank_separation = inches(graph.attribute("ranksep")).unwrap_or(RANK_SEPARATION);
+        // Converts a (width, height) as drawn into (breadth, depth) in the layout frame.
+        let frame = |(width, height): (f64, f64)| {
+            if horizontal {
+                (height, width)
+            } else {
+                (width, height)
+            }
+        };
+
+        let sizes: Vec<(f64, f64)> = graph.nodes.iter().map(node_size).collect();
+        let labels: Vec<Option<(f64, f64)>> = graph.edges.iter().map(edge_label_size).collect();
+        let loops = self_loops(graph);
+
+        let (ranks, reversed) = rank(graph);
+        let rank_count = ranks.iter().max().map(|r| r + 1).unwrap_or(0);
+
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); rank_count];
+
+        for (index, size) in sizes.iter().enumerate() {
+            let (mut breadth, depth) = frame(*size);
+
+            // Room for the self loops on one side, mirrored to keep the node centred.
+            if let Some(reach) = loops[index] {
+                breadth += 2.0 * reach;
+            }
+
+            vertices.push(Vertex { breadth, depth });
+            layers[ranks[index]].push(index);
+        }
+
+        // The vertices each edge goes through, in drawing order. Empty for self loops.
+        let mut chains: Vec<Vec<usize>> = Vec::new();
+        let mut gaps = vec![rank_separation; rank_count.saturating_sub(1)];
+
+        for (index, edge) in graph.edges.iter().enumerate() {
+            if edge.tail == edge.head {
+                chains.push(Vec::new());
+                continue;
+            }
+
+            let (top, bottom) = if reversed[index] {
+                (edge.head, edge.tail)
+            } else {
+                (edge.tail, edge.head)
+            };
+            let mut chain = vec![top];
+
+            for layer in &mut layers[(ranks[top] + 1)..ranks[bottom]] {
+                vertices.push(Vertex {
+                    breadth: DUMMY_BREADTH,
+                    depth: 0.0,
+                });
+                layer.push(vertices.len() - 1);
+                chain.push(vertices.len() - 1);
+            }
+            chain.push(bottom);
+
+            if reversed[index] {
+                chain.reverse();
+            }
+
+            if let Some(size) = labels[index] {
+                let (breadth, depth) = frame(size);
+
+                if chain.len() > 2 {
+                    // The label sits next to the middle virtual point so it needs room in that rank.
+                    let vertex = &mut vertices[chain[chain.len() / 2]];
+                    vertex.breadth = 2.0 * (breadth + LABEL_GAP);
+                    vertex.depth = depth;
+                } else {
+                    let r = ranks[edge.tail].min(ranks[edge.head]);
+                    gaps[r] = gaps[r].max(depth + 2.0 * LABEL_GAP);
+                }
+            }
+
+            chains.push(chain);
+        }
+
+        // Links between vertices in consecutive ranks, from the upper rank and from the lower rank.
+        let mut uppers: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+        let mut lowers: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+        let mut vertex_ranks = vec![0; vertices.len()];
+
+        for (r, layer) in layers.iter().enumerate() {
+            for v in layer {
+                vertex_ranks[*v] = r;
+            }
+        }
+
+        for chain in &chains {
+            for pair in chain.windows(2) {
+                let (upper, lower) = if vertex_ranks[pair[0]] < vertex_ranks[pair[1]] {
+                    (pair[0], pair[1])
+                } else {
+                    (pair[1], pair[0])
+                };
+
+                uppers[lower].push(upper);
+                lowers[upper].push(lower);
+            }
+        }
+
+        order(&mut layers, &uppers, &lowers, vertices.len());
+
+        let across = position(&layers, &vertices, &uppers, &lowers, node_separation);
+
+        let mut along = vec![0.0; vertices.len()];
+        let mut offset = 0.0;
+        for (r, layer) in layers.iter().enumerate() {
+            let depth = layer.iter().map(|v| vertices[*v].depth).fold(0.0, f64::max);
+
+            for v in layer {
+                along[*v] = offset + depth / 2.0;
+            }
+
+            offset += depth + gaps.get(r).copied().unwrap_or(0.0);
+        }
+        let total_depth = offset;
+
+        // From the layout frame into the drawing frame.
+        let place = |p: Point| match rank_dir {
+            RankDir::TopBottom => p,
+            RankDir::BottomTop => Point::new(p.x, total_depth - p.y),
+            RankDir::LeftRight => Point::new(p.y, p.x),
+            RankDir::RightLeft => Point::new(total_depth - p.y, p.x),
+        };
+        let centers: Vec<Point> = (0..vertices.len())
+            .map(|v| place(Point::new(across[v], along[v])))
+            .collect();
+
+        let nodes: Vec<NodeBox> = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| NodeBox {
+                center: centers[index],
+                width: sizes[index].0,
+                height: sizes[index].1,
+                shape: Shape::from_attribute(node.attribute("shape")),
+            })
+            .collect();
+
+        let mut loop_counts = vec![0; graph.nodes.len()];
+        let edges = graph
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(index, edge)| {
+                let arrows = arrows(graph, edge);
+
+                if edge.tail == edge.head {
+                    let count = loop_counts[edge.tail];
+                    loop_counts[edge.tail] += 1;
+
+                    self_loop(&nodes[edge.tail], count, rank_dir, labels[index], arrows)
+                } else if chains[index].len() == 2 {
+                    let offset = multi_edge_offset(graph, index);
+
+                    direct_edge(
+                        &nodes[edge.tail],
+                        &nodes[edge.head],
+                        offset,
+                        horizontal,
+                        labels[index],
+                        arrows,
+                    )
+                } else {
+                    let points: Vec<Point> = chains[index][1..chains[index].len() - 1]
+                        .iter()
+                        .map(|v| centers[*v])
+                        .collect();
+
+                    long_edge(
+                        &nodes[edge.tail],
+                        &nodes[edge.head],
+                        &points,
+                        horizontal,
+                        labels[index],
+                        arrows,
+                    )
+                }
+            })
+            .collect();
+
+        let mut layout = Layout {
+            width: 0.0,
+            height: 0.0,
+            nodes,
+            edges,
+            label: None,
+        };
+        layout.fit();
+
+        let label = graph
+            .attributes
+            .get("label")
+            .map(|label| text_size(&super::split_label(label), font_size(&graph.attributes)));
+        if let Some((width, height)) = label {
+            layout.width = layout.width.max(width + 2.0 * MARGIN);
+            layout.label = Some(TextBox {
+                center: Point::new(layout.width / 2.0, layout.height + height / 2.0),
+                width,
+                height,
+            });
+            layout.height += height + MARGIN;
+        }
+
+        layout
+    }
+
+    /// Moves everything so the drawing starts at the margin and sets the size to fit it.
+    fn fit(&mut self) {
+        let mut min = Point::new(f64::INFINITY, f64::INFINITY);
+        let mut max = Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY);
+        let mut include = |p: Point| {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        };
+
+        for node in &self.nodes {
+            let half = Point::new(node.width / 2.0, node.height / 2.0);
+            include(node.center.sub(half));
+            include(node.center.add(half));
+        }
+
+        for edge in &self.edges {
+            for p in edge.points() {
+                include(p);
+            }
+            if let Some(label) = &edge.label {
+                let half = Point::new(label.width / 2.0, label.height / 2.0);
+                include(label.center.sub(half));
+                include(label.center.add(half));
+            }
+        }
+
+        if self.nodes.is_empty() {
+            min = Point::new(0.0, 0.0);
+            max = Point::new(0.0, 0.0);
+        }
+
+        let shift = Point::new(MARGIN - min.x, MARGIN - min.y);
+        let moved = |p: &mut Point| *p = p.add(shift);
+
+        for node in &mut self.nodes {
+            moved(&mut node.center);
+        }
+
+        for edge in &mut self.edges {
+            moved(&mut edge.start);
+            for segment in &mut edge.segments {
+                match segment {
+                    Segment::Line(p) => moved(p),
+                    Segment::Quadratic(c, p) => {
+                        moved(c);
+                        moved(p);
+                    }
+                    Segment::Cubic(c1, c2, p) => {
+                        moved(c1);
+                        moved(c2);
+                        moved(p);
+                    }
+                }
+            }
+            for arrow in &mut edge.arrows {
+                arrow.iter_mut().for_each(moved);
+            }
+            if let Some(label) = &mut edge.label {
+                moved(&mut label.center);
+            }
+        }
+
+        self.width = max.x - min.x + 2.0 * MARGIN;
+        self.height = max.y - min.y + 2.0 * MARGIN;
+    }
+}
+
+impl EdgePath {
+    /// Every point in the path, including control points which bound the curves.
+    fn points(&self) -> Vec<Point> {
+        let mut points = vec![self.start];
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Line(p) => points.push(*p),
+                Segment::Quadratic(c, p) => points.extend([*c, *p]),
+                Segment::Cubic(c1, c2, p) => points.extend([*c1, *c2, *p]),
+            }
+        }
+
+        for arrow in &self.arrows {
+            points.extend(arrow.iter());
+        }
+
+        points
+    }
+}
+
+/// The font size set in the attributes, in points.
+pub fn font_size(attributes: &Attributes) -> f64 {
+    attributes
+        .get("fontsize")
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|size| *size > 0.0)
+        .unwrap_or(FONT_SIZE)
+}
+
+/// The distance between baselines for the given font size.
+pub fn line_height(font_size: f64) -> f64 {
+    font_size * LINE_RATIO
+}
+
+/// An estimate of the (width, height) the text takes.
+fn text_size(lines: &[String], font_size: f64) -> (f64, f64) {
+    let longest = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    (
+        longest as f64 * font_size * GLYPH_RATIO,
+        lines.len() as f64 * line_height(font_size),
+    )
+}
+
+fn inches(value: Option<&str>) -> Option<f64> {
+    value
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|n| *n >= 0.0)
+        .map(|n| n * POINTS)
+}
+
+/// The (width, height) of the node as drawn.
+fn node_size(node: &Node) -> (f64, f64) {
+    let (text_width, text_height) = text_size(&node.label(), font_size(&node.attributes));
+    let (width, height) = match Shape::from_attribute(node.attribute("shape")) {
+        Shape::Box => (text_width + 16.0, text_height + 8.0),
+        Shape::Ellipse => ((text_width + 8.0) * 1.42, text_height * 1.42),
+        Shape::Circle => {
+            let diameter = ((text_width + 8.0) * 1.42).max(text_height * 1.42);
+
+            (diameter, diameter)
+        }
+        Shape::Diamond => ((text_width + 8.0) * 2.0, text_height * 2.0),
+        Shape::Point => return (8.0, 8.0),
+        Shape::Plain => return (text_width + 8.0, text_height + 4.0),
+    };
+    let min_width = inches(node.attribute("width")).unwrap_or(54.0);
+    let min_height = inches(node.attribute("height")).unwrap_or(36.0);
+
+    (width.max(min_width), height.max(min_height))
+}
+
+fn edge_label_size(edge: &Edge) -> Option<(f64, f64)> {
+    let lines = edge.label();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(text_size(&lines, font_size(&edge.attributes)))
+    }
+}
+
+/// How far the self loops of each node reach out, if it has any.
+fn self_loops(graph: &Graph) -> Vec<Option<f64>> {
+    let mut reach = vec![None; graph.nodes.len()];
+    let mut counts = vec![0; graph.nodes.len()];
+    let horizontal = RankDir::from_attribute(graph.attribute("rankdir")).is_horizontal();
+
+    for edge in graph.edges.iter().filter(|edge| edge.tail == edge.head) {
+        counts[edge.tail] += 1;
+
+        let label = edge_label_size(edge)
+            .map(|(width, height)| if horizontal { height } else { width } + LABEL_GAP)
+            .unwrap_or(0.0);
+        let current: f64 = reach[edge.tail].unwrap_or(0.0);
+
+        reach[edge.tail] = Some(current.max(loop_reach(counts[edge.tail] - 1) + label));
+    }
+
+    reach
+}
+
+fn loop_reach(count: usize) -> f64 {
+    LOOP_REACH + count as f64 * LOOP_REACH / 2.0
+}
+
+/// Ranks every node with the longest path from a source.
+///
+/// Edges closing a cycle, found with a depth first search in order of appearance, are reversed for ranking.
+/// Returns the rank for each node and whether each edge was reversed.
+fn rank(graph: &Graph) -> (Vec<usize>, Vec<bool>) {
+    let count = graph.nodes.len();
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); count];
+
+    for (index, edge) in graph.edges.iter().enumerate() {
+        if edge.tail != edge.head {
+            outgoing[edge.tail].push(index);
+        }
+    }
+
+    // 0: unvisited, 1: in the current path, 2: done.
+    fn visit(
+        node: usize,
+        graph: &Graph,
+        outgoing: &[Vec<usize>],
+        state: &mut [u8],
+        reversed: &mut [bool],
+    ) {
+        state[node] = 1;
+
+        for edge in &outgoing[node] {
+            let head = graph.edges[*edge].head;
+
+            match state[head] {
+                0 => visit(head, graph, outgoing, state, reversed),
+                1 => reversed[*edge] = true,
+                _ => (),
+            }
+        }
+
+        state[node] = 2;
+    }
+
+    let mut state = vec![0; count];
+    let mut reversed = vec![false; graph.edges.len()];
+
+    for node in 0..count {
+        if state[node] == 0 {
+            visit(node, graph, &outgoing, &mut state, &mut reversed);
+        }
+    }
+
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let mut indegree = vec![0; count];
+
+    for (index, edge) in graph.edges.iter().enumerate() {
+        if edge.tail == edge.head {
+            continue;
+        }
+
+        let (from, to) = if reversed[index] {
+            (edge.head, edge.tail)
+        } else {
+            (edge.tail, edge.head)
+        };
+
+        successors[from].push(to);
+        indegree[to] += 1;
+    }
+
+    let mut ranks = vec![0; count];
+    let mut queue: VecDeque<usize> = (0..count).filter(|n| indegree[*n] == 0).collect();
+
+    while let Some(node) = queue.pop_front() {
+        for next in &successors[node] {
+            ranks[*next] = ranks[*next].max(ranks[node] + 1);
+            indegree[*next] -= 1;
+
+            if indegree[*next] == 0 {
+                queue.push_back(*next);
+            }
+        }
+    }
+
+    (ranks, reversed)
+}
+
+/// Orders each rank to reduce crossings, keeping the best ordering found.
+fn order(layers: &mut [Vec<usize>], uppers: &[Vec<usize>], lowers: &[Vec<usize>], count: usize) {
+    let mut positions = vec![0; count];
+    let update = |layer: &[usize], positions: &mut [usize]| {
+        for (i, v) in layer.iter().enumerate() {
+            positions[*v] = i;
+        }
+    };
+
+    for layer in layers.iter() {
+        update(layer, &mut positions);
+    }
+
+    let mut best = layers.to_vec();
+    let mut best_crossings = crossings(layers, lowers, &positions);
+
+    for sweep in 0..ORDER_SWEEPS {
+        if sweep % 2 == 0 {
+            for layer in layers.iter_mut().skip(1) {
+                sort_by_barycentre(layer, uppers, &positions);
+                update(layer, &mut positions);
+            }
+        } else {
+            for layer in layers.iter_mut().rev().skip(1) {
+                sort_by_barycentre(layer, lowers, &positions);
+                update(layer, &mut positions);
+            }
+        }
+
+        let current = crossings(layers, lowers, &positions);
+        if current < best_crossings {
+            best_crossings = current;
+            best = layers.to_vec();
+        }
+    }
+
+    layers.clone_from_slice(&best);
+}
+
+fn sort_by_barycentre(layer: &mut [usize], neighbours: &[Vec<usize>], positions: &[usize]) {
+    let mut keyed: Vec<(f64, usize, usize)> = layer
+        .iter()
+        .map(|v| {
+            let linked = &neighbours[*v];
+            let key = if linked.is_empty() {
+                positions[*v] as f64
+            } else {
+                linked.iter().map(|n| positions[*n] as f64).sum::<f64>() / linked.len() as f64
+            };
+
+            (key, positions[*v], *v)
+        })
+        .collect();
+
+    keyed.sort_by(|a, b| {
+        a.0.partial_cmp(&b.0)
+            .unwrap_or(Ordering::Equal)
+            .then(a.1.cmp(&b.1))
+    });
+
+    for (slot, (_, _, v)) in layer.iter_mut().zip(keyed) {
+        *slot = v;
+    }
+}
+
+fn crossings(layers: &[Vec<usize>], lowers: &[Vec<usize>], positions: &[usize]) -> usize {
+    let mut total = 0;
+
+    for layer in layers {
+        let links: Vec<(usize, usize)> = layer
+            .iter()
+            .flat_map(|v| {
+                lowers[*v]
+                    .iter()
+                    .map(move |w| (positions[*v], positions[*w]))
+            })
+            .collect();
+
+        for (i, a) in links.iter().enumerate() {
+            for b in &links[i + 1..] {
+                if (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1) {
+                    total += 1;
+                }
+            }
+        }
+    }
+
+    total
+}
+
+/// Places every vertex across its rank, keeping the order and the separation between neighbours.
+fn position(
+    layers: &[Vec<usize>],
+    vertices: &[Vertex],
+    uppers: &[Vec<usize>],
+    lowers: &[Vec<usize>],
+    separation: f64,
+) -> Vec<f64> {
+    let mut across = vec![0.0; vertices.len()];
+    let mut widest: f64 = 0.0;
+
+    for layer in layers {
+        let mut offset = 0.0;
+        for v in layer {
+            across[*v] = offset + vertices[*v].breadth / 2.0;
+            offset += vertices[*v].breadth + separation;
+        }
+        widest = widest.max(offset);
+    }
+
+    // Centre every rank on the widest one.
+    for layer in layers {
+        let width: f64 = layer
+            .iter()
+            .map(|v| vertices[*v].breadth + separation)
+            .sum();
+        let shift = (widest - width) / 2.0;
+
+        for v in layer {
+            across[*v] += shift;
+        }
+    }
+
+    for _ in 0..POSITION_SWEEPS {
+        for layer in layers.iter().skip(1) {
+            align(layer, vertices, uppers, separation, &mut across);
+        }
+        for layer in layers.iter().rev().skip(1) {
+            align(layer, vertices, lowers, separation, &mut across);
+        }
+    }
+
+    across
+}
+
+/// Moves each vertex towards the mean of its neighbours without breaking the order or the separation.
+///
+/// It averages the placement packed from the left with the one packed from the right, both feasible.
+fn align(
+    layer: &[usize],
+    vertices: &[Vertex],
+    neighbours: &[Vec<usize>],
+    separation: f64,
+    across: &mut [f64],
+) {
+    if layer.is_empty() {
+        return;
+    }
+
+    let desired: Vec<f64> = layer
+        .iter()
+        .map(|v| {
+            let linked = &neighbours[*v];
+
+            if linked.is_empty() {
+                across[*v]
+            } else {
+                linked.iter().map(|n| across[*n]).sum::<f64>() / linked.len() as f64
+            }
+        })
+        .collect();
+    let gap =
+        |i: usize| (vertices[layer[i - 1]].breadth + vertices[layer[i]].breadth) / 2.0 + separation;
+
+    let mut left = desired.clone();
+    for i in 1..layer.len() {
+        left[i] = left[i].max(left[i - 1] + gap(i));
+    }
+
+    let mut right = desired;
+    for i in (0..layer.len() - 1).rev() {
+        right[i] = right[i].min(right[i + 1] - gap(i + 1));
+    }
+
+    for (i, v) in layer.iter().enumerate() {
+        across[*v] = (left[i] + right[i]) / 2.0;
+    }
+}
+
+/// Which ends of the edge get an arrowhead: (tail, head).
+fn arrows(graph: &Graph, edge: &Edge) -> (bool, bool) {
+    let default = if graph.directed { "forward" } else { "none" };
+    let (tail, head) = match edge.attribute("dir").unwrap_or(default) {
+        "both" => (true, true),
+        "back" => (true, false),
+        "none" => (false, false),
+        _ => (false, true),
+    };
+
+    (
+        tail && edge.attribute("arrowtail") != Some("none"),
+        head && edge.attribute("arrowhead") != Some("none"),
+    )
+}
+
+/// The triangle for an arrowhead with its tip at the given point, and where the line should end.
+fn arrowhead(tip: Point, from: Point) -> ([Point; 3], Point) {
+    let direction = tip.sub(from).unit();
+    let base = tip.sub(direction.scale(ARROW_LENGTH));
+    let side = direction.perpendicular().scale(ARROW_WIDTH / 2.0);
+
+    ([tip, base.add(side), base.sub(side)], base)
+}
+
+/// How far an edge bends to stay clear of other edges between the same two nodes.
+fn multi_edge_offset(graph: &Graph, index: usize) -> f64 {
+    let edge = &graph.edges[index];
+    let pair = (edge.tail.min(edge.head), edge.tail.max(edge.head));
+    let siblings: Vec<usize> = graph
+        .edges
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| (e.tail.min(e.head), e.tail.max(e.head)) == pair)
+        .map(|(i, _)| i)
+        .collect();
+    let position = siblings.iter().position(|i| *i == index).unwrap_or(0);
+
+    (position as f64 - (siblings.len() - 1) as f64 / 2.0) * MULTI_EDGE_SEPARATION
+}
+
+/// Places a label next to the given point of an edge: to the right when ranks are stacked and above otherwise.
+fn edge_label(point: Point, horizontal: bool, size: Option<(f64, f64)>) -> Option<TextBox> {
+    size.map(|(width, height)| {
+        let center = if horizontal {
+            Point::new(point.x, point.y - LABEL_GAP - height / 2.0)
+        } else {
+            Point::new(point.x + LABEL_GAP + width / 2.0, point.y)
+        };
+
+        TextBox {
+            center,
+            width,
+            height,
+        }
+    })
+}
+
+fn clip(node: &NodeBox, toward: Point) -> Point {
+    node.shape.boundary(
+        node.center,
+        node.width / 2.0,
+        node.height / 2.0,
+        toward.sub(node.center),
+    )
+}
+
+fn direct_edge(
+    tail: &NodeBox,
+    head: &NodeBox,
+    offset: f64,
+    horizontal: bool,
+    label: Option<(f64, f64)>,
+    (tail_arrow, head_arrow): (bool, bool),
+) -> EdgePath {
+    // The bend is relative to the node that comes first so edges in opposite directions do not overlap.
+    let (first, second) = if tail.center.x + tail.center.y <= head.center.x + head.center.y {
+        (tail.center, head.center)
+    } else {
+        (head.center, tail.center)
+    };
+    let normal = second.sub(first).unit().perpendicular();
+    let control = tail
+        .center
+        .midpoint(head.center)
+        .add(normal.scale(2.0 * offset));
+
+    let mut start = clip(tail, control);
+    let mut end = clip(head, control);
+    let mut arrows = Vec::new();
+
+    if head_arrow {
+        let (triangle, base) = arrowhead(end, control);
+        arrows.push(triangle);
+        end = base;
+    }
+    if tail_arrow {
+        let (triangle, base) = arrowhead(start, control);
+        arrows.push(triangle);
+        start = base;
+    }
+
+    let (segment, middle) = if offset == 0.0 {
+        (Segment::Line(end), start.midpoint(end))
+    } else {
+        (
+            Segment::Quadratic(control, end),
+            start.midpoint(end).midpoint(control),
+        )
+    };
+
+    EdgePath {
+        start,
+        segments: vec![segment],
+        arrows,
+        label: edge_label(middle, horizontal, label),
+    }
+}
+
+/// An edge through virtual points, drawn as a line rounded at each of them.
+fn long_edge(
+    tail: &NodeBox,
+    head: &NodeBox,
+    points: &[Point],
+    horizontal: bool,
+    label: Option<(f64, f64)>,
+    (tail_arrow, head_arrow): (bool, bool),
+) -> EdgePath {
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let mut start = clip(tail, first);
+    let mut end = clip(head, last);
+    let mut arrows = Vec::new();
+
+    if head_arrow {
+        let (triangle, base) = arrowhead(end, last);
+        arrows.push(triangle);
+        end = base;
+    }
+    if tail_arrow {
+        let (triangle, base) = arrowhead(start, first);
+        arrows.push(triangle);
+        start = base;
+    }
+
+    let mut all = vec![start];
+    all.extend_from_slice(points);
+    all.push(end);
+
+    let mut segments = vec![Segment::Line(all[0].midpoint(all[1]))];
+    for i in 1..all.len() - 1 {
+        segments.push(Segment::Quadratic(all[i], all[i].midpoint(all[i + 1])));
+    }
+    segments.push(Segment::Line(end));
+
+    let middle = points.len() / 2;
+    let anchor = all[middle]
+        .midpoint(all[middle + 1])
+        .midpoint(all[middle + 1])
+        .midpoint(all[middle + 1].midpoint(all[middle + 2]));
+
+    EdgePath {
+        start,
+        segments,
+        arrows,
+        label: edge_label(anchor, horizontal, label),
+    }
+}
+
+/// A loop on the side of the node, further out for each additional loop.
+fn self_loop(
+    node: &NodeBox,
+    count: usize,
+    rank_dir: RankDir,
+    label: Option<(f64, f64)>,
+    (tail_arrow, head_arrow): (bool, bool),
+) -> EdgePath {
+    let horizontal = rank_dir.is_horizontal();
+    // Outwards across the rank and along the rank, in the drawing frame.
+    let (out, along) = if horizontal {
+        (Point::new(0.0, 1.0), Point::new(1.0, 0.0))
+    } else {
+        (Point::new(1.0, 0.0), Point::new(0.0, 1.0))
+    };
+    let half_out = if horizontal {
+        node.height / 2.0
+    } else {
+        node.width / 2.0
+    };
+    let half_along = if horizontal {
+        node.width / 2.0
+    } else {
+        node.height / 2.0
+    };
+    let reach = loop_reach(count);
+
+    let exit = node
+        .center
+        .add(out.scale(half_out * 0.85))
+        .sub(along.scale(half_along * 0.5));
+    let mut start = clip(node, exit);
+    let entry = node
+        .center
+        .add(out.scale(half_out * 0.85))
+        .add(along.scale(half_along * 0.5));
+    let mut end = clip(node, entry);
+
+    let control_start = start.add(out.scale(reach)).sub(along.scale(reach / 2.0));
+    let control_end = end.add(out.scale(reach)).add(along.scale(reach / 2.0));
+    let mut arrows = Vec::new();
+
+    if head_arrow {
+        let (triangle, base) = arrowhead(end, control_end);
+        arrows.push(triangle);
+        end = base;
+    }
+    if tail_arrow {
+        let (triangle, base) = arrowhead(start, control_start);
+        arrows.push(triangle);
+        start = base;
+    }
+
+    let tip = node.center.add(out.scale(half_out + reach * 0.75));
+    let label = label.map(|(width, height)| {
+        let extent = if horizontal { height } else { width };
+
+        TextBox {
+            center: tip.add(out.scale(LABEL_GAP + extent / 2.0)),
+            width,
+            height,
+        }
+    });
+
+    EdgePath {
+        start,
+        segments: vec![Segment::Cubic(control_start, control_end, end)],
+        arrows,
+        label,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::dot::parse;
+
+    fn layout(text: &str) -> Layout {
+        Layout::new(&parse(text).expect("valid dot"))
+    }
+
+    #[test]
+    fn rank_with_cycles() {
+        let graph = parse("digraph { a -> b -> c -> a; a -> c; c -> c }").expect("valid dot");
+        let (ranks, reversed) = rank(&graph);
+
+        assert_eq!(ranks, vec![0, 1, 2]);
+        assert_eq!(reversed, vec![false, false, true, false, false]);
+    }
+
+    #[test]
+    fn rank_direction() {
+        let stacked = layout("digraph { a -> b }");
+        let sideways = layout("digraph { rankdir = LR; a -> b }");
+        let upwards = layout("digraph { rankdir = BT; a -> b }");
+
+        assert_eq!(stacked.nodes[0].center.x, stacked.nodes[1].center.x);
+        assert!(stacked.nodes[0].center.y < stacked.nodes[1].center.y);
+        assert_eq!(sideways.nodes[0].center.y, sideways.nodes[1].center.y);
+        assert!(sideways.nodes[0].center.x < sideways.nodes[1].center.x);
+        assert!(upwards.nodes[0].center.y > upwards.nodes[1].center.y);
+        assert!(sideways.width > sideways.height);
+    }
+
+    #[test]
+    fn fit_in_margins() {
+        let layout = layout(
+            r#"digraph { a -> b [label = "a long label"]; a -> c; b -> d; c -> d; a -> d; d -> d }"#,
+        );
+
+        for node in &layout.nodes {
+            assert!(node.center.x - node.width / 2.0 >= MARGIN - 1e-9);
+            assert!(node.center.y - node.height / 2.0 >= MARGIN - 1e-9);
+            assert!(node.center.x + node.width / 2.0 <= layout.width - MARGIN + 1e-9);
+            assert!(node.center.y + node.height / 2.0 <= layout.height - MARGIN + 1e-9);
+        }
+    }
+
+    #[test]
+    fn separate_nodes_in_rank() {
+        let layout = layout("digraph { a -> b; a -> c; a -> d }");
+        let mut xs: Vec<f64> = layout.nodes[1..].iter().map(|n| n.center.x).collect();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        for pair in xs.windows(2) {
+            assert!(pair[1] - pair[0] >= 54.0 + NODE_SEPARATION - 1e-9);
+        }
+    }
+
+    #[test]
+    fn bend_parallel_edges() {
+        let layout = layout("digraph { a -> b; a -> b; b -> a }");
+
+        assert!(matches!(
+            layout.edges[0].segments[0],
+            Segment::Quadratic(..)
+        ));
+        assert!(matches!(layout.edges[1].segments[0], Segment::Line(..)));
+        assert_ne!(layout.edges[0].segments, layout.edges[2].segments);
+    }
+}
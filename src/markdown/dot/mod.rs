@@ -0,0 +1,160 @@
+//! This module renders DOT diagrams as SVG without leaving the process.
+//!
+//! It covers the subset of the DOT language used in the notes: `graph` and `digraph` with nodes, edges, attribute
+//! statements and the common attributes for labels, shapes, colours and direction. Anything outside the subset, such
+//! as subgraphs, ports or HTML labels, is an [`DotError::Unsupported`] error.
+//!
+//! The layout is a simplified layered layout so the same input always yields the same SVG, byte for byte.
+
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+mod layout;
+mod parser;
+mod svg;
+
+pub use layout::Layout;
+pub use parser::parse;
+
+/// The attributes attached to a graph, a node or an edge.
+pub type Attributes = BTreeMap<String, String>;
+
+/// An error parsing or rendering a DOT diagram.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum DotError {
+    #[error("dot: {message} at line {line}, column {column}")]
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("dot: {0} are not supported")]
+    Unsupported(String),
+}
+
+/// A parsed DOT graph.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Graph {
+    pub directed: bool,
+    pub id: Option<String>,
+    pub attributes: Attributes,
+    /// Nodes in order of first appearance.
+    pub nodes: Vec<Node>,
+    /// Edges in order of appearance.
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub id: String,
+    pub attributes: Attributes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    /// The index of the tail node.
+    pub tail: usize,
+    /// The index of the head node.
+    pub head: usize,
+    pub attributes: Attributes,
+}
+
+impl Graph {
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(|s| s.as_str())
+    }
+
+    /// The index of the node with the given id.
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node.id == id)
+    }
+}
+
+impl Node {
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(|s| s.as_str())
+    }
+
+    /// The label lines, defaulting to the node id.
+    pub fn label(&self) -> Vec<String> {
+        let label = self.attribute("label").unwrap_or("\\N");
+
+        split_label(&label.replace("\\N", &self.id))
+    }
+}
+
+impl Edge {
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(|s| s.as_str())
+    }
+
+    pub fn label(&self) -> Vec<String> {
+        self.attribute("label").map(split_label).unwrap_or_default()
+    }
+}
+
+/// Renders the given DOT text as an SVG document.
+pub fn render(input: &str) -> Result<String, DotError> {
+    let graph = parse(input)?;
+    let layout = Layout::new(&graph);
+
+    Ok(svg::write(&graph, &layout))
+}
+
+/// Splits a label on the DOT line breaks: `\n`, `\l` and `\r`.
+///
+/// All lines are centred regardless of the escape used.
+fn split_label(label: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut chars = label.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('n')) | ('\\', Some('l')) | ('\\', Some('r')) => {
+                chars.next();
+                lines.push(line);
+                line = String::new();
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                line.push('\\');
+            }
+            _ => line.push(c),
+        }
+    }
+
+    // A trailing break does not add an empty line.
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_lines() {
+        assert_eq!(split_label("one"), vec!["one"]);
+        assert_eq!(split_label("one\\ntwo\\l"), vec!["one", "two"]);
+        assert_eq!(split_label(""), vec![""]);
+    }
+
+    #[test]
+    fn deterministic() -> Result<(), DotError> {
+        let text = r#"
+digraph g {
+  rankdir = LR;
+  A -> B -> C;
+  C -> A [ label = "back" ];
+  A -> C;
+}"#;
+
+        assert_eq!(render(text)?, render(text)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,661 @@
+//! This module parses DOT text into a [`Graph`].
+
+use super::{Attributes, DotError, Edge, Graph, Node};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier, numeral or quoted string. Only unquoted ones can be keywords.
+    Id {
+        value: String,
+        quoted: bool,
+    },
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Equal,
+    Semicolon,
+    Comma,
+    Colon,
+    Plus,
+    DirectedEdge,
+    UndirectedEdge,
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match self {
+            Token::Id {
+                value,
+                quoted: false,
+            } => value.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Token::Id { value, .. } => format!("'{}'", value),
+            Token::LeftBrace => "'{'".to_string(),
+            Token::RightBrace => "'}'".to_string(),
+            Token::LeftBracket => "'['".to_string(),
+            Token::RightBracket => "']'".to_string(),
+            Token::Equal => "'='".to_string(),
+            Token::Semicolon => "';'".to_string(),
+            Token::Comma => "','".to_string(),
+            Token::Colon => "':'".to_string(),
+            Token::Plus => "'+'".to_string(),
+            Token::DirectedEdge => "'->'".to_string(),
+            Token::UndirectedEdge => "'--'".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+/// Parses the given DOT text.
+pub fn parse(input: &str) -> Result<Graph, DotError> {
+    let tokens = tokenize(input)?;
+    let end = tokens
+        .last()
+        .map(|(_, position)| *position)
+        .unwrap_or(Position { line: 1, column: 1 });
+    let mut parser = Parser {
+        tokens,
+        cursor: 0,
+        end,
+        graph: Graph::default(),
+        node_defaults: Attributes::new(),
+        edge_defaults: Attributes::new(),
+    };
+
+    parser.graph()?;
+
+    Ok(parser.graph)
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, Position)>, DotError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut line = 1;
+    let mut column = 1;
+
+    // Advances the cursor keeping track of lines and columns.
+    let advance = |i: &mut usize, line: &mut usize, column: &mut usize| {
+        if chars[*i] == '\n' {
+            *line += 1;
+            *column = 1;
+        } else {
+            *column += 1;
+        }
+        *i += 1;
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let position = Position { line, column };
+
+        if c.is_whitespace() {
+            advance(&mut i, &mut line, &mut column);
+            continue;
+        }
+
+        // Line comments, including the `#` preprocessor lines.
+        if c == '#' || (c == '/' && next == Some('/')) {
+            while i < chars.len() && chars[i] != '\n' {
+                advance(&mut i, &mut line, &mut column);
+            }
+            continue;
+        }
+
+        if c == '/' && next == Some('*') {
+            advance(&mut i, &mut line, &mut column);
+            advance(&mut i, &mut line, &mut column);
+
+            loop {
+                if i >= chars.len() {
+                    return Err(syntax(position, "unterminated comment"));
+                }
+                if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    advance(&mut i, &mut line, &mut column);
+                    advance(&mut i, &mut line, &mut column);
+                    break;
+                }
+                advance(&mut i, &mut line, &mut column);
+            }
+            continue;
+        }
+
+        let punctuation = match c {
+            '{' => Some(Token::LeftBrace),
+            '}' => Some(Token::RightBrace),
+            '[' => Some(Token::LeftBracket),
+            ']' => Some(Token::RightBracket),
+            '=' => Some(Token::Equal),
+            ';' => Some(Token::Semicolon),
+            ',' => Some(Token::Comma),
+            ':' => Some(Token::Colon),
+            '+' => Some(Token::Plus),
+            _ => None,
+        };
+
+        if let Some(token) = punctuation {
+            tokens.push((token, position));
+            advance(&mut i, &mut line, &mut column);
+            continue;
+        }
+
+        if c == '-' && (next == Some('>') || next == Some('-')) {
+            let token = if next == Some('>') {
+                Token::DirectedEdge
+            } else {
+                Token::UndirectedEdge
+            };
+            tokens.push((token, position));
+            advance(&mut i, &mut line, &mut column);
+            advance(&mut i, &mut line, &mut column);
+            continue;
+        }
+
+        if c == '"' {
+            let mut value = String::new();
+            advance(&mut i, &mut line, &mut column);
+
+            loop {
+                match chars.get(i) {
+                    None => return Err(syntax(position, "unterminated string")),
+                    Some('"') => {
+                        advance(&mut i, &mut line, &mut column);
+                        break;
+                    }
+                    Some('\\') if chars.get(i + 1) == Some(&'"') => {
+                        value.push('"');
+                        advance(&mut i, &mut line, &mut column);
+                        advance(&mut i, &mut line, &mut column);
+                    }
+                    // A backslash before a newline continues the string on the next line.
+                    Some('\\') if chars.get(i + 1) == Some(&'\n') => {
+                        advance(&mut i, &mut line, &mut column);
+                        advance(&mut i, &mut line, &mut column);
+                    }
+                    Some(&c) => {
+                        value.push(c);
+                        advance(&mut i, &mut line, &mut column);
+                    }
+                }
+            }
+
+            tokens.push((
+                Token::Id {
+                    value,
+                    quoted: true,
+                },
+                position,
+            ));
+            continue;
+        }
+
+        if c == '<' {
+            return Err(DotError::Unsupported("HTML labels".to_string()));
+        }
+
+        if c == '-' || c == '.' || c.is_ascii_digit() {
+            let mut value = String::new();
+
+            if c == '-' {
+                value.push(c);
+                advance(&mut i, &mut line, &mut column);
+            }
+
+            let mut seen_dot = false;
+            while let Some(&c) = chars.get(i) {
+                if c.is_ascii_digit() || (c == '.' && !seen_dot) {
+                    seen_dot = seen_dot || c == '.';
+                    value.push(c);
+                    advance(&mut i, &mut line, &mut column);
+                } else {
+                    break;
+                }
+            }
+
+            if value == "-" || value == "." || value == "-." {
+                return Err(syntax(position, &format!("unexpected '{}'", value)));
+            }
+
+            tokens.push((
+                Token::Id {
+                    value,
+                    quoted: false,
+                },
+                position,
+            ));
+            continue;
+        }
+
+        if is_id_start(c) {
+            let mut value = String::new();
+
+            while let Some(&c) = chars.get(i) {
+                if is_id_start(c) || c.is_ascii_digit() {
+                    value.push(c);
+                    advance(&mut i, &mut line, &mut column);
+                } else {
+                    break;
+                }
+            }
+
+            tokens.push((
+                Token::Id {
+                    value,
+                    quoted: false,
+                },
+                position,
+            ));
+            continue;
+        }
+
+        return Err(syntax(position, &format!("unexpected '{}'", c)));
+    }
+
+    Ok(tokens)
+}
+
+fn is_id_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn syntax(position: Position, message: &str) -> DotError {
+    DotError::Syntax {
+        line: position.line,
+        column: position.column,
+        message: message.to_string(),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    cursor: usize,
+    /// The position of the last token, used to report an unexpected end.
+    end: Position,
+    graph: Graph,
+    /// The attributes set by `node [...]` so far. They apply to nodes created from then on.
+    node_defaults: Attributes,
+    /// The attributes set by `edge [...]` so far. They apply to edges created from then on.
+    edge_defaults: Attributes,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor).map(|(token, _)| token)
+    }
+
+    fn peek_next(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor + 1).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> Position {
+        self.tokens
+            .get(self.cursor)
+            .map(|(_, position)| *position)
+            .unwrap_or(self.end)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.cursor).map(|(token, _)| token.clone());
+        self.cursor += 1;
+
+        token
+    }
+
+    fn unexpected(&self, expected: &str) -> DotError {
+        let message = match self.peek() {
+            Some(token) => format!("expected {} but found {}", expected, token.describe()),
+            None => format!("expected {} but the input ended", expected),
+        };
+
+        syntax(self.position(), &message)
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> Result<(), DotError> {
+        if self.peek() == Some(&token) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn eat(&mut self, token: Token) -> bool {
+        if self.peek() == Some(&token) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes an identifier, concatenating quoted strings joined with `+`.
+    fn id(&mut self, expected: &str) -> Result<String, DotError> {
+        if !matches!(self.peek(), Some(Token::Id { .. })) {
+            return Err(self.unexpected(expected));
+        }
+
+        let mut value = match self.bump() {
+            Some(Token::Id { value, quoted }) => {
+                if !quoted {
+                    return Ok(value);
+                }
+                value
+            }
+            _ => unreachable!(),
+        };
+
+        while self.peek() == Some(&Token::Plus) {
+            self.bump();
+            match self.bump() {
+                Some(Token::Id {
+                    value: more,
+                    quoted: true,
+                }) => value.push_str(&more),
+                _ => {
+                    self.cursor -= 1;
+                    return Err(self.unexpected("a quoted string after '+'"));
+                }
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn graph(&mut self) -> Result<(), DotError> {
+        if self.peek().map(|t| t.is_keyword("strict")).unwrap_or(false) {
+            self.bump();
+        }
+
+        match self.peek() {
+            Some(token) if token.is_keyword("digraph") => self.graph.directed = true,
+            Some(token) if token.is_keyword("graph") => self.graph.directed = false,
+            _ => return Err(self.unexpected("'graph' or 'digraph'")),
+        }
+        self.bump();
+
+        if let Some(Token::Id { .. }) = self.peek() {
+            self.graph.id = Some(self.id("a graph id")?);
+        }
+
+        self.expect(Token::LeftBrace, "'{'")?;
+
+        while self.peek() != Some(&Token::RightBrace) {
+            if self.peek().is_none() {
+                return Err(self.unexpected("'}'"));
+            }
+
+            self.statement()?;
+            self.eat(Token::Semicolon);
+        }
+        self.bump();
+
+        if self.peek().is_some() {
+            return Err(self.unexpected("the end of the input"));
+        }
+
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), DotError> {
+        let token = self.peek().cloned();
+
+        match token {
+            Some(Token::LeftBrace) => Err(DotError::Unsupported("subgraphs".to_string())),
+            Some(token) if token.is_keyword("subgraph") => {
+                Err(DotError::Unsupported("subgraphs".to_string()))
+            }
+            Some(token)
+                if token.is_keyword("graph")
+                    || token.is_keyword("node")
+                    || token.is_keyword("edge") =>
+            {
+                self.bump();
+                let attributes = self.attribute_lists()?;
+                let target = if token.is_keyword("graph") {
+                    &mut self.graph.attributes
+                } else if token.is_keyword("node") {
+                    &mut self.node_defaults
+                } else {
+                    &mut self.edge_defaults
+                };
+                target.extend(attributes);
+
+                Ok(())
+            }
+            Some(Token::Id { .. }) if self.peek_next() == Some(&Token::Equal) => {
+                let key = self.id("an attribute name")?;
+                self.bump();
+                let value = self.id("an attribute value")?;
+                self.graph.attributes.insert(key, value);
+
+                Ok(())
+            }
+            Some(Token::Id { .. }) => self.node_or_edge(),
+            _ => Err(self.unexpected("a statement")),
+        }
+    }
+
+    fn node_or_edge(&mut self) -> Result<(), DotError> {
+        let mut ids = vec![self.node_id()?];
+
+        loop {
+            match self.peek() {
+                Some(Token::DirectedEdge) if !self.graph.directed => {
+                    return Err(syntax(self.position(), "'->' in an undirected graph"));
+                }
+                Some(Token::UndirectedEdge) if self.graph.directed => {
+                    return Err(syntax(self.position(), "'--' in a directed graph"));
+                }
+                Some(Token::DirectedEdge) | Some(Token::UndirectedEdge) => {
+                    self.bump();
+                    if self.peek() == Some(&Token::LeftBrace)
+                        || self
+                            .peek()
+                            .map(|t| t.is_keyword("subgraph"))
+                            .unwrap_or(false)
+                    {
+                        return Err(DotError::Unsupported("subgraphs".to_string()));
+                    }
+                    ids.push(self.node_id()?);
+                }
+                _ => break,
+            }
+        }
+
+        let attributes = if self.peek() == Some(&Token::LeftBracket) {
+            self.attribute_lists()?
+        } else {
+            Attributes::new()
+        };
+
+        if ids.len() == 1 {
+            let index = self.ensure_node(&ids[0]);
+            self.graph.nodes[index].attributes.extend(attributes);
+        } else {
+            let indices: Vec<usize> = ids.iter().map(|id| self.ensure_node(id)).collect();
+
+            for pair in indices.windows(2) {
+                let mut edge_attributes = self.edge_defaults.clone();
+                edge_attributes.extend(attributes.clone());
+
+                self.graph.edges.push(Edge {
+                    tail: pair[0],
+                    head: pair[1],
+                    attributes: edge_attributes,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn node_id(&mut self) -> Result<String, DotError> {
+        let id = self.id("a node id")?;
+
+        if self.peek() == Some(&Token::Colon) {
+            return Err(DotError::Unsupported("ports".to_string()));
+        }
+
+        Ok(id)
+    }
+
+    /// Returns the index of the node, creating it with the current defaults when it is new.
+    fn ensure_node(&mut self, id: &str) -> usize {
+        match self.graph.position(id) {
+            Some(index) => index,
+            None => {
+                self.graph.nodes.push(Node {
+                    id: id.to_string(),
+                    attributes: self.node_defaults.clone(),
+                });
+
+                self.graph.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Takes one or more consecutive `[...]` lists.
+    fn attribute_lists(&mut self) -> Result<Attributes, DotError> {
+        let mut attributes = Attributes::new();
+
+        self.expect(Token::LeftBracket, "'['")?;
+        loop {
+            while self.peek() != Some(&Token::RightBracket) {
+                let key = self.id("an attribute name")?;
+                self.expect(Token::Equal, "'='")?;
+                let value = self.id("an attribute value")?;
+                attributes.insert(key, value);
+
+                if !self.eat(Token::Comma) {
+                    self.eat(Token::Semicolon);
+                }
+            }
+            self.bump();
+
+            if !self.eat(Token::LeftBracket) {
+                break;
+            }
+        }
+
+        Ok(attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_example() -> Result<(), DotError> {
+        let text = r##"
+digraph g {
+  bgcolor="#ffffff00" # RGBA (with alpha);
+  rankdir = LR;
+
+  A [ label = "https://example.org/foo.html", shape = egg ];
+  "text/csv" [ shape = box ];
+
+  A -> B [ label = "alternate" ];
+  B -> "text/csv" [ label = "type" ];
+}"##;
+        let graph = parse(text)?;
+
+        assert!(graph.directed);
+        assert_eq!(graph.id, Some("g".to_string()));
+        assert_eq!(graph.attribute("bgcolor"), Some("#ffffff00"));
+        assert_eq!(graph.attribute("rankdir"), Some("LR"));
+        assert_eq!(
+            graph
+                .nodes
+                .iter()
+                .map(|n| n.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["A", "text/csv", "B"]
+        );
+        assert_eq!(
+            graph.nodes[0].attribute("label"),
+            Some("https://example.org/foo.html")
+        );
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!((graph.edges[1].tail, graph.edges[1].head), (2, 1));
+        assert_eq!(graph.edges[1].attribute("label"), Some("type"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_defaults_and_chains() -> Result<(), DotError> {
+        let text = r#"
+/* A block
+   comment. */
+graph {
+  node [shape=box] [color=red];
+  edge [style=dashed]
+  a -- b -- c // chained
+  node [shape=circle]
+  d
+  graph [label="A " + "graph"]
+}"#;
+        let graph = parse(text)?;
+
+        assert!(!graph.directed);
+        assert_eq!(graph.id, None);
+        assert_eq!(graph.attribute("label"), Some("A graph"));
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.nodes[2].attribute("shape"), Some("box"));
+        assert_eq!(graph.nodes[2].attribute("color"), Some("red"));
+        assert_eq!(graph.nodes[3].attribute("shape"), Some("circle"));
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[1].attribute("style"), Some("dashed"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("digraph {\n  a -- b\n}"),
+            Err(DotError::Syntax {
+                line: 2,
+                column: 5,
+                message: "'--' in a directed graph".to_string()
+            })
+        );
+        assert_eq!(
+            parse("digraph { a -> }"),
+            Err(DotError::Syntax {
+                line: 1,
+                column: 16,
+                message: "expected a node id but found '}'".to_string()
+            })
+        );
+        assert_eq!(
+            parse("digraph { a -> b"),
+            Err(DotError::Syntax {
+                line: 1,
+                column: 16,
+                message: "expected '}' but the input ended".to_string()
+            })
+        );
+        assert_eq!(
+            parse("digraph { subgraph x { a } }"),
+            Err(DotError::Unsupported("subgraphs".to_string()))
+        );
+        assert_eq!(
+            parse("digraph { a:n -> b }"),
+            Err(DotError::Unsupported("ports".to_string()))
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! This module writes a laid out [`Graph`] as SVG.
+//!
+//! The output follows the structure Graphviz uses, a `g` with class `node` or `edge` and a `title` for each element,
+//! so existing stylesheets keep working. It has no `id` attributes as many diagrams can be inlined in the same page.
+
+use pulldown_cmark::escape::escape_html;
+
+use super::layout::{self, Layout, Point, Segment, Shape, TextBox};
+use super::{Attributes, Graph};
+
+const FONT_FAMILY: &str = "Times,serif";
+
+/// Writes the graph with the given layout as a standalone `svg` element without blank lines, safe to inline in
+/// Markdown.
+pub fn write(graph: &Graph, layout: &Layout) -> String {
+    let mut svg = String::new();
+
+    svg.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}pt\" height=\"{h}pt\" viewBox=\"0 0 {w} {h}\">\n",
+        w = num(layout.width),
+        h = num(layout.height)
+    ));
+    svg.push_str("<g class=\"graph\">\n");
+
+    if let Some(id) = &graph.id {
+        svg.push_str(&format!("<title>{}</title>\n", escape(id)));
+    }
+
+    let background = graph.attribute("bgcolor").unwrap_or("white");
+    svg.push_str(&format!(
+        "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"none\"/>\n",
+        num(layout.width),
+        num(layout.height),
+        escape(background)
+    ));
+
+    for (node, geometry) in graph.nodes.iter().zip(&layout.nodes) {
+        let style = Style::new(&node.attributes);
+
+        if style.invisible {
+            continue;
+        }
+
+        svg.push_str("<g class=\"node\">\n");
+        svg.push_str(&format!("<title>{}</title>\n", escape(&node.id)));
+
+        let paint = style.paint(&node.attributes, true);
+        let (cx, cy) = (num(geometry.center.x), num(geometry.center.y));
+        let (rx, ry) = (geometry.width / 2.0, geometry.height / 2.0);
+
+        match geometry.shape {
+            Shape::Ellipse | Shape::Circle => svg.push_str(&format!(
+                "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"{}/>\n",
+                cx,
+                cy,
+                num(rx),
+                num(ry),
+                paint
+            )),
+            Shape::Point => svg.push_str(&format!(
+                "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"{}/>\n",
+                cx,
+                cy,
+                num(rx),
+                num(ry),
+                style.paint_point(&node.attributes)
+            )),
+            Shape::Box => svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>\n",
+                num(geometry.center.x - rx),
+                num(geometry.center.y - ry),
+                num(geometry.width),
+                num(geometry.height),
+                paint
+            )),
+            Shape::Diamond => {
+                let c = geometry.center;
+                let points = [
+                    Point::new(c.x, c.y - ry),
+                    Point::new(c.x + rx, c.y),
+                    Point::new(c.x, c.y + ry),
+                    Point::new(c.x - rx, c.y),
+                ];
+
+                svg.push_str(&format!(
+                    "<polygon points=\"{}\"{}/>\n",
+                    points_list(&points),
+                    paint
+                ));
+            }
+            Shape::Plain => (),
+        }
+
+        if geometry.shape != Shape::Point {
+            let text = TextBox {
+                center: geometry.center,
+                width: geometry.width,
+                height: geometry.height,
+            };
+
+            write_text(&mut svg, &node.label(), &text, &node.attributes);
+        }
+
+        svg.push_str("</g>\n");
+    }
+
+    let connector = if graph.directed { "->" } else { "--" };
+
+    for (edge, geometry) in graph.edges.iter().zip(&layout.edges) {
+        let style = Style::new(&edge.attributes);
+
+        if style.invisible {
+            continue;
+        }
+
+        svg.push_str("<g class=\"edge\">\n");
+        svg.push_str(&format!(
+            "<title>{}</title>\n",
+            escape(&format!(
+                "{}{}{}",
+                graph.nodes[edge.tail].id, connector, graph.nodes[edge.head].id
+            ))
+        ));
+
+        let mut d = format!("M{}", point(geometry.start));
+        for segment in &geometry.segments {
+            match segment {
+                Segment::Line(p) => d.push_str(&format!(" L{}", point(*p))),
+                Segment::Quadratic(c, p) => d.push_str(&format!(" Q{} {}", point(*c), point(*p))),
+                Segment::Cubic(c1, c2, p) => {
+                    d.push_str(&format!(" C{} {} {}", point(*c1), point(*c2), point(*p)))
+                }
+            }
+        }
+
+        svg.push_str(&format!(
+            "<path d=\"{}\"{}/>\n",
+            d,
+            style.paint(&edge.attributes, false)
+        ));
+
+        for arrow in &geometry.arrows {
+            svg.push_str(&format!(
+                "<polygon points=\"{}\"{}/>\n",
+                points_list(arrow),
+                style.paint_arrow(&edge.attributes)
+            ));
+        }
+
+        if let Some(label) = &geometry.label {
+            write_text(&mut svg, &edge.label(), label, &edge.attributes);
+        }
+
+        svg.push_str("</g>\n");
+    }
+
+    if let Some(label) = &layout.label {
+        let lines = super::split_label(graph.attribute("label").unwrap_or_default());
+        write_text(&mut svg, &lines, label, &graph.attributes);
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+
+    svg
+}
+
+/// The drawing style for a node or an edge.
+#[derive(Debug, Default)]
+struct Style {
+    filled: bool,
+    invisible: bool,
+    dash: Option<&'static str>,
+    bold: bool,
+}
+
+impl Style {
+    fn new(attributes: &Attributes) -> Self {
+        let mut style = Style::default();
+
+        for part in attributes
+            .get("style")
+            .map(|s| s.as_str())
+            .unwrap_or_default()
+            .split(',')
+        {
+            match part.trim() {
+                "filled" => style.filled = true,
+                "invis" => style.invisible = true,
+                "dashed" => style.dash = Some("5,2"),
+                "dotted" => style.dash = Some("1,5"),
+                "bold" => style.bold = true,
+                _ => (),
+            }
+        }
+
+        style
+    }
+
+    fn stroke_width(&self, attributes: &Attributes) -> Option<String> {
+        attributes
+            .get("penwidth")
+            .and_then(|s| s.parse::<f64>().ok())
+            .map(num)
+            .or_else(|| {
+                if self.bold {
+                    Some("2".to_string())
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// The fill and stroke attributes. Only closed shapes get a fill.
+    fn paint(&self, attributes: &Attributes, closed: bool) -> String {
+        let color = attributes.get("color").map(|s| s.as_str());
+        let fill = if closed && self.filled {
+            attributes
+                .get("fillcolor")
+                .map(|s| s.as_str())
+                .or(color)
+                .unwrap_or("lightgrey")
+        } else {
+            "none"
+        };
+        let mut paint = format!(
+            " fill=\"{}\" stroke=\"{}\"",
+            escape(fill),
+            escape(color.unwrap_or("black"))
+        );
+
+        if let Some(width) = self.stroke_width(attributes) {
+            paint.push_str(&format!(" stroke-width=\"{}\"", width));
+        }
+        if let Some(dash) = self.dash {
+            paint.push_str(&format!(" stroke-dasharray=\"{}\"", dash));
+        }
+
+        paint
+    }
+
+    fn paint_arrow(&self, attributes: &Attributes) -> String {
+        let color = escape(
+            attributes
+                .get("color")
+                .map(|s| s.as_str())
+                .unwrap_or("black"),
+        );
+        let mut paint = format!(" fill=\"{}\" stroke=\"{}\"", color, color);
+
+        if let Some(width) = self.stroke_width(attributes) {
+            paint.push_str(&format!(" stroke-width=\"{}\"", width));
+        }
+
+        paint
+    }
+
+    /// Points are always filled, with the fill colour if given.
+    fn paint_point(&self, attributes: &Attributes) -> String {
+        let color = attributes
+            .get("color")
+            .map(|s| s.as_str())
+            .unwrap_or("black");
+        let fill = attributes
+            .get("fillcolor")
+            .map(|s| s.as_str())
+            .unwrap_or(color);
+
+        format!(" fill=\"{}\" stroke=\"{}\"", escape(fill), escape(color))
+    }
+}
+
+/// Writes each line centred in the box.
+fn write_text(svg: &mut String, lines: &[String], text: &TextBox, attributes: &Attributes) {
+    let font_size = layout::font_size(attributes);
+    let line_height = layout::line_height(font_size);
+    let family = attributes
+        .get("fontname")
+        .map(|s| s.as_str())
+        .unwrap_or(FONT_FAMILY);
+    let fill = attributes
+        .get("fontcolor")
+        .map(|color| format!(" fill=\"{}\"", escape(color)))
+        .unwrap_or_default();
+    let first = text.center.y - (lines.len() - 1) as f64 * line_height / 2.0;
+
+    for (i, line) in lines.iter().enumerate() {
+        // Baselines sit a third of the font size below the middle of each line.
+        let baseline = first + i as f64 * line_height + font_size * 0.3;
+
+        svg.push_str(&format!(
+            "<text text-anchor=\"middle\" x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\"{}>{}</text>\n",
+            num(text.center.x),
+            num(baseline),
+            escape(family),
+            num(font_size),
+            fill,
+            escape(line)
+        ));
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    escape_html(&mut escaped, text).expect("writing to a string to succeed");
+
+    escaped
+}
+
+/// Formats a number with at most two decimals and no trailing zeros.
+fn num(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match trimmed {
+        "-0" | "" => "0".to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+fn point(p: Point) -> String {
+    format!("{},{}", num(p.x), num(p.y))
+}
+
+fn points_list(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|p| point(*p))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::dot::render;
+
+    #[test]
+    fn format_numbers() {
+        assert_eq!(num(1.0), "1");
+        assert_eq!(num(1.5), "1.5");
+        assert_eq!(num(1.256), "1.26");
+        assert_eq!(num(-0.001), "0");
+        assert_eq!(num(10.0), "10");
+    }
+
+    #[test]
+    fn write_graph() {
+        let text = r##"
+digraph g {
+  bgcolor = "#ffffff00";
+  A [ shape = box, style = filled, fillcolor = "#eeeeee" ];
+  A -> "<B>" [ label = "a & b" ];
+  C [ style = invis ];
+}"##;
+        let svg = render(text).expect("valid dot");
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("<title>g</title>"));
+        assert!(svg.contains("fill=\"#ffffff00\""));
+        assert!(svg.contains("<rect x="));
+        assert!(svg.contains("fill=\"#eeeeee\" stroke=\"black\""));
+        assert!(svg.contains("<title>A-&gt;&lt;B&gt;</title>"));
+        assert!(svg.contains(">a &amp; b</text>"));
+        assert!(!svg.contains("<title>C</title>"));
+        assert_eq!(svg.matches("<g class=\"node\">").count(), 2);
+        assert!(!svg.contains("\n\n"));
+    }
+}
@@ -69,6 +69,8 @@ where
 pub enum ExtractError {
     #[error("Could not find a title in the given markdown text.")]
     NotFound,
+    #[error("Could not find a frontmatter in the given markdown text.")]
+    FrontmatterNotFound,
 }
 
 /// Extracts the title (i.e. first h1) from a markdown text.
@@ -88,27 +90,19 @@ pub fn take_title(text: &str) -> Result<String> {
             Event::End(Tag::Heading(HeadingLevel::H1, _, _)) => {
                 extract.finish();
             }
-            Event::Start(Tag::Emphasis) | Event::End(Tag::Emphasis) => {
-                if extract.is_active() {
-                    extract.append("_")?;
-                }
+            Event::Start(Tag::Emphasis) | Event::End(Tag::Emphasis) if extract.is_active() => {
+                extract.append("_")?;
             }
-            Event::Start(Tag::Strong) | Event::End(Tag::Strong) => {
-                if extract.is_active() {
-                    extract.append("**")?;
-                }
+            Event::Start(Tag::Strong) | Event::End(Tag::Strong) if extract.is_active() => {
+                extract.append("**")?;
             }
-            Event::Code(ref text) => {
-                if extract.is_active() {
-                    extract.append("`")?;
-                    extract.append(text)?;
-                    extract.append("`")?;
-                }
+            Event::Code(ref text) if extract.is_active() => {
+                extract.append("`")?;
+                extract.append(text)?;
+                extract.append("`")?;
             }
-            Event::Text(ref text) => {
-                if extract.is_active() {
-                    extract.append(text)?;
-                }
+            Event::Text(ref text) if extract.is_active() => {
+                extract.append(text)?;
             }
             _ => (),
         }
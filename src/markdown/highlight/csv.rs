@@ -0,0 +1,52 @@
+//! This module highlights CSV, colouring each column in turn.
+
+use super::scanner::Scanner;
+use super::{Class, Span};
+
+pub fn scan(text: &str) -> Vec<Span> {
+    let mut s = Scanner::new(text);
+    let mut column = 0;
+
+    while let Some(c) = s.peek() {
+        let start = s.pos();
+
+        match c {
+            ',' => {
+                s.bump();
+                column += 1;
+            }
+            '\n' | '\r' => {
+                s.bump();
+                column = 0;
+            }
+            _ => {
+                if c == '"' {
+                    // Quoted fields can hold commas, line breaks and doubled quotes.
+                    s.string('"', false);
+                    while s.peek() == Some('"') {
+                        s.string('"', false);
+                    }
+                }
+                s.eat_while(|c| c != ',' && c != '\n' && c != '\r');
+                s.emit(Class::Column(column), start);
+            }
+        }
+    }
+
+    s.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::highlight::{render, Language};
+
+    #[test]
+    fn scan_csv() {
+        let actual = render(Language::Csv, "a,\"b,\"\"c\"\"\",,d,e\n1,2\n");
+
+        assert_eq!(
+            actual,
+            "<span class=\"hl-column-0\">a</span>,<span class=\"hl-column-1\">&quot;b,&quot;&quot;c&quot;&quot;&quot;</span>,,<span class=\"hl-column-3\">d</span>,<span class=\"hl-column-0\">e</span>\n<span class=\"hl-column-0\">1</span>,<span class=\"hl-column-1\">2</span>\n"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! This module highlights JSON.
+
+use super::scanner::Scanner;
+use super::{Class, Span};
+
+pub fn scan(text: &str) -> Vec<Span> {
+    let mut s = Scanner::new(text);
+
+    while let Some(c) = s.peek() {
+        let start = s.pos();
+
+        if c == '"' {
+            s.string('"', true);
+
+            // A string followed by a colon is an object key.
+            let next = s.rest().chars().find(|c| !c.is_whitespace());
+            let class = if next == Some(':') {
+                Class::Key
+            } else {
+                Class::String
+            };
+            s.emit(class, start);
+        } else if c == '-' || c.is_ascii_digit() {
+            s.bump();
+            s.number();
+            s.emit(Class::Number, start);
+        } else if c.is_ascii_alphabetic() {
+            let word = s.eat_while(|c| c.is_ascii_alphanumeric());
+
+            if matches!(word, "true" | "false" | "null") {
+                s.emit(Class::Literal, start);
+            }
+        } else {
+            s.bump();
+        }
+    }
+
+    s.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::highlight::{render, Language};
+
+    #[test]
+    fn scan_json() {
+        let actual = render(
+            Language::Json,
+            r#"{"id": "a-note", "size": -1.5e+3, "tags": [], "draft": false}"#,
+        );
+
+        assert_eq!(
+            actual,
+            "{<span class=\"hl-key\">&quot;id&quot;</span>: <span class=\"hl-string\">&quot;a-note&quot;</span>, <span class=\"hl-key\">&quot;size&quot;</span>: <span class=\"hl-number\">-1.5e+3</span>, <span class=\"hl-key\">&quot;tags&quot;</span>: [], <span class=\"hl-key\">&quot;draft&quot;</span>: <span class=\"hl-literal\">false</span>}"
+        );
+    }
+}
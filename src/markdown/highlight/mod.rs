@@ -0,0 +1,291 @@
+//! This module highlights code blocks at build time.
+//!
+//! Each supported language has a small hand-written scanner that marks the interesting parts of the code with a
+//! [`Class`]. The output is plain HTML with a `span` per marked part so any page or feed reader gets the same markup
+//! and the colours are left to a stylesheet, see [`stylesheet`].
+
+use pulldown_cmark::escape::escape_html;
+use std::fmt;
+use std::str::FromStr;
+
+mod csv;
+mod json;
+mod rust;
+mod scanner;
+mod sh;
+mod sql;
+mod toml;
+mod yaml;
+
+/// The prefix for every class name so highlighting does not clash with the site styles.
+const PREFIX: &str = "hl-";
+
+/// The file name the stylesheet is written as, in the static files.
+pub const STYLESHEET_FILENAME: &str = "highlight.css";
+
+/// The number of column classes CSV cycles through.
+const COLUMNS: usize = 4;
+
+/// The languages with a highlighter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Csv,
+    Json,
+    Rust,
+    Sh,
+    Sql,
+    Toml,
+    Yaml,
+}
+
+impl FromStr for Language {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Language::Csv),
+            "json" => Ok(Language::Json),
+            "rust" | "rs" => Ok(Language::Rust),
+            "sh" | "bash" | "shell" => Ok(Language::Sh),
+            "sql" | "sqlite" => Ok(Language::Sql),
+            "toml" => Ok(Language::Toml),
+            "yaml" | "yml" => Ok(Language::Yaml),
+            s => anyhow::bail!(
+                "unknown language '{}'. Expected one of: csv, json, rust, sh, sql, toml, yaml",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Language::Csv => "csv",
+            Language::Json => "json",
+            Language::Rust => "rust",
+            Language::Sh => "sh",
+            Language::Sql => "sql",
+            Language::Toml => "toml",
+            Language::Yaml => "yaml",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl Language {
+    fn scan(&self, text: &str) -> Vec<Span> {
+        match self {
+            Language::Csv => csv::scan(text),
+            Language::Json => json::scan(text),
+            Language::Rust => rust::scan(text),
+            Language::Sh => sh::scan(text),
+            Language::Sql => sql::scan(text),
+            Language::Toml => toml::scan(text),
+            Language::Yaml => yaml::scan(text),
+        }
+    }
+}
+
+/// What a marked part of the code is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Class {
+    Attribute,
+    /// A CSV field, by column position modulo [`COLUMNS`].
+    Column(usize),
+    Comment,
+    Function,
+    Key,
+    Keyword,
+    Lifetime,
+    /// Booleans and null values.
+    Literal,
+    Macro,
+    Number,
+    /// A TOML table header.
+    Section,
+    String,
+    Type,
+    Variable,
+}
+
+impl Class {
+    /// Every class, in the order they are written in the stylesheet.
+    fn all() -> Vec<Class> {
+        let mut classes = vec![
+            Class::Attribute,
+            Class::Comment,
+            Class::Function,
+            Class::Key,
+            Class::Keyword,
+            Class::Lifetime,
+            Class::Literal,
+            Class::Macro,
+            Class::Number,
+            Class::Section,
+            Class::String,
+            Class::Type,
+            Class::Variable,
+        ];
+        classes.extend((0..COLUMNS).map(Class::Column));
+
+        classes
+    }
+
+    /// The CSS declarations for the class in the given theme.
+    fn style(&self, theme: &Theme) -> String {
+        match self {
+            Class::Attribute | Class::Section => format!("color: {}", theme.meta),
+            Class::Column(n) => format!("color: {}", theme.columns[n % COLUMNS]),
+            Class::Comment => format!("color: {}; font-style: italic", theme.comment),
+            Class::Function | Class::Macro => format!("color: {}", theme.function),
+            Class::Key | Class::Variable | Class::Lifetime => format!("color: {}", theme.name),
+            Class::Keyword => format!("color: {}; font-weight: bold", theme.keyword),
+            Class::Literal | Class::Number => format!("color: {}", theme.constant),
+            Class::String => format!("color: {}", theme.string),
+            Class::Type => format!("color: {}", theme.kind),
+        }
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Class::Attribute => "attribute",
+            Class::Column(n) => return write!(f, "{}column-{}", PREFIX, n % COLUMNS),
+            Class::Comment => "comment",
+            Class::Function => "function",
+            Class::Key => "key",
+            Class::Keyword => "keyword",
+            Class::Lifetime => "lifetime",
+            Class::Literal => "literal",
+            Class::Macro => "macro",
+            Class::Number => "number",
+            Class::Section => "section",
+            Class::String => "string",
+            Class::Type => "type",
+            Class::Variable => "variable",
+        };
+
+        write!(f, "{}{}", PREFIX, s)
+    }
+}
+
+/// A marked part of the code, by byte offsets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub class: Class,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Renders the code as escaped HTML with a `span` for each marked part.
+///
+/// The result is meant to sit inside a `<pre><code>` pair, see [`opening`].
+pub fn render(language: Language, text: &str) -> String {
+    let mut html = String::new();
+    let mut cursor = 0;
+
+    for span in language.scan(text) {
+        escape(&mut html, &text[cursor..span.start]);
+        html.push_str(&format!("<span class=\"{}\">", span.class));
+        escape(&mut html, &text[span.start..span.end]);
+        html.push_str("</span>");
+        cursor = span.end;
+    }
+    escape(&mut html, &text[cursor..]);
+
+    html
+}
+
+/// The opening tags for a highlighted block, following the markup Zola uses.
+pub fn opening(language: Language) -> String {
+    format!(
+        "<pre class=\"highlight\" data-lang=\"{lang}\"><code class=\"language-{lang}\" data-lang=\"{lang}\">",
+        lang = language
+    )
+}
+
+fn escape(html: &mut String, text: &str) {
+    escape_html(html, text).expect("writing to a string to succeed");
+}
+
+/// The colours for each group of classes.
+struct Theme {
+    comment: &'static str,
+    keyword: &'static str,
+    string: &'static str,
+    constant: &'static str,
+    function: &'static str,
+    kind: &'static str,
+    name: &'static str,
+    meta: &'static str,
+    columns: [&'static str; COLUMNS],
+}
+
+const LIGHT: Theme = Theme {
+    comment: "#6a737d",
+    keyword: "#a626a4",
+    string: "#50a14f",
+    constant: "#986801",
+    function: "#4078f2",
+    kind: "#c18401",
+    name: "#e45649",
+    meta: "#0184bc",
+    columns: ["#4078f2", "#50a14f", "#a626a4", "#986801"],
+};
+
+const DARK: Theme = Theme {
+    comment: "#7f848e",
+    keyword: "#c678dd",
+    string: "#98c379",
+    constant: "#d19a66",
+    function: "#61afef",
+    kind: "#e5c07b",
+    name: "#e06c75",
+    meta: "#56b6c2",
+    columns: ["#61afef", "#98c379", "#c678dd", "#d19a66"],
+};
+
+/// Generates the stylesheet for the highlighted code, with a light theme and a dark one for readers who prefer it.
+pub fn stylesheet() -> String {
+    let mut css = String::from("/* Highlighted code classes. Generated by aquarium. */\n");
+
+    for class in Class::all() {
+        css.push_str(&format!(".{} {{ {}; }}\n", class, class.style(&LIGHT)));
+    }
+
+    css.push_str("\n@media (prefers-color-scheme: dark) {\n");
+    for class in Class::all() {
+        css.push_str(&format!("  .{} {{ {}; }}\n", class, class.style(&DARK)));
+    }
+    css.push_str("}\n");
+
+    css
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_escaped() {
+        let actual = render(Language::Rust, "let a = \"<b>\";\n");
+
+        assert_eq!(
+            actual,
+            "<span class=\"hl-keyword\">let</span> a = <span class=\"hl-string\">&quot;&lt;b&gt;&quot;</span>;\n"
+        );
+    }
+
+    #[test]
+    fn stylesheet_covers_classes() {
+        let css = stylesheet();
+
+        for class in Class::all() {
+            assert_eq!(css.matches(&format!(".{} {{", class)).count(), 2);
+        }
+        assert!(css.contains(".hl-column-3 { color: #986801; }"));
+    }
+}
@@ -0,0 +1,182 @@
+//! This module highlights Rust.
+
+use super::scanner::{is_ident_continue, is_ident_start, Scanner};
+use super::{Class, Span};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "type", "union", "unsafe",
+    "use", "where", "while", "yield",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "str", "u8", "u16",
+    "u32", "u64", "u128", "usize",
+];
+
+pub fn scan(text: &str) -> Vec<Span> {
+    let mut s = Scanner::new(text);
+
+    while let Some(c) = s.peek() {
+        let start = s.pos();
+
+        if s.starts_with("//") {
+            s.eat_line();
+            s.emit(Class::Comment, start);
+        } else if s.starts_with("/*") {
+            block_comment(&mut s);
+            s.emit(Class::Comment, start);
+        } else if s.starts_with("#[") || s.starts_with("#![") {
+            attribute(&mut s);
+            s.emit(Class::Attribute, start);
+        } else if c == '"' {
+            s.string('"', true);
+            s.emit(Class::String, start);
+        } else if c == '\'' {
+            quote(&mut s, start);
+        } else if c.is_ascii_digit() {
+            s.number();
+            s.emit(Class::Number, start);
+        } else if prefixed_string(&mut s) {
+            s.emit(Class::String, start);
+        } else if is_ident_start(c) {
+            let word = s.eat_while(is_ident_continue);
+
+            if KEYWORDS.contains(&word) {
+                s.emit(Class::Keyword, start);
+            } else if word == "true" || word == "false" {
+                s.emit(Class::Literal, start);
+            } else if s.starts_with("!") && !s.starts_with("!=") {
+                s.bump();
+                s.emit(Class::Macro, start);
+            } else if PRIMITIVES.contains(&word) || word.starts_with(char::is_uppercase) {
+                s.emit(Class::Type, start);
+            } else if s.peek_after_spaces() == Some('(') {
+                s.emit(Class::Function, start);
+            }
+        } else {
+            s.bump();
+        }
+    }
+
+    s.finish()
+}
+
+/// Consumes a block comment, taking nested comments into account.
+fn block_comment(s: &mut Scanner) {
+    let mut depth = 0;
+
+    while s.peek().is_some() {
+        if s.eat("/*") {
+            depth += 1;
+        } else if s.eat("*/") {
+            depth -= 1;
+            if depth == 0 {
+                break;
+            }
+        } else {
+            s.bump();
+        }
+    }
+}
+
+/// Consumes an attribute up to its matching bracket.
+fn attribute(s: &mut Scanner) {
+    let mut depth = 0;
+
+    while let Some(c) = s.peek() {
+        match c {
+            '"' => {
+                s.string('"', true);
+                continue;
+            }
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    s.bump();
+                    break;
+                }
+            }
+            _ => (),
+        }
+        s.bump();
+    }
+}
+
+/// Consumes either a character literal or a lifetime.
+fn quote(s: &mut Scanner, start: usize) {
+    let is_char = s.peek_nth(1) == Some('\\') || s.peek_nth(2) == Some('\'');
+
+    if is_char {
+        s.string('\'', true);
+        s.emit(Class::String, start);
+    } else {
+        s.bump();
+        s.eat_while(is_ident_continue);
+        s.emit(Class::Lifetime, start);
+    }
+}
+
+/// Consumes byte and raw strings, e.g. `b"…"`, `b'…'`, `r"…"` or `br#"…"#`.
+fn prefixed_string(s: &mut Scanner) -> bool {
+    let rest = s.rest();
+    let unprefixed = rest.strip_prefix('b').unwrap_or(rest);
+
+    if let Some(raw) = unprefixed.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+
+        if raw[hashes..].starts_with('"') {
+            s.advance(rest.len() - raw.len() + hashes + 1);
+            s.eat_until(&format!("\"{}", "#".repeat(hashes)));
+
+            return true;
+        }
+    }
+
+    if unprefixed.len() < rest.len() {
+        if let Some(quote @ '"') | Some(quote @ '\'') = unprefixed.chars().next() {
+            s.bump();
+            s.string(quote, true);
+
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::highlight::{render, Language};
+
+    #[test]
+    fn scan_rust() {
+        let text = r##"#[derive(Debug)]
+struct A<'a>(&'a str); // A comment.
+fn main() {
+    let s = r#"raw "string""#;
+    println!("{} {}", s, '\n');
+    let n: u8 = 0xff;
+    x.max(1.5e-3)
+}"##;
+        let actual = render(Language::Rust, text);
+
+        assert!(actual.starts_with("<span class=\"hl-attribute\">#[derive(Debug)]</span>\n"));
+        assert!(actual.contains(
+            "<span class=\"hl-type\">A</span>&lt;<span class=\"hl-lifetime\">'a</span>&gt;"
+        ));
+        assert!(actual.contains("<span class=\"hl-comment\">// A comment.</span>"));
+        assert!(actual.contains("<span class=\"hl-function\">main</span>()"));
+        assert!(actual
+            .contains("<span class=\"hl-string\">r#&quot;raw &quot;string&quot;&quot;#</span>"));
+        assert!(actual.contains("<span class=\"hl-macro\">println!</span>"));
+        assert!(actual.contains("<span class=\"hl-string\">'\\n'</span>"));
+        assert!(actual
+            .contains("<span class=\"hl-type\">u8</span> = <span class=\"hl-number\">0xff</span>"));
+        assert!(actual.contains(
+            "<span class=\"hl-function\">max</span>(<span class=\"hl-number\">1.5e-3</span>)"
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+//! This module offers the cursor the language highlighters use to walk the text and mark spans.
+
+use super::{Class, Span};
+
+/// A cursor over the text collecting classified spans in order.
+#[derive(Debug)]
+pub struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    spans: Vec<Span>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            spans: Vec::new(),
+        }
+    }
+
+    /// The byte offset of the cursor.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    /// The rest of the current line, without the line break.
+    pub fn line_rest(&self) -> &'a str {
+        let rest = self.rest();
+
+        rest.split('\n').next().unwrap_or(rest)
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub fn peek_nth(&self, n: usize) -> Option<char> {
+        self.rest().chars().nth(n)
+    }
+
+    /// The character right before the cursor.
+    pub fn previous(&self) -> Option<char> {
+        self.text[..self.pos].chars().next_back()
+    }
+
+    /// Whether there is only whitespace between the start of the line and the cursor.
+    pub fn at_line_start(&self) -> bool {
+        self.text[..self.pos]
+            .chars()
+            .rev()
+            .take_while(|c| *c != '\n')
+            .all(|c| c == ' ' || c == '\t')
+    }
+
+    pub fn starts_with(&self, pattern: &str) -> bool {
+        self.rest().starts_with(pattern)
+    }
+
+    pub fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+
+        Some(c)
+    }
+
+    /// Moves the cursor the given number of bytes.
+    pub fn advance(&mut self, len: usize) {
+        self.pos = (self.pos + len).min(self.text.len());
+    }
+
+    /// Consumes the pattern if the text continues with it.
+    pub fn eat(&mut self, pattern: &str) -> bool {
+        if self.starts_with(pattern) {
+            self.advance(pattern.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn eat_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> &'a str {
+        let start = self.pos;
+
+        while let Some(c) = self.peek() {
+            if !predicate(c) {
+                break;
+            }
+            self.bump();
+        }
+
+        &self.text[start..self.pos]
+    }
+
+    /// Consumes up to and including the pattern, or everything left when the pattern is not found.
+    pub fn eat_until(&mut self, pattern: &str) {
+        match self.rest().find(pattern) {
+            Some(i) => self.advance(i + pattern.len()),
+            None => self.pos = self.text.len(),
+        }
+    }
+
+    /// Consumes up to the end of the line, leaving the line break.
+    pub fn eat_line(&mut self) {
+        self.advance(self.line_rest().len());
+    }
+
+    pub fn skip_spaces(&mut self) {
+        self.eat_while(|c| c == ' ' || c == '\t');
+    }
+
+    /// The next character after any spaces, without moving the cursor.
+    pub fn peek_after_spaces(&self) -> Option<char> {
+        self.rest().chars().find(|c| *c != ' ' && *c != '\t')
+    }
+
+    /// Consumes a quoted string, opening quote included. Without escapes a backslash is a regular character.
+    pub fn string(&mut self, quote: char, escapes: bool) {
+        self.bump();
+
+        while let Some(c) = self.bump() {
+            if c == quote {
+                break;
+            }
+            if escapes && c == '\\' {
+                self.bump();
+            }
+        }
+    }
+
+    /// Consumes a number with its suffixes, e.g. `1_000`, `0xff`, `2.5e10` or `3u8`.
+    pub fn number(&mut self) {
+        let hex = self.starts_with("0x") || self.starts_with("0X");
+
+        while let Some(c) = self.peek() {
+            let fraction = c == '.' && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit());
+            let exponent_sign =
+                !hex && (c == '+' || c == '-') && matches!(self.previous(), Some('e') | Some('E'));
+
+            if c.is_ascii_alphanumeric() || c == '_' || fraction || exponent_sign {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Marks the text from `start` to the cursor with the given class.
+    pub fn emit(&mut self, class: Class, start: usize) {
+        if self.pos > start {
+            self.spans.push(Span {
+                class,
+                start,
+                end: self.pos,
+            });
+        }
+    }
+
+    pub fn finish(self) -> Vec<Span> {
+        self.spans
+    }
+}
+
+pub fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+pub fn is_ident_continue(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
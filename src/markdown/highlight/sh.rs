@@ -0,0 +1,232 @@
+//! This module highlights shell scripts and commands.
+
+use super::scanner::{is_ident_continue, is_ident_start, Scanner};
+use super::{Class, Span};
+
+/// Keywords after which a command is expected.
+const COMMAND_KEYWORDS: &[&str] = &[
+    "do", "elif", "else", "if", "then", "time", "until", "while", "!",
+];
+
+const KEYWORDS: &[&str] = &[
+    "case", "declare", "done", "esac", "export", "fi", "for", "function", "in", "local",
+    "readonly", "return", "select", "unset",
+];
+
+pub fn scan(text: &str) -> Vec<Span> {
+    let mut s = Scanner::new(text);
+    // Whether the next word is a command name.
+    let mut command = true;
+    // The heredocs opened on the current line, whose bodies start on the next one.
+    let mut heredocs: Vec<(String, bool)> = Vec::new();
+
+    while let Some(c) = s.peek() {
+        let start = s.pos();
+
+        match c {
+            '#' if s.previous().is_none_or(char::is_whitespace) => {
+                s.eat_line();
+                s.emit(Class::Comment, start);
+            }
+            '\'' => {
+                s.string('\'', false);
+                s.emit(Class::String, start);
+                command = false;
+            }
+            '"' => {
+                double_quoted(&mut s);
+                command = false;
+            }
+            '$' => {
+                variable(&mut s);
+                // A lone dollar is likely a prompt.
+                if s.pos() > start + 1 {
+                    s.emit(Class::Variable, start);
+                }
+            }
+            '\\' => {
+                s.bump();
+                s.bump();
+            }
+            // Redirections such as `2>&1`.
+            '&' if matches!(s.previous(), Some('>') | Some('<')) => {
+                s.bump();
+            }
+            '\n' if !heredocs.is_empty() => {
+                s.bump();
+                for (delimiter, strip_tabs) in heredocs.drain(..) {
+                    heredoc_body(&mut s, &delimiter, strip_tabs);
+                }
+                command = true;
+            }
+            '<' if s.starts_with("<<") && !s.starts_with("<<<") => {
+                heredocs.extend(heredoc(&mut s));
+            }
+            '\n' | ';' | '|' | '&' | '(' | '`' => {
+                s.bump();
+                command = true;
+            }
+            c if c.is_whitespace() || "<>)".contains(c) => {
+                s.bump();
+            }
+            _ => {
+                let word = s.eat_while(|c| !c.is_whitespace() && !";|&()<>'\"`$".contains(c));
+
+                if command && COMMAND_KEYWORDS.contains(&word) {
+                    s.emit(Class::Keyword, start);
+                } else if command && KEYWORDS.contains(&word) {
+                    s.emit(Class::Keyword, start);
+                    command = false;
+                } else if command && is_assignment(word) {
+                    // The command, if any, comes after the assignments.
+                    s.emit(Class::Variable, start);
+                } else if command {
+                    s.emit(Class::Function, start);
+                    command = false;
+                }
+            }
+        }
+    }
+
+    s.finish()
+}
+
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => name.starts_with(is_ident_start) && name.chars().all(is_ident_continue),
+        None => false,
+    }
+}
+
+/// Consumes a variable expansion, e.g. `$HOME`, `${HOME}`, `$1` or `$?`.
+fn variable(s: &mut Scanner) {
+    s.bump();
+
+    match s.peek() {
+        Some('{') => s.eat_until("}"),
+        Some(c) if is_ident_start(c) => {
+            s.eat_while(is_ident_continue);
+        }
+        Some(c) if c.is_ascii_digit() || "@*#?$!-".contains(c) => {
+            s.bump();
+        }
+        _ => (),
+    }
+}
+
+/// Consumes a heredoc redirection such as `<<EOF` or `<<-'EOF'`, returning the delimiter and whether leading tabs are
+/// stripped from the body.
+fn heredoc(s: &mut Scanner) -> Option<(String, bool)> {
+    s.advance(2);
+    let strip_tabs = s.eat("-");
+    s.skip_spaces();
+
+    let start = s.pos();
+    let word = s.eat_while(|c| !c.is_whitespace() && !";|&()<>".contains(c));
+    // Quoting any part of the delimiter only turns expansions off in the body.
+    let delimiter: String = word.chars().filter(|c| !"'\"\\".contains(*c)).collect();
+
+    // A number is rather a shift in an arithmetic expansion, e.g. `$((1 << 2))`.
+    if delimiter.is_empty() || delimiter.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    s.emit(Class::String, start);
+
+    Some((delimiter, strip_tabs))
+}
+
+/// Consumes the lines of a heredoc body up to and including the terminator line, as a string.
+fn heredoc_body(s: &mut Scanner, delimiter: &str, strip_tabs: bool) {
+    while s.peek().is_some() {
+        let line = s.line_rest();
+        let done = if strip_tabs {
+            line.trim_start_matches('\t') == delimiter
+        } else {
+            line == delimiter
+        };
+        let start = s.pos();
+        s.eat_line();
+        s.emit(Class::String, start);
+
+        if done {
+            break;
+        }
+        s.bump();
+    }
+}
+
+/// Consumes a double quoted string, marking the variables expanded in it.
+fn double_quoted(s: &mut Scanner) {
+    let mut start = s.pos();
+    s.bump();
+
+    while let Some(c) = s.peek() {
+        match c {
+            '"' => {
+                s.bump();
+                break;
+            }
+            '\\' => {
+                s.bump();
+                s.bump();
+            }
+            '$' if s
+                .peek_nth(1)
+                .is_some_and(|c| c == '{' || is_ident_continue(c)) =>
+            {
+                s.emit(Class::String, start);
+                let variable_start = s.pos();
+                variable(s);
+                s.emit(Class::Variable, variable_start);
+                start = s.pos();
+            }
+            _ => {
+                s.bump();
+            }
+        }
+    }
+
+    s.emit(Class::String, start);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::highlight::{render, Language};
+
+    #[test]
+    fn scan_sh() {
+        let text = r#"# Build the site.
+RUST_LOG=info cargo run -- build -i "$HOME/corpus" | tee 'build.log'
+if [ -n "${OUT}" ]; then echo "done: $1"; fi"#;
+        let actual = render(Language::Sh, text);
+
+        assert!(actual.starts_with("<span class=\"hl-comment\"># Build the site.</span>\n"));
+        assert!(actual.contains("<span class=\"hl-variable\">RUST_LOG=info</span> <span class=\"hl-function\">cargo</span> run -- build -i"));
+        assert!(actual.contains("<span class=\"hl-string\">&quot;</span><span class=\"hl-variable\">$HOME</span><span class=\"hl-string\">/corpus&quot;</span>"));
+        assert!(actual.contains(
+            "| <span class=\"hl-function\">tee</span> <span class=\"hl-string\">'build.log'</span>"
+        ));
+        assert!(actual
+            .contains("<span class=\"hl-keyword\">if</span> <span class=\"hl-function\">[</span>"));
+        assert!(actual.contains(
+            "<span class=\"hl-keyword\">then</span> <span class=\"hl-function\">echo</span>"
+        ));
+        assert!(actual.contains(
+            "<span class=\"hl-variable\">$1</span><span class=\"hl-string\">&quot;</span>;"
+        ));
+        assert!(actual.contains("; <span class=\"hl-keyword\">fi</span>"));
+    }
+
+    #[test]
+    fn scan_heredoc() {
+        let text = "cat <<EOF | grep -v x\nhello $USER; rm -rf\n\nEOF\necho done\ncat <<-'END'\n\tif\n\tEND\nls";
+        let actual = render(Language::Sh, text);
+        let shift = render(Language::Sh, "echo $((1 << 2))\nls");
+
+        assert!(actual.starts_with("<span class=\"hl-function\">cat</span> &lt;&lt;<span class=\"hl-string\">EOF</span> | <span class=\"hl-function\">grep</span> -v x\n"));
+        assert!(actual.contains("\n<span class=\"hl-string\">hello $USER; rm -rf</span>\n\n<span class=\"hl-string\">EOF</span>\n<span class=\"hl-function\">echo</span> done\n"));
+        assert!(actual.contains("&lt;&lt;-<span class=\"hl-string\">'END'</span>\n<span class=\"hl-string\">\tif</span>\n<span class=\"hl-string\">\tEND</span>\n<span class=\"hl-function\">ls</span>"));
+        assert!(!shift.contains("hl-string"));
+        assert!(shift.ends_with("\n<span class=\"hl-function\">ls</span>"));
+    }
+}
@@ -0,0 +1,187 @@
+//! This module highlights SQL, with SQLite in mind.
+
+use super::scanner::{is_ident_continue, is_ident_start, Scanner};
+use super::{Class, Span};
+
+const KEYWORDS: &[&str] = &[
+    "abort",
+    "add",
+    "all",
+    "alter",
+    "and",
+    "as",
+    "asc",
+    "autoincrement",
+    "begin",
+    "between",
+    "by",
+    "cascade",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "column",
+    "commit",
+    "conflict",
+    "constraint",
+    "create",
+    "cross",
+    "default",
+    "delete",
+    "desc",
+    "distinct",
+    "do",
+    "drop",
+    "else",
+    "end",
+    "escape",
+    "except",
+    "exists",
+    "foreign",
+    "from",
+    "full",
+    "glob",
+    "group",
+    "having",
+    "if",
+    "ignore",
+    "in",
+    "index",
+    "inner",
+    "insert",
+    "intersect",
+    "into",
+    "is",
+    "join",
+    "key",
+    "left",
+    "like",
+    "limit",
+    "natural",
+    "not",
+    "nothing",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "outer",
+    "pragma",
+    "primary",
+    "recursive",
+    "references",
+    "rename",
+    "replace",
+    "returning",
+    "right",
+    "rollback",
+    "select",
+    "set",
+    "table",
+    "temp",
+    "temporary",
+    "then",
+    "to",
+    "transaction",
+    "trigger",
+    "union",
+    "unique",
+    "update",
+    "using",
+    "values",
+    "view",
+    "when",
+    "where",
+    "with",
+    "without",
+];
+
+const TYPES: &[&str] = &[
+    "blob",
+    "boolean",
+    "char",
+    "date",
+    "datetime",
+    "decimal",
+    "float",
+    "int",
+    "integer",
+    "numeric",
+    "real",
+    "text",
+    "timestamp",
+    "varchar",
+];
+
+const LITERALS: &[&str] = &["false", "null", "true"];
+
+pub fn scan(text: &str) -> Vec<Span> {
+    let mut s = Scanner::new(text);
+
+    while let Some(c) = s.peek() {
+        let start = s.pos();
+
+        if s.starts_with("--") {
+            s.eat_line();
+            s.emit(Class::Comment, start);
+        } else if s.starts_with("/*") {
+            s.advance(2);
+            s.eat_until("*/");
+            s.emit(Class::Comment, start);
+        } else if c == '\'' {
+            // A quote is escaped by doubling it.
+            s.string('\'', false);
+            while s.peek() == Some('\'') {
+                s.string('\'', false);
+            }
+            s.emit(Class::String, start);
+        } else if c == '"' || c == '`' {
+            // Quoted identifiers are left as they are.
+            s.string(c, false);
+        } else if c.is_ascii_digit() {
+            s.number();
+            s.emit(Class::Number, start);
+        } else if matches!(c, '?' | ':' | '@' | '$') && s.peek_nth(1).is_some_and(is_ident_continue)
+        {
+            s.bump();
+            s.eat_while(is_ident_continue);
+            s.emit(Class::Variable, start);
+        } else if is_ident_start(c) {
+            let word = s.eat_while(is_ident_continue).to_lowercase();
+
+            if KEYWORDS.contains(&word.as_str()) {
+                s.emit(Class::Keyword, start);
+            } else if TYPES.contains(&word.as_str()) {
+                s.emit(Class::Type, start);
+            } else if LITERALS.contains(&word.as_str()) {
+                s.emit(Class::Literal, start);
+            } else if s.peek_after_spaces() == Some('(') {
+                s.emit(Class::Function, start);
+            }
+        } else {
+            s.bump();
+        }
+    }
+
+    s.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::highlight::{render, Language};
+
+    #[test]
+    fn scan_sql() {
+        let text = r#"-- Notes by tag.
+SELECT coalesce(note.title, 'It''s') AS "from", count(*)
+FROM note WHERE id = ?1 AND draft IS NOT NULL LIMIT 10;"#;
+        let actual = render(Language::Sql, text);
+
+        assert!(actual.starts_with("<span class=\"hl-comment\">-- Notes by tag.</span>\n"));
+        assert!(actual.contains("<span class=\"hl-function\">coalesce</span>(note.title, "));
+        assert!(actual.contains("<span class=\"hl-string\">'It''s'</span>"));
+        assert!(actual.contains("<span class=\"hl-keyword\">AS</span> &quot;from&quot;"));
+        assert!(actual.contains("<span class=\"hl-variable\">?1</span>"));
+        assert!(actual.contains("<span class=\"hl-literal\">NULL</span>"));
+        assert!(actual.contains("<span class=\"hl-number\">10</span>;"));
+    }
+}
@@ -0,0 +1,126 @@
+//! This module highlights TOML.
+
+use super::scanner::Scanner;
+use super::{Class, Span};
+
+pub fn scan(text: &str) -> Vec<Span> {
+    let mut s = Scanner::new(text);
+
+    while let Some(c) = s.peek() {
+        let start = s.pos();
+
+        if c == '#' {
+            s.eat_line();
+            s.emit(Class::Comment, start);
+        } else if c == '[' && s.at_line_start() {
+            s.eat_until("]");
+            s.eat("]");
+            s.emit(Class::Section, start);
+        } else if is_bare(c) || c == '"' || c == '\'' {
+            value_or_key(&mut s, start);
+        } else {
+            s.bump();
+        }
+    }
+
+    s.finish()
+}
+
+fn is_bare(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+'
+}
+
+/// Consumes a key, dotted keys included, or a value.
+fn value_or_key(s: &mut Scanner, start: usize) {
+    let mut class = part(s);
+
+    // A key is anything followed by an equals sign, perhaps after a dot and more parts.
+    loop {
+        match s.peek_after_spaces() {
+            Some('=') => {
+                s.emit(Class::Key, start);
+                return;
+            }
+            Some('.') if class != Class::Number => {
+                s.skip_spaces();
+                s.bump();
+                s.skip_spaces();
+                class = part(s);
+            }
+            _ => break,
+        }
+    }
+
+    if class != Class::Key {
+        s.emit(class, start);
+    }
+}
+
+/// Consumes a single string or bare word and tells the class it has when used as a value. Bare words without a
+/// meaning as values are classed as keys.
+fn part(s: &mut Scanner) -> Class {
+    match s.peek() {
+        Some('"') if s.starts_with("\"\"\"") => {
+            s.advance(3);
+            s.eat_until("\"\"\"");
+            Class::String
+        }
+        Some('\'') if s.starts_with("'''") => {
+            s.advance(3);
+            s.eat_until("'''");
+            Class::String
+        }
+        Some('"') => {
+            s.string('"', true);
+            Class::String
+        }
+        Some('\'') => {
+            s.string('\'', false);
+            Class::String
+        }
+        _ => {
+            let signed = matches!(s.peek(), Some('+') | Some('-'));
+            let number = s
+                .peek_nth(if signed { 1 } else { 0 })
+                .is_some_and(|c| c.is_ascii_digit());
+            let word = s.eat_while(|c| is_bare(c) || (number && (c == ':' || c == '.')));
+
+            match word.trim_start_matches(&['+', '-'][..]) {
+                "true" | "false" => Class::Literal,
+                "inf" | "nan" => Class::Number,
+                _ if number => Class::Number,
+                _ => Class::Key,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::highlight::{render, Language};
+
+    #[test]
+    fn scan_toml() {
+        let text = r#"# Settings.
+[licence]
+name = "CC BY-NC 4.0" # Inline.
+site.url = 'https://example.org'
+date = 1979-05-27T07:32:00Z
+sizes = [1, -2.5, inf]
+inline = { draft = true }
+
+[[entries]]
+"#;
+        let actual = render(Language::Toml, text);
+
+        assert!(actual.starts_with("<span class=\"hl-comment\"># Settings.</span>\n<span class=\"hl-section\">[licence]</span>\n"));
+        assert!(actual.contains("<span class=\"hl-key\">name</span> = <span class=\"hl-string\">&quot;CC BY-NC 4.0&quot;</span> <span class=\"hl-comment\"># Inline.</span>"));
+        assert!(actual.contains("<span class=\"hl-key\">site.url</span> = <span class=\"hl-string\">'https://example.org'</span>"));
+        assert!(actual.contains("<span class=\"hl-number\">1979-05-27T07:32:00Z</span>"));
+        assert!(actual.contains("[<span class=\"hl-number\">1</span>, <span class=\"hl-number\">-2.5</span>, <span class=\"hl-number\">inf</span>]"));
+        assert!(actual.contains(
+            "{ <span class=\"hl-key\">draft</span> = <span class=\"hl-literal\">true</span> }"
+        ));
+        assert!(actual.contains("<span class=\"hl-section\">[[entries]]</span>"));
+    }
+}
@@ -0,0 +1,250 @@
+//! This module highlights YAML, line by line.
+
+use super::scanner::Scanner;
+use super::{Class, Span};
+
+const LITERALS: &[&str] = &[
+    "true", "True", "TRUE", "false", "False", "FALSE", "null", "Null", "NULL", "~", "yes", "no",
+    "on", "off",
+];
+
+pub fn scan(text: &str) -> Vec<Span> {
+    let mut s = Scanner::new(text);
+
+    while s.peek().is_some() {
+        line(&mut s);
+    }
+
+    s.finish()
+}
+
+/// Consumes a line up to and including its line break, plus the content of any block scalar it opens.
+fn line(s: &mut Scanner) {
+    let indent = s.eat_while(|c| c == ' ' || c == '\t').len();
+    let start = s.pos();
+
+    if s.starts_with("---") || s.starts_with("...") {
+        s.advance(3);
+        if s.peek().is_none_or(char::is_whitespace) {
+            s.emit(Class::Keyword, start);
+        }
+    }
+
+    // Sequence entries.
+    while s.peek() == Some('-') && s.peek_nth(1).is_none_or(char::is_whitespace) {
+        s.bump();
+        s.skip_spaces();
+    }
+
+    if let Some(len) = key_length(s.line_rest()) {
+        let start = s.pos();
+        s.advance(len);
+        s.emit(Class::Key, start);
+        s.bump();
+    }
+
+    if value(s) {
+        block_scalar(s, indent);
+    }
+
+    s.bump();
+}
+
+/// The length of the key at the start of the line, if any.
+fn key_length(line: &str) -> Option<usize> {
+    let mut chars = line.char_indices().peekable();
+    let first = line.chars().next()?;
+
+    if first == '"' || first == '\'' {
+        chars.next();
+        let end = chars.find(|(_, c)| *c == first)?.0 + 1;
+
+        return if line[end..].trim_start().starts_with(':') {
+            Some(end + line[end..].find(':')?)
+        } else {
+            None
+        };
+    }
+
+    if "#&*!|>%@`[]{},?".contains(first) {
+        return None;
+    }
+
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+
+        match c {
+            ':' if next.is_none_or(char::is_whitespace) => {
+                return Some(line[..i].trim_end().len());
+            }
+            ' ' | '\t' if next == Some('#') => return None,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Consumes the value up to the end of the line. Tells whether it opens a block scalar.
+fn value(s: &mut Scanner) -> bool {
+    let mut flow = 0;
+
+    while let Some(c) = s.peek() {
+        let start = s.pos();
+
+        match c {
+            '\n' => break,
+            ' ' | '\t' | ',' | ':' => {
+                s.bump();
+            }
+            '#' if s.previous().is_none_or(char::is_whitespace) => {
+                s.eat_line();
+                s.emit(Class::Comment, start);
+            }
+            '"' => {
+                s.string('"', true);
+                s.emit(Class::String, start);
+            }
+            '\'' => {
+                // A quote is escaped by doubling it.
+                s.string('\'', false);
+                while s.peek() == Some('\'') {
+                    s.string('\'', false);
+                }
+                s.emit(Class::String, start);
+            }
+            '&' | '*' => {
+                s.eat_while(|c| !c.is_whitespace() && !",[]{}".contains(c));
+                s.emit(Class::Variable, start);
+            }
+            '!' => {
+                s.eat_while(|c| !c.is_whitespace());
+                s.emit(Class::Type, start);
+            }
+            '|' | '>' if flow == 0 => {
+                s.eat_while(|c| !c.is_whitespace());
+                s.skip_spaces();
+                if s.peek() == Some('#') {
+                    let start = s.pos();
+                    s.eat_line();
+                    s.emit(Class::Comment, start);
+                }
+                return true;
+            }
+            '[' | '{' => {
+                flow += 1;
+                s.bump();
+            }
+            ']' | '}' => {
+                flow -= 1;
+                s.bump();
+            }
+            _ => scalar(s, flow > 0),
+        }
+    }
+
+    false
+}
+
+/// Consumes a plain scalar, which can hold spaces. Only numbers and literals are marked, plus keys in flow mappings.
+fn scalar(s: &mut Scanner, flow: bool) {
+    let start = s.pos();
+    let line = s.line_rest();
+    let mut end = line.len();
+
+    for (i, c) in line.char_indices() {
+        let next = line[i + c.len_utf8()..].chars().next();
+        let comment = (c == ' ' || c == '\t') && next == Some('#');
+        let mapping = c == ':' && next.is_none_or(char::is_whitespace);
+
+        if comment || mapping || (flow && ",[]{}".contains(c)) {
+            end = i;
+            break;
+        }
+    }
+
+    let scalar = line[..end].trim_end();
+
+    if scalar.is_empty() {
+        s.bump();
+        return;
+    }
+    s.advance(scalar.len());
+
+    if s.peek() == Some(':') && flow {
+        s.emit(Class::Key, start);
+    } else if LITERALS.contains(&scalar) {
+        s.emit(Class::Literal, start);
+    } else if is_number(scalar) {
+        s.emit(Class::Number, start);
+    }
+}
+
+fn is_number(scalar: &str) -> bool {
+    let unsigned = scalar.trim_start_matches(&['+', '-'][..]);
+
+    if let Some(hex) = unsigned.strip_prefix("0x") {
+        return i64::from_str_radix(hex, 16).is_ok();
+    }
+
+    // Rust parses words like `inf` or `NaN` as floats but YAML spells them `.inf` and `.nan`.
+    matches!(
+        unsigned,
+        ".inf" | ".Inf" | ".INF" | ".nan" | ".NaN" | ".NAN"
+    ) || (unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && scalar.parse::<f64>().is_ok())
+}
+
+/// Consumes the lines more indented than the one opening the block scalar, as a string.
+fn block_scalar(s: &mut Scanner, indent: usize) {
+    while s.peek() == Some('\n') {
+        let next = &s.rest()[1..];
+        let line = next.split('\n').next().unwrap_or(next);
+        let line_indent = line.len() - line.trim_start().len();
+
+        if !line.trim().is_empty() && line_indent <= indent {
+            break;
+        }
+
+        s.bump();
+        let start = s.pos();
+        s.eat_line();
+        s.emit(Class::String, start);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markdown::highlight::{render, Language};
+
+    #[test]
+    fn scan_yaml() {
+        let text = r#"---
+type: note # A comment.
+id: a-note
+publication_date: 2021-07-07
+tags: [rust, 42]
+"quoted key": 'it''s'
+draft: false
+size: -1.5
+list:
+  - &anchor one two
+  - *anchor
+body: |
+  A block.
+
+  More.
+after: ~
+"#;
+        let actual = render(Language::Yaml, text);
+
+        assert!(actual.starts_with("<span class=\"hl-keyword\">---</span>\n<span class=\"hl-key\">type</span>: note <span class=\"hl-comment\"># A comment.</span>\n"));
+        assert!(actual.contains("<span class=\"hl-key\">publication_date</span>: 2021-07-07\n"));
+        assert!(actual.contains("[rust, <span class=\"hl-number\">42</span>]"));
+        assert!(actual.contains("<span class=\"hl-key\">&quot;quoted key&quot;</span>: <span class=\"hl-string\">'it''s'</span>"));
+        assert!(actual.contains("<span class=\"hl-literal\">false</span>"));
+        assert!(actual.contains("<span class=\"hl-number\">-1.5</span>"));
+        assert!(actual.contains("  - <span class=\"hl-variable\">&amp;anchor</span> one two\n  - <span class=\"hl-variable\">*anchor</span>\n"));
+        assert!(actual.contains("|\n<span class=\"hl-string\">  A block.</span>\n\n<span class=\"hl-string\">  More.</span>\n<span class=\"hl-key\">after</span>: <span class=\"hl-literal\">~</span>"));
+    }
+}
@@ -0,0 +1,113 @@
+//! This module parses the info string of fenced code blocks.
+
+/// A fenced code block info string: a language followed by `key=value` attributes.
+///
+/// Values can be double quoted to hold spaces, e.g. ```` ```csv target=table caption="Monthly totals" ````. A bare
+/// word is an attribute with an empty value.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Info {
+    pub lang: String,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Info {
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim();
+        let (lang, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let mut attributes = Vec::new();
+        let mut chars = rest.chars().peekable();
+
+        loop {
+            while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+                chars.next();
+            }
+
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let mut key = String::new();
+            while let Some(c) = chars.peek() {
+                if c.is_whitespace() || *c == '=' {
+                    break;
+                }
+                key.push(*c);
+                chars.next();
+            }
+
+            let mut value = String::new();
+            if chars.peek() == Some(&'=') {
+                chars.next();
+
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    // An unterminated quote runs to the end of the info string.
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '"' => break,
+                            '\\' if chars.peek() == Some(&'"') => {
+                                chars.next();
+                                value.push('"');
+                            }
+                            _ => value.push(c),
+                        }
+                    }
+                } else {
+                    while let Some(c) = chars.peek() {
+                        if c.is_whitespace() {
+                            break;
+                        }
+                        value.push(*c);
+                        chars.next();
+                    }
+                }
+            }
+
+            attributes.push((key, value));
+        }
+
+        Self {
+            lang: lang.to_string(),
+            attributes,
+        }
+    }
+
+    /// The value of the given attribute. The last one wins when repeated.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.attribute("target")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_plain() {
+        let info = Info::parse("rust");
+
+        assert_eq!(info.lang, "rust");
+        assert!(info.attributes.is_empty());
+    }
+
+    #[test]
+    fn parse_attributes() {
+        let info =
+            Info::parse(r#"csv target=table caption="A \"quoted\" caption" header=false flag"#);
+
+        assert_eq!(info.lang, "csv");
+        assert_eq!(info.target(), Some("table"));
+        assert_eq!(info.attribute("caption"), Some(r#"A "quoted" caption"#));
+        assert_eq!(info.attribute("header"), Some("false"));
+        assert_eq!(info.attribute("flag"), Some(""));
+        assert_eq!(info.attribute("missing"), None);
+    }
+}
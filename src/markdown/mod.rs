@@ -4,12 +4,20 @@ use pulldown_cmark::{html, Options, Parser};
 use regex::Regex;
 use std::str::FromStr;
 
+mod card;
+mod dot;
 mod extract;
+mod highlight;
+mod info;
 mod processor;
 mod strip;
+mod table;
+mod toc;
 
+pub use highlight::{stylesheet, STYLESHEET_FILENAME};
 pub use processor::enrich;
 pub use strip::strip;
+pub use toc::{Heading, Toc};
 
 lazy_static! {
     static ref FRONTMATTER_RE: Regex =
@@ -81,7 +89,7 @@ pub fn to_html(blob: &str) -> String {
 pub fn take_frontmatter(blob: &str) -> Result<(&str, &str)> {
     let groups = FRONTMATTER_RE
         .captures(blob)
-        .expect("frontmatter split failure");
+        .ok_or(extract::ExtractError::FrontmatterNotFound)?;
     let frontmatter = groups.get(1).expect("group frontmatter missing").as_str();
     let content = groups.get(2).expect("group content missing").as_str();
 
@@ -1,10 +1,20 @@
 use anyhow::Result;
 use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag};
+#[cfg(feature = "graphviz")]
 use std::io::Write;
+#[cfg(feature = "graphviz")]
 use std::process::{Command, Stdio};
 
+use super::card;
+use super::dot;
+use super::highlight::{self, Language};
+use super::info::Info;
+use super::table;
+
 /// Processes the given markdown text with tranformation rules such as generating dot diagrams from code blocks.
-pub fn enrich(text: &str) -> Result<String> {
+///
+/// With `highlight`, code blocks in a supported language are highlighted as HTML instead of being left as fenced code.
+pub fn enrich(text: &str, highlight: bool) -> Result<String> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TABLES);
@@ -13,6 +23,8 @@ pub fn enrich(text: &str) -> Result<String> {
     let mut stack: Vec<Tag<'_>> = Vec::new();
     // TODO: slurp into stack
     let mut list_depth: Option<usize> = None;
+    // Fenced blocks can come in several text events, e.g. one per line, so they are rendered once whole.
+    let mut block = String::new();
 
     let parser = Parser::new_ext(text, options);
     let mut recipient = String::new();
@@ -22,50 +34,24 @@ pub fn enrich(text: &str) -> Result<String> {
             Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
                 recipient.push('\n');
 
-                match info.as_ref() {
-                    "dot" => {
-                        let opening = format!("\n<div class=\"figure from-{}\">", info);
-                        recipient.push_str(&opening);
-                    }
-                    "csv target=table" => {
-                        let tokens: Vec<_> = info.split(' ').collect();
-                        let lang = tokens[0];
-                        let opening = format!("\n<div class=\"table-wrapper from-{}\">", lang);
-                        recipient.push_str(&opening);
-                    }
-                    "csv target=card" => {
-                        let tokens: Vec<_> = info.split(' ').collect();
-                        let lang = tokens[0];
-                        let opening = format!("\n<div class=\"card-wrapper from-{}\">", lang);
+                match Block::from_info(&info, highlight).opening() {
+                    Some(opening) => {
+                        recipient.push('\n');
                         recipient.push_str(&opening);
                     }
-                    // TODO:
-                    // "toml target=card" => {
-                    //     let tokens: Vec<_> = info.split(' ').collect();
-                    //     let lang = tokens[0];
-                    //     let opening = format!("\n<div class=\"card-wrapper from-{}\">", lang);
-                    //     recipient.push_str(&opening);
-                    // }
-                    _ => {
+                    None => {
                         recipient.push_str("```");
                         recipient.push_str(&info);
+                        recipient.push('\n');
                     }
                 }
-                recipient.push('\n');
                 stack.push(Tag::CodeBlock(CodeBlockKind::Fenced(info)));
             }
             Event::End(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
-                match info.as_ref() {
-                    "dot" => {
-                        recipient.push_str("</div>\n");
-                    }
-                    "csv target=table" => {
-                        recipient.push_str("</div>\n");
-                    }
-                    _ => {
-                        recipient.push_str("```\n");
-                    }
-                }
+                let kind = Block::from_info(&info, highlight);
+                recipient.push_str(&kind.render(&block)?);
+                recipient.push_str(kind.closing());
+                block.clear();
                 stack.pop();
             }
             Event::Start(tag @ Tag::CodeBlock(CodeBlockKind::Indented)) => {
@@ -78,20 +64,9 @@ pub fn enrich(text: &str) -> Result<String> {
             }
             Event::Text(ref text) => {
                 match stack.last() {
-                    Some(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => match info.as_ref() {
-                        "dot" => {
-                            let svg = process_graphviz(text)?;
-                            recipient.push_str(&svg);
-                        }
-                        "csv target=table" => {
-                            recipient.push_str("!!!!\n");
-                            recipient.push_str(text);
-                            recipient.push_str("\n!!!!");
-                        }
-                        _ => {
-                            recipient.push_str(text);
-                        }
-                    },
+                    Some(Tag::CodeBlock(CodeBlockKind::Fenced(_))) => {
+                        block.push_str(text);
+                    }
                     _ => {
                         recompose_sentence(text, &mut recipient);
                     }
@@ -333,7 +308,90 @@ pub fn enrich(text: &str) -> Result<String> {
     Ok(recipient)
 }
 
+/// The kind of fenced code block, from its info string.
+///
+/// The language and the `target` attribute decide how the block is rendered. To add a new target, add a variant and
+/// cover it in [`Block::opening`], [`Block::closing`] and [`Block::render`].
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Dot,
+    Table(Info),
+    Card(Info),
+    /// Code in a language with a highlighter, only when highlighting.
+    Code(Language),
+    /// Any other block, kept as it is.
+    Verbatim,
+}
+
+impl Block {
+    fn from_info(info: &str, highlight: bool) -> Self {
+        let info = Info::parse(info);
+
+        match (info.lang.as_str(), info.target()) {
+            ("dot", None) => Block::Dot,
+            ("csv", Some("table")) => Block::Table(info),
+            ("csv", Some("card")) | ("toml", Some("card")) => Block::Card(info),
+            (lang, None) if highlight => match lang.parse() {
+                Ok(language) => Block::Code(language),
+                Err(_) => Block::Verbatim,
+            },
+            _ => Block::Verbatim,
+        }
+    }
+
+    /// The opening tags for blocks rendered as HTML.
+    ///
+    /// Highlighted code starts right after the tags as any line break would become part of the code.
+    fn opening(&self) -> Option<String> {
+        match self {
+            Block::Dot => Some("<div class=\"figure from-dot\">\n".to_string()),
+            Block::Table(info) => Some(format!(
+                "<div class=\"table-wrapper from-{}\">\n",
+                info.lang
+            )),
+            Block::Card(info) => Some(format!("<div class=\"card-wrapper from-{}\">\n", info.lang)),
+            Block::Code(language) => Some(highlight::opening(*language)),
+            Block::Verbatim => None,
+        }
+    }
+
+    fn closing(&self) -> &'static str {
+        match self {
+            Block::Code(_) => "</code></pre>\n",
+            Block::Verbatim => "```\n",
+            _ => "</div>\n",
+        }
+    }
+
+    fn render(&self, text: &str) -> Result<String> {
+        match self {
+            Block::Dot => process_graphviz(text),
+            Block::Table(info) => table::render(text, info),
+            Block::Card(info) => card::render(text, info),
+            Block::Code(language) => Ok(highlight::render(*language, text)),
+            Block::Verbatim => Ok(text.to_string()),
+        }
+    }
+}
+
+/// Renders a DOT diagram as SVG in process.
+///
+/// With the `graphviz` feature, diagrams the native renderer cannot handle are handed over to the `dot` binary.
 fn process_graphviz(input: &str) -> Result<String> {
+    match dot::render(input) {
+        Ok(svg) => Ok(svg),
+        #[cfg(feature = "graphviz")]
+        Err(err) => {
+            log::warn!("{}. Falling back to the dot binary.", err);
+            external_graphviz(input)
+        }
+        #[cfg(not(feature = "graphviz"))]
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(feature = "graphviz")]
+fn external_graphviz(input: &str) -> Result<String> {
     let mut child = Command::new("dot")
         .arg("-Tsvg")
         .stdin(Stdio::piped())
@@ -392,7 +450,7 @@ digraph g {
 }";
         let actual = process_graphviz(text)?;
 
-        assert!(actual.len() > 0);
+        assert!(!actual.is_empty());
 
         Ok(())
     }
@@ -406,9 +464,9 @@ A paragraph with **inline** `stuff`.
 ## Heading 2
 
 A final paragraph"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -418,9 +476,9 @@ A final paragraph"#;
         let text = r#"# Heading `stuff`
 
 A paragraph with `stuff`."#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -432,9 +490,9 @@ digraph g {
   A -> B;
 }
 ```"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -446,9 +504,9 @@ digraph g {
   A -> B;
 }
 ```"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -464,9 +522,9 @@ digraph g {
 
 Another paragraph.
 ```"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -481,9 +539,9 @@ Another paragraph.
         let expected = r#"> This is a blockquote with more than one line. and _inline_ marks, and [a link](foo.html)
 >
 > Final blockquote paragraph."#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
 
         Ok(())
     }
@@ -498,7 +556,7 @@ digraph g {
 }
 ```
 "#;
-        let actual = enrich(text);
+        let actual = enrich(text, false);
 
         assert!(actual.is_ok());
 
@@ -508,9 +566,9 @@ digraph g {
     #[test]
     fn preserve_plain_links() -> Result<()> {
         let text = r#"[text](http://foo.bar)"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -518,9 +576,9 @@ digraph g {
     #[test]
     fn preserve_links_with_inline() -> Result<()> {
         let text = r#"[text _and_ more text and `code`](http://foo.bar)"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -531,9 +589,9 @@ digraph g {
 
 [text]: http://foo.bar"#;
         let expected = "[text](http://foo.bar)";
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
 
         Ok(())
     }
@@ -544,9 +602,9 @@ digraph g {
 
 [text]: http://foo.bar"#;
         let expected = "[[text](http://foo.bar)]";
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
 
         Ok(())
     }
@@ -555,9 +613,9 @@ digraph g {
     fn preserve_links_with_square_parens() -> Result<()> {
         let text = r#"[[text](http://foo.bar)]"#;
         let expected = "[[text](http://foo.bar)]";
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
 
         Ok(())
     }
@@ -566,9 +624,9 @@ digraph g {
     fn preserve_links_in_sentences() -> Result<()> {
         let text = r#"A [link](http://foo.bar). And a text."#;
 
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -580,9 +638,9 @@ then more with [a link](http://foo.bar)
 and yet another line"#;
         let expected =
             r#"A bit of text then more with [a link](http://foo.bar) and yet another line"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
 
         Ok(())
     }
@@ -590,9 +648,9 @@ and yet another line"#;
     #[test]
     fn preserve_rich_paragraphs() -> Result<()> {
         let text = r#"A paragraph with ~~strikethrough~~, _emphasis_ and **strong**. As well as `code` and a [link](https://foo)."#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -600,9 +658,9 @@ and yet another line"#;
     #[test]
     fn preserve_paragraph_with_marks() -> Result<()> {
         let text = r#"In words of the RFC8288, “[...] a link is a typed connection between two resources [...]”."#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -612,9 +670,9 @@ and yet another line"#;
         let text = r#"A paragraph
 
 ![](foo.png)"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -624,9 +682,9 @@ and yet another line"#;
         let text = r#"- item1
 - item2
 - item3"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -638,9 +696,9 @@ and yet another line"#;
   - item21
   - item22
 - item3"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -652,9 +710,9 @@ and yet another line"#;
    - item21 _with_ some **rich** content.
    - item22
 3. item3"#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -666,9 +724,9 @@ and yet another line"#;
 - item3
 
 A paragraph."#;
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), text);
+        assert_eq!(actual.trim(), text);
 
         Ok(())
     }
@@ -681,9 +739,9 @@ A paragraph."#;
 | --- | --- |
 | baz | bim |"#;
         let expected = "A basic table:\n\n| foo | bar |\n|-|-|\n| baz | bim |";
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
 
         Ok(())
     }
@@ -696,14 +754,13 @@ A paragraph."#;
 | :-- | --: |
 | baz | bim |"#;
         let expected = "A basic table:\n\n| foo | bar |\n|:-|-:|\n| baz | bim |";
-        let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
 
-        assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
 
         Ok(())
     }
 
-    // TODO: Implement
     #[test]
     fn process_csv_table() -> Result<()> {
         let text = r#"```csv target=table
@@ -727,9 +784,127 @@ baz,bim
 </table>
 </div>"#;
 
-        // let actual = enrich(text)?;
+        let actual = enrich(text, false)?;
+
+        assert_eq!(actual.trim(), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn process_crlf_csv_table() -> Result<()> {
+        let text = "```csv target=table\r\na,b\r\n1,2\r\n```";
+        let actual = enrich(text, false)?;
+
+        assert_eq!(actual.matches("<table>").count(), 1);
+        assert!(actual.contains("<th>a</th>"));
+        assert!(actual.contains("<td>2</td>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn process_nested_csv_table() -> Result<()> {
+        let text = "- item\n\n  ```csv target=table\n  a,b\n  1,2\n  ```";
+        let actual = enrich(text, false)?;
+
+        assert_eq!(actual.matches("<table>").count(), 1);
+        assert!(actual.contains("<th>a</th>"));
+        assert!(actual.contains("<td>2</td>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn process_csv_card() -> Result<()> {
+        let text = r#"```csv target=card
+title,url,language
+Zola,https://www.getzola.org/,Rust
+```
+
+A paragraph."#;
+        let expected = r#"<div class="card-wrapper from-csv">
+<div class="card">
+<div class="card-title"><a href="https://www.getzola.org/">Zola</a></div>
+<dl>
+   <dt>language</dt>
+   <dd>Rust</dd>
+</dl>
+</div>
+</div>
+
+A paragraph."#;
+        let actual = enrich(text, false)?;
+
+        assert_eq!(actual.trim(), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn process_nested_card() -> Result<()> {
+        let text = "> ```csv target=card\n> title,url,language\n> Zola,https://www.getzola.org/,Rust\n> ```";
+        let actual = enrich(text, false)?;
+
+        assert!(actual.contains(
+            r#"<div class="card-title"><a href="https://www.getzola.org/">Zola</a></div>"#
+        ));
+        assert!(actual.contains("<dd>Rust</dd>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn process_nested_graphviz() -> Result<()> {
+        let text = "> ```dot\n> digraph g {\n>   A -> B;\n> }\n> ```";
+        let actual = enrich(text, false)?;
+
+        assert!(actual.contains("<svg"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn process_toml_card() -> Result<()> {
+        let text = r#"```toml target=card title=name
+name = "SQLite"
+language = "C"
+```"#;
+        let expected = r#"<div class="card-wrapper from-toml">
+<div class="card">
+<div class="card-title">SQLite</div>
+<dl>
+   <dt>language</dt>
+   <dd>C</dd>
+</dl>
+</div>
+</div>"#;
+        let actual = enrich(text, false)?;
+
+        assert_eq!(actual.trim(), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn process_highlighted_code() -> Result<()> {
+        let text = r#"```rust
+fn main() {}
+```
+
+```abnf
+rule = "a"
+```"#;
+        let expected = r#"<pre class="highlight" data-lang="rust"><code class="language-rust" data-lang="rust"><span class="hl-keyword">fn</span> <span class="hl-function">main</span>() {}
+</code></pre>
+
+```abnf
+rule = "a"
+```"#;
+        let actual = enrich(text, true)?;
 
-        // assert_eq!((&actual).trim(), expected);
+        assert_eq!(actual.trim(), expected);
+        assert!(enrich(text, false)?.contains("```rust\nfn main() {}\n```"));
 
         Ok(())
     }
@@ -0,0 +1,172 @@
+//! This module renders CSV blocks as HTML tables.
+
+use anyhow::{anyhow, bail, Result};
+use pulldown_cmark::escape::escape_html;
+use std::str::FromStr;
+
+use super::info::Info;
+
+/// The alignment for a column, as set with the `align` attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+impl FromStr for Alignment {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        match input.trim() {
+            "" | "none" => Ok(Alignment::None),
+            "left" => Ok(Alignment::Left),
+            "center" => Ok(Alignment::Center),
+            "right" => Ok(Alignment::Right),
+            s => bail!(
+                "unknown alignment '{}'. Expected one of: left, center, right, none",
+                s
+            ),
+        }
+    }
+}
+
+impl Alignment {
+    fn style(&self) -> &str {
+        match self {
+            Alignment::None => "",
+            Alignment::Left => " style=\"text-align: left\"",
+            Alignment::Center => " style=\"text-align: center\"",
+            Alignment::Right => " style=\"text-align: right\"",
+        }
+    }
+}
+
+/// Renders the CSV text as a table.
+///
+/// The info string accepts:
+///
+/// - `header=false` to treat the first row as data instead of the table head.
+/// - `caption="…"` for the table caption.
+/// - `align=left,right,…` with the alignment for each column in order. Columns without one are left to the stylesheet.
+pub fn render(text: &str, info: &Info) -> Result<String> {
+    let header = match info.attribute("header") {
+        None | Some("true") => true,
+        Some("false") => false,
+        Some(value) => bail!("invalid header '{}'. Expected true or false", value),
+    };
+    let alignments = info
+        .attribute("align")
+        .map(|value| value.split(',').map(Alignment::from_str).collect())
+        .transpose()?
+        .unwrap_or_else(Vec::new);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    let mut rows = Vec::new();
+
+    for result in reader.records() {
+        let record = result.map_err(|err| anyhow!("malformed CSV table: {}", err))?;
+        rows.push(record);
+    }
+
+    let mut html = String::from("<table>\n");
+
+    if let Some(caption) = info.attribute("caption") {
+        html.push_str("<caption>");
+        escape_html(&mut html, caption)?;
+        html.push_str("</caption>\n");
+    }
+
+    let mut rows = rows.iter();
+
+    if header {
+        if let Some(record) = rows.next() {
+            html.push_str("<thead>\n");
+            write_row(&mut html, record, "th", &alignments)?;
+            html.push_str("</thead>\n");
+        }
+    }
+
+    html.push_str("<tbody>\n");
+    for record in rows {
+        write_row(&mut html, record, "td", &alignments)?;
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    Ok(html)
+}
+
+fn write_row(
+    html: &mut String,
+    record: &csv::StringRecord,
+    cell: &str,
+    alignments: &[Alignment],
+) -> Result<()> {
+    html.push_str("   <tr>\n");
+
+    for (i, value) in record.iter().enumerate() {
+        let alignment = alignments.get(i).unwrap_or(&Alignment::None);
+
+        html.push_str(&format!("     <{}{}>", cell, alignment.style()));
+        escape_html(&mut *html, value)?;
+        html.push_str(&format!("</{}>\n", cell));
+    }
+
+    html.push_str("   </tr>\n");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_without_header() -> Result<()> {
+        let info = Info::parse("csv target=table header=false");
+        let actual = render("a,b\n", &info)?;
+
+        assert_eq!(
+            actual,
+            "<table>\n<tbody>\n   <tr>\n     <td>a</td>\n     <td>b</td>\n   </tr>\n</tbody>\n</table>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_caption_and_alignment() -> Result<()> {
+        let info = Info::parse(r#"csv target=table caption="Fish & chips" align=left,right"#);
+        let actual = render("name, price\n<cod>, 9\n", &info)?;
+
+        assert!(actual.starts_with("<table>\n<caption>Fish &amp; chips</caption>\n<thead>\n"));
+        assert!(actual.contains(r#"<th style="text-align: left">name</th>"#));
+        assert!(actual.contains(r#"<th style="text-align: right">price</th>"#));
+        assert!(actual.contains(r#"<td style="text-align: left">&lt;cod&gt;</td>"#));
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_malformed() {
+        let info = Info::parse("csv target=table");
+        let actual = render("a,b\nc\n", &info);
+
+        assert!(actual
+            .unwrap_err()
+            .to_string()
+            .starts_with("malformed CSV table:"));
+
+        let info = Info::parse("csv target=table align=top");
+        let actual = render("a,b\n", &info);
+
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "unknown alignment 'top'. Expected one of: left, center, right, none"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! This module computes the heading anchors and the table of contents of a Markdown text.
+//!
+//! Anchors follow the rules Zola applies so the ids match the rendered page: an explicit `{#id}` wins, otherwise the
+//! heading text is slugified and, when taken, suffixed with `-1`, `-2` and so on.
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A heading in the table of contents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    pub id: String,
+}
+
+/// A problem with the anchors of a text. Zola copes with both so they are only worth a warning.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnchorWarning {
+    /// More than one heading would get the same anchor.
+    Duplicate(String),
+    /// A link points at an anchor no heading has.
+    Missing(String),
+}
+
+impl fmt::Display for AnchorWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnchorWarning::Duplicate(id) => write!(f, "duplicate anchor '#{}'", id),
+            AnchorWarning::Missing(id) => write!(f, "link to missing anchor '#{}'", id),
+        }
+    }
+}
+
+/// The headings of a text in order of appearance plus any problem found with its anchors.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Toc {
+    pub headings: Vec<Heading>,
+    pub warnings: Vec<AnchorWarning>,
+}
+
+impl Toc {
+    pub fn new(text: &str) -> Self {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+
+        // Each heading with its explicit id, if any.
+        let mut found: Vec<(usize, String, Option<String>)> = Vec::new();
+        let mut links: Vec<String> = Vec::new();
+        let mut current: Option<(usize, String, Option<String>)> = None;
+
+        for event in Parser::new_ext(text, options) {
+            match event {
+                Event::Start(Tag::Heading(level, id, _)) => {
+                    current = Some((level as usize, String::new(), id.map(String::from)));
+                }
+                Event::End(Tag::Heading(..)) => {
+                    if let Some((level, title, id)) = current.take() {
+                        found.push((level, title.trim().to_string(), id));
+                    }
+                }
+                Event::Text(text) | Event::Code(text) => {
+                    if let Some((_, title, _)) = current.as_mut() {
+                        title.push_str(&text);
+                    }
+                }
+                Event::Start(Tag::Link(_, url, _)) => {
+                    if let Some(fragment) = url.strip_prefix('#') {
+                        links.push(fragment.to_string());
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut toc = Toc::default();
+        // Explicit ids are taken before any slug is computed.
+        let mut anchors: Vec<String> = found.iter().filter_map(|(_, _, id)| id.clone()).collect();
+
+        for (i, anchor) in anchors.iter().enumerate() {
+            if anchors[..i].contains(anchor) {
+                toc.push_warning(AnchorWarning::Duplicate(anchor.clone()));
+            }
+        }
+
+        for (level, title, id) in found {
+            let id = match id {
+                Some(id) => id,
+                None => {
+                    let slug = slug::slugify(&title);
+
+                    if anchors.contains(&slug) {
+                        toc.push_warning(AnchorWarning::Duplicate(slug.clone()));
+                    }
+
+                    let id = find_anchor(&anchors, slug);
+                    anchors.push(id.clone());
+
+                    id
+                }
+            };
+
+            toc.headings.push(Heading { level, title, id });
+        }
+
+        for link in links {
+            if !link.is_empty() && !anchors.contains(&link) {
+                toc.push_warning(AnchorWarning::Missing(link));
+            }
+        }
+
+        toc
+    }
+
+    fn push_warning(&mut self, warning: AnchorWarning) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+}
+
+/// Finds the first free anchor for the slug, adding a numeric suffix when taken.
+fn find_anchor(anchors: &[String], slug: String) -> String {
+    if !anchors.contains(&slug) {
+        return slug;
+    }
+
+    (1..)
+        .map(|n| format!("{}-{}", slug, n))
+        .find(|anchor| !anchors.contains(anchor))
+        .expect("a free anchor")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headings() {
+        let text = r#"
+Go to the [closing thoughts](#closing-thoughts).
+
+## What is `CSVW`?
+
+### Café & crème
+
+## Closing thoughts
+"#;
+        let toc = Toc::new(text);
+
+        assert_eq!(
+            toc.headings,
+            vec![
+                Heading {
+                    level: 2,
+                    title: "What is CSVW?".into(),
+                    id: "what-is-csvw".into()
+                },
+                Heading {
+                    level: 3,
+                    title: "Café & crème".into(),
+                    id: "cafe-creme".into()
+                },
+                Heading {
+                    level: 2,
+                    title: "Closing thoughts".into(),
+                    id: "closing-thoughts".into()
+                },
+            ]
+        );
+        assert!(toc.warnings.is_empty());
+    }
+
+    #[test]
+    fn duplicates_and_missing_anchors() {
+        let text = r#"
+## Notes
+
+## Notes
+
+## Other {#notes-1}
+
+See [above](#notes), [below](#nowhere) and [again](#nowhere).
+"#;
+        let toc = Toc::new(text);
+        let ids: Vec<&str> = toc.headings.iter().map(|h| h.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["notes", "notes-2", "notes-1"]);
+        assert_eq!(
+            toc.warnings,
+            vec![
+                AnchorWarning::Duplicate("notes".into()),
+                AnchorWarning::Missing("nowhere".into())
+            ]
+        );
+        assert_eq!(
+            toc.warnings[1].to_string(),
+            "link to missing anchor '#nowhere'"
+        );
+    }
+}
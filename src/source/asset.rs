@@ -1,7 +1,6 @@
 //! This module defines the asset for the Source stage.
 
 use anyhow::Result;
-use std::convert::TryFrom;
 use std::iter::FromIterator;
 
 use crate::cache::records::*;
@@ -103,8 +102,9 @@ impl WriteCache for AssetSet {
     type Item = Asset;
 
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
-        let record = AssetRecord::try_from(resource)?;
+        let record = AssetRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "asset").insert(tx)?;
 
         Ok(())
     }
@@ -112,6 +112,19 @@ impl WriteCache for AssetSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         AssetRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match AssetRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "asset").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for AssetSet {}
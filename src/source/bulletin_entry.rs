@@ -1,9 +1,17 @@
 //! This module defines bulletin for the Source stage.
 
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use crate::cache::records::*;
+use crate::cache::Transaction;
 use crate::checksum::{Digest, Hasher};
+use crate::source::SourceError;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BulletinEntry {
@@ -12,7 +20,229 @@ pub struct BulletinEntry {
     title: String,
     #[serde(deserialize_with = "super::de_trim")]
     summary: String,
-    content_type: String,
+    content_type: ContentType,
+    /// The running time for timed content, as written. E.g. `1:02:30`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration: Option<String>,
+    /// The authors of a paper.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "super::de_unique",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    tags: Vec<String>,
+    /// The places where the link was found, to credit them.
+    #[serde(
+        default,
+        alias = "via",
+        deserialize_with = "super::de_unique",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    mentions: Vec<String>,
+}
+
+impl BulletinEntry {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Checks that the optional fields belong to the content type.
+    pub fn validate(&self) -> Result<()> {
+        if self.duration.is_some() && !self.content_type.is_timed() {
+            bail!(
+                "entry '{}' is {} but only video, audio and podcast entries have a duration",
+                self.url,
+                self.content_type
+            );
+        }
+
+        if !self.authors.is_empty() && self.content_type != ContentType::Paper {
+            bail!(
+                "entry '{}' is {} but only paper entries have authors",
+                self.url,
+                self.content_type
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn mentions(&self) -> &[String] {
+        &self.mentions
+    }
+}
+
+/// The kind of content a bulletin entry links to.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum ContentType {
+    Text,
+    Video,
+    Audio,
+    Podcast,
+    Paper,
+    Repository,
+    Tool,
+}
+
+impl ContentType {
+    pub const ALL: [ContentType; 7] = [
+        ContentType::Text,
+        ContentType::Video,
+        ContentType::Audio,
+        ContentType::Podcast,
+        ContentType::Paper,
+        ContentType::Repository,
+        ContentType::Tool,
+    ];
+
+    /// Whether the content has a running time.
+    pub fn is_timed(&self) -> bool {
+        matches!(
+            self,
+            ContentType::Video | ContentType::Audio | ContentType::Podcast
+        )
+    }
+}
+
+impl fmt::Display for ContentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ContentType::*;
+
+        let s = match self {
+            Text => "text",
+            Video => "video",
+            Audio => "audio",
+            Podcast => "podcast",
+            Paper => "paper",
+            Repository => "repository",
+            Tool => "tool",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for ContentType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match ContentType::ALL.iter().find(|typ| typ.to_string() == s) {
+            Some(typ) => Ok(*typ),
+            None => {
+                let expected: Vec<String> =
+                    ContentType::ALL.iter().map(|typ| typ.to_string()).collect();
+
+                bail!(
+                    "unknown content type '{}'. Expected one of: {}",
+                    s,
+                    expected.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for ContentType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        ContentType::from_str(&s)
+    }
+}
+
+impl Digest for ContentType {
+    fn digest(&self, hasher: &mut Hasher) {
+        self.to_string().digest(hasher);
+    }
+}
+
+/// Normalises a URL so the same link is recognised regardless of its scheme, trailing slash or `utm_*` parameters.
+pub fn normalise_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (url, None),
+    };
+    let params: Vec<&str> = query
+        .map(|query| {
+            query
+                .split('&')
+                .filter(|param| !param.is_empty() && !param.starts_with("utm_"))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut normalised = path.trim_end_matches('/').to_string();
+
+    if !params.is_empty() {
+        normalised.push('?');
+        normalised.push_str(&params.join("&"));
+    }
+
+    if let Some(fragment) = fragment {
+        normalised.push('#');
+        normalised.push_str(fragment);
+    }
+
+    normalised
+}
+
+/// Keeps track of the bulletin entries seen across files to find the same link posted more than once.
+#[derive(Debug, Default)]
+pub struct EntryIndex {
+    seen: HashMap<String, PathBuf>,
+}
+
+impl EntryIndex {
+    /// Records the entries found in the given file, returning a [`SourceError::Duplicate`] for each one seen before.
+    pub fn record(&mut self, path: &Path, entries: &[BulletinEntry]) -> Vec<SourceError> {
+        let mut duplicates = Vec::new();
+
+        for entry in entries {
+            let key = normalise_url(&entry.url);
+
+            match self.seen.get(&key) {
+                Some(other) => duplicates.push(SourceError::Duplicate {
+                    path: path.to_path_buf(),
+                    other: other.clone(),
+                    url: entry.url.clone(),
+                }),
+                None => {
+                    self.seen.insert(key, path.to_path_buf());
+                }
+            }
+        }
+
+        duplicates
+    }
+
+    /// Records the entries like [`EntryIndex::record`] and drops the ones seen before, so only the first occurrence of
+    /// a link gets cached.
+    pub fn dedupe(&mut self, path: &Path, entries: &mut Vec<BulletinEntry>) -> Vec<SourceError> {
+        let mut duplicates = Vec::new();
+
+        entries.retain(|entry| {
+            let mut found = self.record(path, std::slice::from_ref(entry));
+            let first = found.is_empty();
+            duplicates.append(&mut found);
+
+            first
+        });
+
+        duplicates
+    }
 }
 
 impl Digest for BulletinEntry {
@@ -21,6 +251,10 @@ impl Digest for BulletinEntry {
         self.title.digest(hasher);
         self.summary.digest(hasher);
         self.content_type.digest(hasher);
+        self.duration.digest(hasher);
+        self.authors.digest(hasher);
+        self.tags.digest(hasher);
+        self.mentions.digest(hasher);
     }
 }
 
@@ -34,17 +268,220 @@ impl From<(Option<String>, &BulletinEntry)> for BulletinEntryRecord {
             title: resource.title.to_string(),
             summary: resource.summary.to_string(),
             content_type: resource.content_type.to_string(),
+            duration: resource.duration.clone(),
         }
     }
 }
 
-impl From<BulletinEntryRecord> for BulletinEntry {
-    fn from(record: BulletinEntryRecord) -> Self {
-        Self {
-            url: record.url,
-            title: record.title,
-            summary: record.summary,
-            content_type: record.content_type,
-        }
+fn from_record(tx: &Transaction, record: BulletinEntryRecord) -> Result<BulletinEntry> {
+    let tags: Vec<String> =
+        TagRecordSet::select(tx, (TagTable::BulletinEntry, record.url.clone()))?
+            .into_iter()
+            .map(|record| record.tag)
+            .collect();
+    let authors: Vec<String> = BulletinEntryAuthorRecordSet::select(tx, record.url.clone())?
+        .into_iter()
+        .map(|record| record.name)
+        .collect();
+    let mentions: Vec<String> = BulletinMentionRecordSet::select(tx, record.url.clone())?
+        .into_iter()
+        .map(|record| record.mention_url)
+        .collect();
+    let resource = BulletinEntry {
+        url: record.url,
+        title: record.title,
+        summary: record.summary,
+        content_type: ContentType::from_str(&record.content_type)?,
+        duration: record.duration,
+        authors,
+        tags,
+        mentions,
+    };
+
+    Ok(resource)
+}
+
+/// Reads the entries for the given issue or the stash if none is given.
+pub(super) fn select(tx: &Transaction, issue_id: Option<String>) -> Result<Vec<BulletinEntry>> {
+    BulletinEntryRecordSet::select(tx, issue_id)?
+        .into_iter()
+        .map(|record| from_record(tx, record))
+        .collect()
+}
+
+/// Adds the entry for the given issue, or the stash if none is given, along with its authors, tags and mentions.
+pub(super) fn add(tx: &Transaction, issue_id: Option<String>, entry: &BulletinEntry) -> Result<()> {
+    // Tags removed from the entry would otherwise linger.
+    TagRecordSet::delete(tx, TagTable::BulletinEntry, &entry.url)?;
+
+    for tag in &entry.tags {
+        let record = TagRecord {
+            table: TagTable::BulletinEntry,
+            resource_id: entry.url.clone(),
+            tag: tag.clone(),
+        };
+
+        record.insert(tx)?;
+    }
+
+    BulletinEntryAuthorRecordSet::delete(tx, &entry.url)?;
+
+    for name in &entry.authors {
+        let record = BulletinEntryAuthorRecord {
+            entry_url: entry.url.clone(),
+            name: name.clone(),
+        };
+
+        record.insert(tx)?;
+    }
+
+    BulletinMentionRecordSet::delete(tx, &entry.url)?;
+
+    for mention in &entry.mentions {
+        let record = BulletinMentionRecord {
+            mention_url: mention.clone(),
+            entry_url: entry.url.clone(),
+        };
+
+        record.insert(tx)?;
+    }
+
+    let record = BulletinEntryRecord::from((issue_id, entry));
+    record.insert(tx)?;
+    SessionTrailRecord::new(&record.checksum, "bulletin_entry").insert(tx)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+
+    #[test]
+    fn normalise() {
+        assert_eq!(normalise_url("https://napi.rs/"), "napi.rs");
+        assert_eq!(
+            normalise_url("http://example.org/a/?utm_source=x&id=1&utm_medium=y#top"),
+            "example.org/a?id=1#top"
+        );
+        assert_eq!(
+            normalise_url("https://example.org/a?utm_campaign=z"),
+            normalise_url("http://example.org/a/")
+        );
+    }
+
+    #[test]
+    fn content_types() -> Result<()> {
+        let raw = r#"url = "https://example.org/"
+title = "Example"
+summary = "An example."
+content_type = "slides"
+"#;
+        let err = toml::from_str::<BulletinEntry>(raw).expect_err("slides to be unknown");
+
+        assert!(err.to_string().contains(
+            "unknown content type 'slides'. Expected one of: text, video, audio, podcast, paper, repository, tool"
+        ));
+
+        let entry: BulletinEntry = toml::from_str(
+            &raw.replace("slides", "text")
+                .replace("An example.\"", "An example.\"\nduration = \"3:00\""),
+        )?;
+        let err = entry.validate().expect_err("text to have no duration");
+
+        assert_eq!(
+            err.to_string(),
+            "entry 'https://example.org/' is text but only video, audio and podcast entries have a duration"
+        );
+
+        let entry: BulletinEntry = toml::from_str(
+            &raw.replace("slides", "podcast")
+                .replace("An example.\"", "An example.\"\nduration = \"3:00\""),
+        )?;
+
+        assert!(entry.validate().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_mentions() -> Result<()> {
+        let raw = r#"url = "https://napi.rs/"
+title = "napi-rs"
+summary = "A library for building pre-compiled NodeJS addons in Rust."
+content_type = "text"
+tags = ["rust", "nodejs", "rust"]
+via = ["https://news.ycombinator.com/", "https://this-week-in-rust.org/", "https://news.ycombinator.com/"]
+"#;
+        let entry: BulletinEntry = toml::from_str(raw)?;
+
+        assert_eq!(
+            entry.mentions,
+            [
+                "https://news.ycombinator.com/",
+                "https://this-week-in-rust.org/"
+            ]
+        );
+        assert_eq!(entry.tags(), ["rust", "nodejs"]);
+
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        add(&tx, None, &entry)?;
+
+        assert_eq!(select(&tx, None)?, vec![entry]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn record_duplicates() {
+        let entry = |url: &str| BulletinEntry {
+            url: url.to_string(),
+            title: "Title".to_string(),
+            summary: "Summary".to_string(),
+            content_type: ContentType::Text,
+            duration: None,
+            authors: Vec::new(),
+            tags: Vec::new(),
+            mentions: Vec::new(),
+        };
+        let mut index = EntryIndex::default();
+
+        assert!(index
+            .record(Path::new("2021-W01.toml"), &[entry("https://napi.rs/")])
+            .is_empty());
+
+        let duplicates = index.record(Path::new("stash.toml"), &[entry("http://napi.rs")]);
+
+        assert_eq!(
+            duplicates[0].to_string(),
+            "stash.toml: bulletin entry 'http://napi.rs' is already in 2021-W01.toml"
+        );
+    }
+
+    #[test]
+    fn dedupe_entries() {
+        let entry = |url: &str| BulletinEntry {
+            url: url.to_string(),
+            title: "Title".to_string(),
+            summary: "Summary".to_string(),
+            content_type: ContentType::Text,
+            duration: None,
+            authors: Vec::new(),
+            tags: Vec::new(),
+            mentions: Vec::new(),
+        };
+        let mut index = EntryIndex::default();
+        let mut first = vec![entry("https://napi.rs/"), entry("https://napi.rs/")];
+        let mut second = vec![entry("http://napi.rs"), entry("https://example.org/")];
+
+        assert_eq!(
+            index.dedupe(Path::new("2021-W01.toml"), &mut first).len(),
+            1
+        );
+        assert_eq!(index.dedupe(Path::new("stash.toml"), &mut second).len(), 1);
+        assert_eq!(first, vec![entry("https://napi.rs/")]);
+        assert_eq!(second, vec![entry("https://example.org/")]);
     }
 }
@@ -4,26 +4,83 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::iter::FromIterator;
+use std::path::Path;
 use std::str::FromStr;
 
-use super::BulletinEntry;
+use super::{bulletin_entry, BulletinEntry, SourceError};
 use crate::cache::records::*;
 use crate::cache::{ReadCache, Transaction, WriteCache};
 use crate::checksum::{Digest, Hasher};
+use crate::stamp::{Date, IsoWeek};
 use crate::{Resource, ResourceSet};
 
 /// A bulletin issue resource.
+///
+/// The id is the ISO week the issue covers, e.g. `2021-W02`, and the publication date is expected to fall within it.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Bulletin {
     #[serde(rename = "type")]
     _type: String,
     id: String,
-    publication_date: String,
+    publication_date: Date,
     #[serde(deserialize_with = "super::de_trim")]
     summary: String,
+    /// Drafts are kept out of the output unless drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    draft: bool,
     entries: Vec<BulletinEntry>,
 }
 
+impl Bulletin {
+    pub fn entries(&self) -> &[BulletinEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn entries_mut(&mut self) -> &mut Vec<BulletinEntry> {
+        &mut self.entries
+    }
+
+    pub fn is_draft(&self) -> bool {
+        self.draft
+    }
+
+    /// The ISO week the issue covers.
+    pub fn week(&self) -> IsoWeek {
+        IsoWeek::from_str(&self.id).expect("id to be an ISO week")
+    }
+
+    /// Flags a publication date outside the week the issue covers, for the file the issue was read from.
+    pub(crate) fn misdated(&self, path: &Path) -> Option<SourceError> {
+        let week = self.week();
+
+        if week.contains(&self.publication_date) {
+            return None;
+        }
+
+        Some(SourceError::Misdated {
+            path: path.to_path_buf(),
+            week,
+            publication_date: self.publication_date,
+        })
+    }
+
+    pub fn new(
+        week: IsoWeek,
+        publication_date: Date,
+        summary: String,
+        entries: Vec<BulletinEntry>,
+    ) -> Self {
+        Self {
+            _type: "bulletin".to_string(),
+            id: week.to_string(),
+            publication_date,
+            summary,
+            draft: false,
+            entries,
+        }
+    }
+}
+
 impl Resource for Bulletin {
     type Id = String;
 
@@ -37,6 +94,7 @@ impl Digest for Bulletin {
         self.id.digest(hasher);
         self.publication_date.digest(hasher);
         self.summary.digest(hasher);
+        self.draft.digest(hasher);
         self.entries.digest(hasher);
     }
 }
@@ -47,6 +105,12 @@ impl FromStr for Bulletin {
     fn from_str(blob: &str) -> Result<Self, Self::Err> {
         let resource: Bulletin = toml::from_str(blob)?;
 
+        for entry in &resource.entries {
+            entry.validate()?;
+        }
+
+        IsoWeek::from_str(&resource.id)?;
+
         Ok(resource)
     }
 }
@@ -66,21 +130,19 @@ impl From<Bulletin> for BulletinRecord {
             id: resource.id,
             summary: resource.summary,
             publication_date: resource.publication_date.to_string(),
+            draft: resource.draft,
         }
     }
 }
 
 fn from_record(tx: &Transaction, record: BulletinRecord) -> Result<Bulletin> {
-    let entries: Vec<BulletinEntry> =
-        BulletinEntryRecordSet::select(tx, Some(record.id.to_string()))?
-            .into_iter()
-            .map(BulletinEntry::from)
-            .collect();
+    let entries = bulletin_entry::select(tx, Some(record.id.to_string()))?;
     let resource = Bulletin {
         _type: "bulletin".to_string(),
         id: record.id,
-        publication_date: record.publication_date,
+        publication_date: Date::from_str(&record.publication_date)?,
         summary: record.summary,
+        draft: record.draft,
         entries,
     };
 
@@ -127,12 +189,12 @@ impl WriteCache for BulletinSet {
 
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
         for entry in &resource.entries {
-            let record = BulletinEntryRecord::from((Some(resource.id.clone()), entry));
-            record.insert(tx)?;
+            bulletin_entry::add(tx, Some(resource.id.clone()), entry)?;
         }
 
         let record = BulletinRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "bulletin").insert(tx)?;
 
         Ok(())
     }
@@ -140,6 +202,40 @@ impl WriteCache for BulletinSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         BulletinRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match BulletinRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => (),
+            _ => return Ok(false),
+        }
+
+        // An entry can be taken over by another issue or the stash even when this issue is unchanged.
+        let mut checksums = Vec::new();
+
+        for entry in &resource.entries {
+            let checksum = entry.checksum().to_string();
+
+            match BulletinEntryRecord::select(tx, entry.url())? {
+                Some(record)
+                    if record.checksum == checksum
+                        && record.issue_id.as_deref() == Some(resource.id.as_str()) =>
+                {
+                    checksums.push(checksum);
+                }
+                _ => return Ok(false),
+            }
+        }
+
+        SessionTrailRecord::new(&checksum, "bulletin").insert(tx)?;
+
+        for checksum in checksums {
+            SessionTrailRecord::new(&checksum, "bulletin_entry").insert(tx)?;
+        }
+
+        Ok(true)
+    }
 }
 
 impl ResourceSet for BulletinSet {}
@@ -174,7 +270,7 @@ mod tests {
     fn full_cycle() -> Result<()> {
         let raw = r#"type = "bulletin"
 id = "2021-W01"
-publication_date = "2021-01-10"
+publication_date = 2021-01-10
 summary = "This week has been about preql, sqlite, web component styling, apache arrow and hexagonal grids."
 
 [[entries]]
@@ -189,6 +285,20 @@ title = "Hexagon Grids"
 summary = "A guide on how to make hexagonal grids, implement a coordinate system, distances and more."
 content_type = "text"
 
+[[entries]]
+url = "https://www.youtube.com/watch?v=rHIkrotSwcc"
+title = "Stop Writing Classes"
+summary = "A talk on when not to write classes."
+content_type = "video"
+duration = "27:29"
+
+[[entries]]
+url = "https://dl.acm.org/doi/10.1145/3453483.3454055"
+title = "Structured Concurrency"
+summary = "A paper about structuring concurrent programs."
+content_type = "paper"
+authors = ["Jane Doe", "John Roe"]
+
 [[entries]]
 url = "https://github.com/inukshuk/sqleton"
 title = "sqleton"
@@ -212,6 +322,8 @@ url = "https://napi.rs/"
 title = "napi-rs"
 summary = "A library for building pre-compiled NodeJS addons in Rust."
 content_type = "text"
+tags = ["rust", "nodejs"]
+mentions = ["https://this-week-in-rust.org/", "https://news.ycombinator.com/"]
 "#;
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
@@ -228,4 +340,29 @@ content_type = "text"
 
         Ok(())
     }
+
+    #[test]
+    fn week_mismatch() -> Result<()> {
+        let raw = r#"type = "bulletin"
+id = "2021-W52"
+publication_date = 2022-01-03
+summary = "A summary."
+entries = []
+"#;
+        let path = Path::new("2021-W52.toml");
+        let err = Bulletin::from_str(raw)?
+            .misdated(path)
+            .expect("the date to be outside the week");
+
+        assert_eq!(
+            err.to_string(),
+            "2021-W52.toml: the publication date 2022-01-03 is not in 2021-W52 which runs from 2021-12-27 to 2022-01-02"
+        );
+        assert!(Bulletin::from_str(&raw.replace("2021-W52", "2021-52")).is_err());
+        assert!(Bulletin::from_str(&raw.replace("2021-W52", "2022-W01"))?
+            .misdated(path)
+            .is_none());
+
+        Ok(())
+    }
 }
@@ -1,14 +1,19 @@
 //! This module defines bulletin stash for the Source stage.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use walkdir::WalkDir;
 
-use super::BulletinEntry;
+use super::{bulletin_entry, is_hidden, Bulletin, BulletinEntry};
 use crate::cache::records::*;
 use crate::cache::{ReadCache, Transaction, WriteCache};
 use crate::checksum::{Digest, Hasher};
+use crate::resource_type::ResourceType;
+use crate::stamp::Date;
 use crate::Resource;
 
 /// A bulletin stash resource.
@@ -19,6 +24,62 @@ pub struct BulletinStash {
     entries: Vec<BulletinEntry>,
 }
 
+impl BulletinStash {
+    pub fn entries(&self) -> &[BulletinEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn entries_mut(&mut self) -> &mut Vec<BulletinEntry> {
+        &mut self.entries
+    }
+
+    /// Moves the entries with the given URLs, or every entry if none is given, into a new issue for the ISO week of the
+    /// given date. The issue is published on the Sunday closing that week.
+    pub fn promote(&mut self, date: &Date, summary: &str, urls: &[String]) -> Result<Bulletin> {
+        for url in urls {
+            if !self.entries.iter().any(|entry| entry.url() == url) {
+                bail!("'{}' is not in the stash", url);
+            }
+        }
+
+        let (chosen, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|entry| urls.is_empty() || urls.iter().any(|url| url == entry.url()));
+        self.entries = kept;
+
+        if chosen.is_empty() {
+            bail!("the stash has no entries to promote");
+        }
+
+        Ok(Bulletin::new(
+            date.iso_week(),
+            date.week_sunday(),
+            summary.to_string(),
+            chosen,
+        ))
+    }
+}
+
+/// Finds the bulletin stash file in the given source.
+pub fn find(source_dir: &Path) -> Result<Option<PathBuf>> {
+    let walker = WalkDir::new(source_dir).sort_by_file_name().into_iter();
+
+    for result in walker.filter_entry(|e| !is_hidden(e)) {
+        let entry = result?;
+        let path = entry.path();
+
+        if path.extension().map(|ext| ext == "toml").unwrap_or(false) {
+            let contents = fs::read_to_string(path)?;
+
+            if let Ok(ResourceType::BulletinStash) = ResourceType::from_hint(&contents) {
+                return Ok(Some(path.to_path_buf()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 impl Resource for BulletinStash {
     type Id = String;
 
@@ -40,6 +101,10 @@ impl FromStr for BulletinStash {
     fn from_str(blob: &str) -> Result<Self, Self::Err> {
         let resource: BulletinStash = toml::from_str(blob)?;
 
+        for entry in &resource.entries {
+            entry.validate()?;
+        }
+
         Ok(resource)
     }
 }
@@ -60,10 +125,7 @@ impl ReadCache for BulletinStash {
     }
 
     fn amass(tx: &Transaction) -> Result<Self> {
-        let entries: Vec<BulletinEntry> = BulletinEntryRecordSet::select(tx, None)?
-            .into_iter()
-            .map(BulletinEntry::from)
-            .collect();
+        let entries = bulletin_entry::select(tx, None)?;
         let resource = Self {
             _type: "bulletin_stash".to_string(),
             entries,
@@ -78,8 +140,7 @@ impl WriteCache for BulletinStash {
 
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
         for entry in &resource.entries {
-            let record = BulletinEntryRecord::from((None, entry));
-            record.insert(tx)?;
+            bulletin_entry::add(tx, None, entry)?;
         }
 
         Ok(())
@@ -88,6 +149,27 @@ impl WriteCache for BulletinStash {
     fn remove(_tx: &Transaction, _id: &str) -> Result<()> {
         unimplemented!()
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let mut checksums = Vec::new();
+
+        for entry in &resource.entries {
+            let checksum = entry.checksum().to_string();
+
+            match BulletinEntryRecord::select(tx, entry.url())? {
+                Some(record) if record.checksum == checksum && record.issue_id.is_none() => {
+                    checksums.push(checksum);
+                }
+                _ => return Ok(false),
+            }
+        }
+
+        for checksum in checksums {
+            SessionTrailRecord::new(&checksum, "bulletin_entry").insert(tx)?;
+        }
+
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +177,40 @@ mod tests {
     use super::*;
     use crate::cache::Cache;
 
+    #[test]
+    fn promote_chosen() -> Result<()> {
+        let raw = r#"type = "bulletin_stash"
+
+[[entries]]
+url = "https://foo.bar"
+title = "Foo"
+summary = "Lorem Ipsum"
+content_type = "text"
+
+[[entries]]
+url = "https://test.dev"
+title = "test dev"
+summary = "Test"
+content_type = "text"
+"#;
+        let mut stash = BulletinStash::from_str(raw)?;
+        let date = Date::from_str("2021-01-06")?;
+        let bulletin = stash.promote(&date, "A summary.", &["https://test.dev".to_string()])?;
+
+        assert_eq!(bulletin.id(), "2021-W01");
+        assert_eq!(BulletinStash::from_str(&stash.to_string())?, stash);
+        assert_eq!(Bulletin::from_str(&bulletin.to_string())?, bulletin);
+        assert_eq!(stash.entries.len(), 1);
+        assert!(bulletin.to_string().starts_with(
+            "type = \"bulletin\"\nid = \"2021-W01\"\npublication_date = 2021-01-10\n"
+        ));
+        assert!(stash
+            .promote(&date, "", &["https://test.dev".to_string()])
+            .is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn full_cycle() -> Result<()> {
         let raw = r#"type = "bulletin_stash"
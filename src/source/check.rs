@@ -0,0 +1,366 @@
+//! This module checks the source for problems without touching the cache.
+//!
+//! Unlike [`super::read`], it does not stop at the first problem. It walks the whole source and reports every problem
+//! found, including references to resources that do not exist.
+
+use anyhow::{bail, Result};
+use log::warn;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::{
+    is_hidden, parse, Bulletin, BulletinStash, Entrance, EntryIndex, Location, Note, Person,
+    Project, Section, Settings, Severity, Sketch, SourceError, Tool,
+};
+use crate::resource_type::ResourceType;
+use crate::Resource;
+
+/// A problem found in a source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub path: PathBuf,
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    fn from_error(path: &Path, err: anyhow::Error) -> Self {
+        match err.downcast::<SourceError>() {
+            Ok(SourceError::Parse {
+                location, message, ..
+            }) => Self {
+                path: path.to_path_buf(),
+                location,
+                message,
+            },
+            Ok(SourceError::Io { source, .. }) => Self::new(path, source.to_string()),
+            Ok(SourceError::Duplicate { other, url, .. }) => Self::new(
+                path,
+                format!("bulletin entry '{}' is already in {}", url, other.display()),
+            ),
+            Ok(SourceError::Misdated {
+                week,
+                publication_date,
+                ..
+            }) => Self::new(
+                path,
+                format!(
+                    "the publication date {} is not in {} which runs from {} to {}",
+                    publication_date,
+                    week,
+                    week.monday(),
+                    week.sunday()
+                ),
+            ),
+            Err(err) => Self::new(path, format!("{:#}", err)),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}:{}: {}", self.path.display(), location, &self.message),
+            None => write!(f, "{}: {}", self.path.display(), &self.message),
+        }
+    }
+}
+
+/// The problems found by [`check`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    problems: Vec<Problem>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    fn push(&mut self, problem: Problem) {
+        self.problems.push(problem);
+    }
+}
+
+/// The kinds of resource a source file can refer to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    /// Assets are not resources on their own so they are referred to by file name.
+    Asset,
+    Person,
+    Tool,
+}
+
+/// A reference from a source file to another resource.
+#[derive(Debug, Clone)]
+struct Reference {
+    path: PathBuf,
+    target: Target,
+    id: String,
+    message: String,
+}
+
+/// The identifiers seen while walking the source.
+#[derive(Debug, Default)]
+struct Index {
+    assets: HashSet<String>,
+    people: HashSet<String>,
+    tools: HashSet<String>,
+    settings: HashSet<String>,
+    entrance: bool,
+    references: Vec<Reference>,
+    entries: EntryIndex,
+    duplicates: Vec<SourceError>,
+    misdated: Vec<SourceError>,
+}
+
+impl Index {
+    fn refer(&mut self, path: &Path, target: Target, id: &str, message: String) {
+        self.references.push(Reference {
+            path: path.to_path_buf(),
+            target,
+            id: id.to_string(),
+            message,
+        });
+    }
+
+    fn contains(&self, target: Target, id: &str) -> bool {
+        match target {
+            Target::Asset => self.assets.contains(id),
+            Target::Person => self.people.contains(id),
+            Target::Tool => self.tools.contains(id),
+        }
+    }
+}
+
+/// Walks through the given path and reports every problem found.
+///
+/// Bulletin entries found in more than one place are only reported when `duplicates` is [`Severity::Error`], and
+/// bulletins published outside the week they cover only when `misdated` is. Otherwise they are logged as warnings.
+pub fn check(source_dir: &Path, duplicates: Severity, misdated: Severity) -> Result<Report> {
+    let mut report = Report::default();
+    let mut index = Index::default();
+    let walker = WalkDir::new(source_dir).sort_by_file_name().into_iter();
+
+    for result in walker.filter_entry(|e| !is_hidden(e)) {
+        let entry = result?;
+        let path = entry.path();
+
+        if path.is_file() {
+            if let Err(err) = check_source(path, &mut index) {
+                report.push(Problem::from_error(path, err));
+            }
+        }
+    }
+
+    let found = index
+        .duplicates
+        .drain(..)
+        .map(|problem| (problem, duplicates))
+        .chain(index.misdated.drain(..).map(|problem| (problem, misdated)));
+
+    for (problem, severity) in found {
+        match severity {
+            Severity::Error => {
+                let path = problem.path().to_path_buf();
+                report.push(Problem::from_error(&path, problem.into()));
+            }
+            Severity::Warning => warn!("{}", problem),
+        }
+    }
+
+    for reference in &index.references {
+        if !index.contains(reference.target, &reference.id) {
+            report.push(Problem::new(&reference.path, reference.message.clone()));
+        }
+    }
+
+    if !index.settings.contains("main") {
+        report.push(Problem::new(source_dir, "missing 'main' settings"));
+    }
+
+    if !index.entrance {
+        report.push(Problem::new(source_dir, "missing entrance"));
+    }
+
+    Ok(report)
+}
+
+fn check_source(path: &Path, index: &mut Index) -> Result<()> {
+    let resource_extensions = ["md", "toml"];
+    let extension = path
+        .extension()
+        .map(|osstr| osstr.to_string_lossy())
+        .unwrap_or_default();
+
+    if !extension.is_empty() && !resource_extensions.iter().any(|rex| rex == &extension) {
+        let id = path
+            .file_name()
+            .expect("file name to exist for an asset")
+            .to_string_lossy();
+        index.assets.insert(id.to_string());
+
+        return Ok(());
+    }
+
+    let contents = fs::read_to_string(path).map_err(|err| SourceError::io(path, err))?;
+
+    // Anything without a hint is skipped by the source stage as well.
+    let resource_type = match ResourceType::from_hint(&contents) {
+        Ok(resource_type) => resource_type,
+        Err(_) => return Ok(()),
+    };
+
+    match resource_type {
+        ResourceType::BulletinStash => {
+            let resource = parse::<BulletinStash>(path, &resource_type, &contents)?;
+            let duplicates = index.entries.record(path, resource.entries());
+            index.duplicates.extend(duplicates);
+        }
+        ResourceType::Bulletin => {
+            let resource = parse::<Bulletin>(path, &resource_type, &contents)?;
+            let duplicates = index.entries.record(path, resource.entries());
+            index.duplicates.extend(duplicates);
+            index.misdated.extend(resource.misdated(path));
+        }
+        ResourceType::Entrance => {
+            parse::<Entrance>(path, &resource_type, &contents)?;
+            index.entrance = true;
+        }
+        ResourceType::Note => {
+            let resource = parse::<Note>(path, &resource_type, &contents)?;
+            let message = format!("note author '{}' has no matching person", resource.author());
+            index.refer(path, Target::Person, resource.author(), message);
+        }
+        ResourceType::Person => {
+            let resource = parse::<Person>(path, &resource_type, &contents)?;
+            index.people.insert(resource.id().clone());
+        }
+        ResourceType::Project => {
+            parse::<Project>(path, &resource_type, &contents)?;
+        }
+        ResourceType::Section => {
+            parse::<Section>(path, &resource_type, &contents)?;
+        }
+        ResourceType::Settings => {
+            let resource = parse::<Settings>(path, &resource_type, &contents)?;
+            index.settings.insert(resource.id().clone());
+        }
+        ResourceType::Sketch => {
+            let resource = parse::<Sketch>(path, &resource_type, &contents)?;
+            let message = format!("unknown author '{}'", resource.author());
+            index.refer(path, Target::Person, resource.author(), message);
+
+            let message = format!("missing asset file '{}'", resource.asset());
+            index.refer(path, Target::Asset, resource.asset(), message);
+
+            for tool in resource.tools() {
+                let message = format!("unknown tool '{}'", tool);
+                index.refer(path, Target::Tool, tool, message);
+            }
+        }
+        ResourceType::Tool => {
+            let resource = parse::<Tool>(path, &resource_type, &contents)?;
+            index.tools.insert(resource.id().clone());
+        }
+        ResourceType::Unknown(ref s) => {
+            bail!("unknown type hint '{}'", s);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_testdir::TempDir;
+
+    #[test]
+    fn report_every_problem() -> Result<()> {
+        let dir = TempDir::default();
+        let sketch = r#"type = "sketch"
+id = "calm-dragon"
+title = "Calm dragon"
+asset = "calm-dragon.jpg"
+author = "nobody"
+publication_date = "2021-07-07"
+tools = ["pencil"]
+"#;
+        let stash = r#"type = "bulletin_stash"
+
+[[entries]]
+url = "https://napi.rs/"
+title = "napi-rs"
+summary = "A library for building pre-compiled NodeJS addons in Rust."
+content_type = "text"
+"#;
+        let issue = r#"type = "bulletin"
+id = "2021-W52"
+publication_date = 2022-01-03
+summary = "A summary."
+entries = []
+"#;
+        let note = r#"---
+type: note
+id: a-note
+publication_date: 2021-07-07
+author: arnau
+---
+# A note without summary
+"#;
+        fs::write(dir.join("sketch.toml"), sketch)?;
+        fs::write(dir.join("note.md"), note)?;
+        fs::write(dir.join("thing.toml"), "type = \"thing\"\n")?;
+        fs::write(dir.join("README.md"), "# Not a resource\n")?;
+        fs::write(dir.join("a-stash.toml"), stash)?;
+        fs::write(dir.join("b-stash.toml"), stash)?;
+        fs::write(dir.join("2021-W52.toml"), issue)?;
+
+        let report = check(&dir, Severity::Error, Severity::Error)?;
+        let messages: Vec<String> = report
+            .problems()
+            .iter()
+            .map(|problem| problem.message.clone())
+            .collect();
+
+        assert!(!report.is_ok());
+        assert!(messages.contains(&"notes must have a summary".to_string()));
+        assert!(messages.contains(&"unknown type hint 'thing'".to_string()));
+        assert!(messages.contains(&"unknown author 'nobody'".to_string()));
+        assert!(messages.contains(&"missing asset file 'calm-dragon.jpg'".to_string()));
+        assert!(messages.contains(&"unknown tool 'pencil'".to_string()));
+        assert!(messages.contains(&"missing 'main' settings".to_string()));
+        assert!(messages.contains(&"missing entrance".to_string()));
+        assert!(messages.contains(&format!(
+            "bulletin entry 'https://napi.rs/' is already in {}",
+            dir.join("a-stash.toml").display()
+        )));
+        assert!(messages.contains(
+            &"the publication date 2022-01-03 is not in 2021-W52 which runs from 2021-12-27 to 2022-01-02"
+                .to_string()
+        ));
+        assert_eq!(report.problems().len(), 9);
+
+        let report = check(&dir, Severity::Error, Severity::Warning)?;
+
+        assert_eq!(report.problems().len(), 8);
+
+        Ok(())
+    }
+}
@@ -126,6 +126,7 @@ impl WriteCache for Entrance {
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
         let record = EntranceRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "entrance").insert(tx)?;
 
         Ok(())
     }
@@ -133,4 +134,17 @@ impl WriteCache for Entrance {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         EntranceRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match EntranceRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "entrance").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
@@ -0,0 +1,261 @@
+//! This module defines the errors for the Source stage.
+
+use anyhow::bail;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::resource_type::ResourceType;
+use crate::stamp::{Date, IsoWeek};
+
+/// An error reading a source file.
+#[derive(Debug, Error)]
+pub enum SourceError {
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{}: failed to parse {resource_type}: {message}", display_path(.path, .location))]
+    Parse {
+        path: PathBuf,
+        resource_type: ResourceType,
+        location: Option<Location>,
+        message: String,
+    },
+    #[error("{}: bulletin entry '{url}' is already in {}", .path.display(), .other.display())]
+    Duplicate {
+        path: PathBuf,
+        /// The file where the entry was first seen.
+        other: PathBuf,
+        url: String,
+    },
+    #[error(
+        "{}: the publication date {publication_date} is not in {week} which runs from {} to {}",
+        .path.display(),
+        .week.monday(),
+        .week.sunday()
+    )]
+    Misdated {
+        path: PathBuf,
+        week: IsoWeek,
+        publication_date: Date,
+    },
+}
+
+impl SourceError {
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        SourceError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// Wraps an error from decoding the contents of a source file hinted as the given resource type.
+    ///
+    /// The location is recovered from TOML and YAML errors. Any other error has no location.
+    pub fn parse(
+        path: &Path,
+        resource_type: ResourceType,
+        contents: &str,
+        err: anyhow::Error,
+    ) -> Self {
+        let (location, message) = if let Some(err) = err.downcast_ref::<toml::de::Error>() {
+            let location = err
+                .span()
+                .map(|span| Location::from_offset(contents, span.start));
+
+            (location, err.message().to_string())
+        } else if let Some(err) = err.downcast_ref::<serde_yaml::Error>() {
+            // The frontmatter starts right after the first `---`.
+            let offset = contents.find("---").map(|idx| idx + 3).unwrap_or(0);
+            let location = err
+                .location()
+                .map(|location| Location::from_offset(contents, offset + location.index()));
+
+            (location, err.to_string())
+        } else {
+            (None, format!("{:#}", err))
+        };
+
+        SourceError::Parse {
+            path: path.to_path_buf(),
+            resource_type,
+            location,
+            message,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            SourceError::Io { path, .. } => path,
+            SourceError::Parse { path, .. } => path,
+            SourceError::Duplicate { path, .. } => path,
+            SourceError::Misdated { path, .. } => path,
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            SourceError::Io { .. } => None,
+            SourceError::Parse { location, .. } => location.as_ref(),
+            SourceError::Duplicate { .. } => None,
+            SourceError::Misdated { .. } => None,
+        }
+    }
+}
+
+/// How to treat a problem that does not prevent building.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Severity::Error),
+            "warning" => Ok(Severity::Warning),
+            s => bail!("unknown severity '{}'. Expected one of: error, warning", s),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+fn display_path(path: &Path, location: &Option<Location>) -> String {
+    match location {
+        Some(location) => format!("{}:{}", path.display(), location),
+        None => path.display().to_string(),
+    }
+}
+
+/// A position within a source file. Both line and column start at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    /// The full line the position points to.
+    pub snippet: String,
+}
+
+impl Location {
+    /// Finds the line and column for the given byte offset.
+    pub fn from_offset(contents: &str, offset: usize) -> Self {
+        let offset = offset.min(contents.len());
+        let before = &contents[..offset];
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        let line = before.matches('\n').count() + 1;
+        let column = before[line_start..].chars().count() + 1;
+        let snippet = contents[line_start..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        Self {
+            line,
+            column,
+            snippet,
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use std::str::FromStr;
+
+    use crate::source::{Note, Sketch};
+
+    #[test]
+    fn toml_location() -> Result<()> {
+        let raw = r#"type = "sketch"
+id = "calm-dragon"
+title = "Calm dragon"
+asset = "calm-dragon.jpg"
+author = "arnau"
+publication_date = "29/09/2017"
+tools = []
+"#;
+        let err = Sketch::from_str(raw).unwrap_err();
+        let err = SourceError::parse(
+            Path::new("calm-dragon.toml"),
+            ResourceType::Sketch,
+            raw,
+            err,
+        );
+        let location = err.location().expect("location to be found");
+
+        assert_eq!(location.line, 6);
+        assert_eq!(location.column, 20);
+        assert_eq!(&location.snippet, "publication_date = \"29/09/2017\"");
+        assert!(err
+            .to_string()
+            .starts_with("calm-dragon.toml:6:20: failed to parse sketch"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn yaml_location() -> Result<()> {
+        let raw = r#"---
+type: note
+id: a-note
+publication_date: [2021-07-07
+author: arnau
+---
+# A note
+
+A summary.
+
+<!-- body -->
+"#;
+        let err = Note::from_str(raw).unwrap_err();
+        let err = SourceError::parse(Path::new("a-note.md"), ResourceType::Note, raw, err);
+        let location = err.location().expect("location to be found");
+
+        assert_eq!(location.line, 4);
+        assert_eq!(location.column, 19);
+        assert_eq!(&location.snippet, "publication_date: [2021-07-07");
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_frontmatter() -> Result<()> {
+        let raw = "# A note\n";
+        let err = Note::from_str(raw).unwrap_err();
+        let err = SourceError::parse(Path::new("a-note.md"), ResourceType::Note, raw, err);
+
+        assert!(err.location().is_none());
+        assert_eq!(
+            &err.to_string(),
+            "a-note.md: failed to parse note: Could not find a frontmatter in the given markdown text."
+        );
+
+        Ok(())
+    }
+}
@@ -4,11 +4,12 @@
 //! other stages to consume.
 
 use anyhow::Result;
-use log::{info, warn};
+use log::{debug, info, warn};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 use walkdir::{DirEntry, WalkDir};
 
@@ -16,7 +17,9 @@ pub mod asset;
 pub mod bulletin_entry;
 pub mod bulletin_issue;
 pub mod bulletin_stash;
+pub mod check;
 pub mod entrance;
+pub mod error;
 pub mod note;
 pub mod person;
 pub mod project;
@@ -26,10 +29,12 @@ pub mod sketch;
 pub mod tool;
 
 pub use asset::{Asset, AssetSet};
-pub use bulletin_entry::BulletinEntry;
+pub use bulletin_entry::{BulletinEntry, ContentType, EntryIndex};
 pub use bulletin_issue::{Bulletin, BulletinSet};
 pub use bulletin_stash::BulletinStash;
+pub use check::{check, Problem, Report};
 pub use entrance::Entrance;
+pub use error::{Location, Severity, SourceError};
 pub use note::{Note, NoteSet};
 pub use person::{Person, PersonSet};
 pub use project::{Project, ProjectSet};
@@ -38,32 +43,122 @@ pub use settings::{Settings, SettingsSet};
 pub use sketch::{Sketch, SketchSet};
 pub use tool::{Tool, ToolSet};
 
-use crate::cache::{Transaction, WriteCache};
+use crate::cache::{check_integrity, Transaction, WriteCache};
 use crate::resource_type::ResourceType;
-use crate::Cache;
+use crate::{Cache, Mode, Resource};
+
+/// The type and id of the resource read from each source file, assets aside.
+pub type Manifest = BTreeMap<PathBuf, (ResourceType, String)>;
 
 /// Walks through the given path and caches any know resource.
-pub fn read(source_dir: &Path, cache: &mut Cache) -> Result<()> {
+///
+/// In [`Mode::Incremental`] resources already cached with the same checksum are left untouched.
+///
+/// A bulletin entry found in more than one place is an error or a warning depending on the `duplicates` severity.
+/// Either way, only its first occurrence in walking order is cached, in both modes. Entries are told apart by their
+/// normalised URL so two spellings of the same link count as a duplicate.
+///
+/// A bulletin published outside the week it covers is an error or a warning depending on the `misdated` severity.
+///
+/// Returns the resource read from each file.
+pub fn read(
+    source_dir: &Path,
+    cache: &mut Cache,
+    mode: Mode,
+    duplicates: Severity,
+    misdated: Severity,
+) -> Result<Manifest> {
     let tx = cache.transaction()?;
-    let walker = WalkDir::new(source_dir).into_iter();
+    let walker = WalkDir::new(source_dir).sort_by_file_name().into_iter();
+    let mut index = EntryIndex::default();
+    let mut manifest = Manifest::new();
 
     for result in walker.filter_entry(|e| !is_hidden(e)) {
         let entry = result?;
         let path = entry.path();
 
         if path.is_file() {
-            process_source(path, &tx)?;
+            report(
+                process_source(path, &tx, mode, &mut index, &mut manifest)?,
+                duplicates,
+                misdated,
+            )?;
+        }
+    }
+
+    check_integrity(&tx)?;
+    tx.commit()?;
+
+    Ok(manifest)
+}
+
+/// Caches the resources found in the given files, leaving any other cached resource as it is.
+///
+/// Meant for rebuilding after a few files change. Files that no longer exist, or that [`read`] would not reach from
+/// the source path, are skipped so removals need a full [`read`] followed by a prune. Duplicate bulletin entries are
+/// only detected amongst the given files, so an entry already cached from another file is taken over silently. Use a
+/// full [`read`] when bulletins or stashes change.
+///
+/// Returns the resource read from each file. One that no longer matches what an earlier read found for the same file
+/// leaves the old resource behind until a full [`read`] and a prune.
+pub fn read_files(
+    source_dir: &Path,
+    paths: &[PathBuf],
+    cache: &mut Cache,
+    mode: Mode,
+    duplicates: Severity,
+    misdated: Severity,
+) -> Result<Manifest> {
+    let tx = cache.transaction()?;
+    let mut index = EntryIndex::default();
+    let mut manifest = Manifest::new();
+
+    for path in paths {
+        if path.is_file() && is_reachable(source_dir, path) {
+            report(
+                process_source(path, &tx, mode, &mut index, &mut manifest)?,
+                duplicates,
+                misdated,
+            )?;
         }
     }
 
+    check_integrity(&tx)?;
     tx.commit()?;
 
+    Ok(manifest)
+}
+
+/// Fails on the first problem or warns about each of them depending on the severity for its kind.
+fn report(found: Vec<SourceError>, duplicates: Severity, misdated: Severity) -> Result<()> {
+    for problem in found {
+        let severity = match problem {
+            SourceError::Misdated { .. } => misdated,
+            _ => duplicates,
+        };
+
+        match severity {
+            Severity::Error => return Err(problem.into()),
+            Severity::Warning => warn!("{}", problem),
+        }
+    }
+
     Ok(())
 }
 
-fn process_source(entry: &Path, tx: &Transaction) -> Result<()> {
+/// Caches the resource found in the given file without the bulletin entries already seen, returning the problems
+/// that do not prevent caching it: the bulletin entries already seen and a bulletin published outside its week.
+///
+/// The resource is recorded in the manifest under the file path.
+fn process_source(
+    entry: &Path,
+    tx: &Transaction,
+    mode: Mode,
+    index: &mut EntryIndex,
+    manifest: &mut Manifest,
+) -> Result<Vec<SourceError>> {
     let path = entry.display();
-    let mut file = File::open(&entry)?;
+    let mut file = File::open(entry).map_err(|err| SourceError::io(entry, err))?;
     let resource_extensions = ["md", "toml"];
 
     // Binary assets
@@ -76,82 +171,119 @@ fn process_source(entry: &Path, tx: &Transaction) -> Result<()> {
 
         if !resource_extensions.iter().any(|rex| rex == &extension) {
             let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer)?;
+            file.read_to_end(&mut buffer)
+                .map_err(|err| SourceError::io(entry, err))?;
 
             let resource = Asset::new(id.to_string(), extension.to_string(), buffer);
-            AssetSet::add(tx, resource)?;
+            store::<AssetSet>(tx, resource, mode)?;
             info!("source(asset): {}", &path);
 
-            return Ok(());
+            return Ok(Vec::new());
         }
     }
 
     // Textual resources
     let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
+    file.read_to_string(&mut contents)
+        .map_err(|err| SourceError::io(entry, err))?;
+
+    let mut problems = Vec::new();
 
     // Skipping anything without a hint.
     if let Ok(resource_type) = ResourceType::from_hint(&contents) {
-        match resource_type {
+        let id = match resource_type {
             ResourceType::BulletinStash => {
                 info!("source(bulletin_stash): {}", &path);
-                let resource = BulletinStash::from_str(&contents)?;
-                BulletinStash::add(tx, resource)?;
+                let mut resource = parse::<BulletinStash>(entry, &resource_type, &contents)?;
+                problems = index.dedupe(entry, resource.entries_mut());
+                store::<BulletinStash>(tx, resource, mode)?
             }
             ResourceType::Bulletin => {
                 info!("source(bulletin): {}", &path);
-                let resource = Bulletin::from_str(&contents)?;
-                BulletinSet::add(tx, resource)?;
+                let mut resource = parse::<Bulletin>(entry, &resource_type, &contents)?;
+                problems = index.dedupe(entry, resource.entries_mut());
+                problems.extend(resource.misdated(entry));
+                store::<BulletinSet>(tx, resource, mode)?
             }
             ResourceType::Entrance => {
                 info!("source(entrance): {}", &path);
-                let resource = Entrance::from_str(&contents)?;
-                Entrance::add(tx, resource)?;
+                let resource = parse::<Entrance>(entry, &resource_type, &contents)?;
+                store::<Entrance>(tx, resource, mode)?
             }
             ResourceType::Note => {
                 info!("source(note): {}", &path);
-                let resource = Note::from_str(&contents)?;
-                NoteSet::add(tx, resource)?;
+                let resource = parse::<Note>(entry, &resource_type, &contents)?;
+                store::<NoteSet>(tx, resource, mode)?
             }
             ResourceType::Person => {
                 info!("source(person): {}", &path);
-                let resource = Person::from_str(&contents)?;
-                PersonSet::add(tx, resource)?;
+                let resource = parse::<Person>(entry, &resource_type, &contents)?;
+                store::<PersonSet>(tx, resource, mode)?
             }
             ResourceType::Project => {
                 info!("source(project): {}", &path);
-                let resource = Project::from_str(&contents)?;
-                ProjectSet::add(tx, resource)?;
+                let resource = parse::<Project>(entry, &resource_type, &contents)?;
+                store::<ProjectSet>(tx, resource, mode)?
             }
             ResourceType::Section => {
                 info!("source(section): {}", &path);
-                let resource = Section::from_str(&contents)?;
-                SectionSet::add(tx, resource)?;
+                let resource = parse::<Section>(entry, &resource_type, &contents)?;
+                store::<SectionSet>(tx, resource, mode)?
             }
             ResourceType::Settings => {
                 info!("source(settings): {}", &path);
-                let resource = Settings::from_str(&contents)?;
-                SettingsSet::add(tx, resource)?;
+                let resource = parse::<Settings>(entry, &resource_type, &contents)?;
+                store::<SettingsSet>(tx, resource, mode)?
             }
             ResourceType::Sketch => {
                 info!("source(sketch): {}", &path);
-                let resource = Sketch::from_str(&contents)?;
-                SketchSet::add(tx, resource)?;
+                let resource = parse::<Sketch>(entry, &resource_type, &contents)?;
+                store::<SketchSet>(tx, resource, mode)?
             }
             ResourceType::Tool => {
                 info!("source(tool): {}", &path);
-                let resource = Tool::from_str(&contents)?;
-                ToolSet::add(tx, resource)?;
+                let resource = parse::<Tool>(entry, &resource_type, &contents)?;
+                store::<ToolSet>(tx, resource, mode)?
             }
-            ResourceType::Unknown(s) => {
+            ResourceType::Unknown(ref s) => {
                 warn!("unknown type '{}' {}", &s, &path);
+
+                return Ok(problems);
             } // _ => {
               //     warn!("unimplemented {}", &path);
               // }
-        }
+        };
+
+        manifest.insert(entry.to_path_buf(), (resource_type, id));
     }
 
-    Ok(())
+    Ok(problems)
+}
+
+/// Decodes the contents as the resource hinted, keeping track of the file it came from.
+fn parse<R>(path: &Path, resource_type: &ResourceType, contents: &str) -> Result<R>
+where
+    R: FromStr<Err = anyhow::Error>,
+{
+    R::from_str(contents)
+        .map_err(|err| SourceError::parse(path, resource_type.clone(), contents, err).into())
+}
+
+/// Adds the resource to the cache unless the mode allows skipping an unchanged one. Returns the resource id.
+fn store<S>(tx: &Transaction, resource: S::Item, mode: Mode) -> Result<String>
+where
+    S: WriteCache,
+    S::Item: Resource<Id = String>,
+{
+    let id = resource.id().clone();
+
+    if mode == Mode::Incremental && S::touch(tx, &resource)? {
+        debug!("source: unchanged {}", &id);
+    } else {
+        S::add(tx, resource)?;
+    }
+
+    Ok(id)
 }
 
 fn is_hidden(entry: &DirEntry) -> bool {
@@ -162,6 +294,17 @@ fn is_hidden(entry: &DirEntry) -> bool {
         .unwrap_or(false)
 }
 
+/// Whether the walk in [`read`] would reach the path, i.e. it sits in the source path and nothing in between is hidden.
+pub fn is_reachable(source_dir: &Path, path: &Path) -> bool {
+    match path.strip_prefix(source_dir) {
+        Ok(relative) => !relative.components().any(|component| match component {
+            Component::Normal(name) => name.to_str().map(|s| s.starts_with('.')).unwrap_or(false),
+            _ => false,
+        }),
+        Err(_) => false,
+    }
+}
+
 pub(crate) fn de_trim<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -169,3 +312,169 @@ where
     let value = String::deserialize(deserializer)?;
     Ok(value.trim().to_string())
 }
+
+/// Drops repeated values, keeping the first occurrence, so they round-trip through cache tables where they are unique.
+pub(crate) fn de_unique<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let values = Vec::<String>::deserialize(deserializer)?;
+    let mut unique = Vec::with_capacity(values.len());
+
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+
+    Ok(unique)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Strategy;
+    use std::fs;
+    use temp_testdir::TempDir;
+
+    fn issue(id: &str, date: &str, summary: &str, urls: &[&str]) -> String {
+        let mut raw = format!(
+            "type = \"bulletin\"\nid = \"{}\"\npublication_date = {}\nsummary = \"{}\"\n",
+            id, date, summary
+        );
+
+        for url in urls {
+            raw.push_str(&format!(
+                "\n[[entries]]\nurl = \"{}\"\ntitle = \"A title\"\nsummary = \"A summary.\"\ncontent_type = \"text\"\n",
+                url
+            ));
+        }
+
+        raw
+    }
+
+    fn entries(cache: &mut Cache) -> Result<Vec<(String, Option<String>)>> {
+        let tx = cache.transaction()?;
+        let mut stmt = tx.prepare("SELECT url, issue_id FROM bulletin_entry ORDER BY url")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    #[test]
+    fn incremental_matches_full() -> Result<()> {
+        let dir = TempDir::default();
+        let source_dir = dir.join("source");
+        let cache_path = dir.join("cache.db");
+        let shared = "https://napi.rs/";
+        fs::create_dir(&source_dir)?;
+        fs::write(
+            source_dir.join("2020-W01.toml"),
+            issue("2020-W01", "2020-01-05", "First.", &[shared]),
+        )?;
+        fs::write(
+            source_dir.join("2021-W02.toml"),
+            issue(
+                "2021-W02",
+                "2021-01-17",
+                "Second.",
+                &[shared, "https://github.com/erezsh/Preql"],
+            ),
+        )?;
+
+        fs::write(
+            source_dir.join("stash.toml"),
+            "type = \"bulletin_stash\"\n\n[[entries]]\nurl = \"http://napi.rs\"\ntitle = \"napi-rs\"\nsummary = \"A summary.\"\ncontent_type = \"text\"\n",
+        )?;
+
+        let mut cache = Cache::connect_with_strategy(Strategy::Disk(cache_path.clone()))?;
+        read(
+            &source_dir,
+            &mut cache,
+            Mode::Full,
+            Severity::Warning,
+            Severity::Warning,
+        )?;
+        cache.prune()?;
+        cache.disconnect()?;
+
+        fs::write(
+            source_dir.join("2020-W01.toml"),
+            issue("2020-W01", "2020-01-05", "First, edited.", &[shared]),
+        )?;
+
+        let mut cache = Cache::connect_with_strategy(Strategy::Disk(cache_path))?;
+        read(
+            &source_dir,
+            &mut cache,
+            Mode::Incremental,
+            Severity::Warning,
+            Severity::Warning,
+        )?;
+        cache.prune()?;
+
+        let mut full = Cache::connect_with_strategy(Strategy::Memory)?;
+        read(
+            &source_dir,
+            &mut full,
+            Mode::Full,
+            Severity::Warning,
+            Severity::Warning,
+        )?;
+        full.prune()?;
+
+        assert_eq!(entries(&mut cache)?, entries(&mut full)?);
+        assert_eq!(
+            entries(&mut full)?,
+            vec![
+                (
+                    "https://github.com/erezsh/Preql".to_string(),
+                    Some("2021-W02".to_string())
+                ),
+                (shared.to_string(), Some("2020-W01".to_string())),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn misdated_issue() -> Result<()> {
+        let dir = TempDir::default();
+        fs::write(
+            dir.join("2021-W52.toml"),
+            issue("2021-W52", "2022-01-03", "Late.", &["https://napi.rs/"]),
+        )?;
+
+        let mut cache = Cache::connect(":memory:")?;
+        let err = read(
+            &dir,
+            &mut cache,
+            Mode::Full,
+            Severity::Warning,
+            Severity::Error,
+        )
+        .expect_err("the issue to be misdated");
+
+        assert!(err.to_string().ends_with(
+            "the publication date 2022-01-03 is not in 2021-W52 which runs from 2021-12-27 to 2022-01-02"
+        ));
+
+        let manifest = read(
+            &dir,
+            &mut cache,
+            Mode::Full,
+            Severity::Warning,
+            Severity::Warning,
+        )?;
+
+        assert_eq!(
+            manifest.get(&dir.join("2021-W52.toml")),
+            Some(&(ResourceType::Bulletin, "2021-W52".to_string()))
+        );
+
+        Ok(())
+    }
+}
@@ -1,8 +1,7 @@
 //! This module defines the note and note set for the Source stage.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
 use std::fmt;
 use std::iter::FromIterator;
 use std::str::FromStr;
@@ -22,9 +21,25 @@ pub struct Note {
     summary: String,
     publication_date: Date,
     author: String,
+    tags: Vec<String>,
+    draft: bool,
     body: String,
 }
 
+impl Note {
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn is_draft(&self) -> bool {
+        self.draft
+    }
+}
+
 impl Resource for Note {
     type Id = String;
 
@@ -40,6 +55,8 @@ impl Digest for Note {
         self.summary.digest(hasher);
         self.publication_date.digest(hasher);
         self.author.digest(hasher);
+        self.tags.digest(hasher);
+        self.draft.digest(hasher);
         self.body.digest(hasher);
     }
 }
@@ -59,9 +76,11 @@ impl FromStr for Note {
         Ok(Self {
             id: metadata.id,
             title,
-            summary: summary.expect("notes must have a summary"),
+            summary: summary.ok_or_else(|| anyhow!("notes must have a summary"))?,
             publication_date: Date::from_str(&metadata.publication_date)?,
             author: metadata.author,
+            tags: metadata.tags,
+            draft: metadata.draft,
             body,
         })
     }
@@ -89,6 +108,15 @@ struct Metadata {
     id: String,
     publication_date: String,
     author: String,
+    #[serde(
+        default,
+        deserialize_with = "super::de_unique",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    tags: Vec<String>,
+    /// Drafts are kept out of the output unless drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    draft: bool,
 }
 
 impl From<&Note> for Metadata {
@@ -98,6 +126,8 @@ impl From<&Note> for Metadata {
             id: resource.id.clone(),
             publication_date: resource.publication_date.to_string(),
             author: resource.author.clone(),
+            tags: resource.tags.clone(),
+            draft: resource.draft,
         }
     }
 }
@@ -112,25 +142,28 @@ impl From<Note> for NoteRecord {
             publication_date: resource.publication_date.to_string(),
             author_id: resource.author,
             body: resource.body,
+            draft: resource.draft,
         }
     }
 }
 
-impl TryFrom<NoteRecord> for Note {
-    type Error = anyhow::Error;
-
-    fn try_from(record: NoteRecord) -> Result<Note> {
-        let resource = Note {
-            id: record.id,
-            title: record.title,
-            summary: record.summary,
-            publication_date: Date::from_str(&record.publication_date)?,
-            author: record.author_id,
-            body: record.body,
-        };
-
-        Ok(resource)
-    }
+fn from_record(tx: &Transaction, record: NoteRecord) -> Result<Note> {
+    let tags: Vec<String> = TagRecordSet::select(tx, (TagTable::Note, record.id.clone()))?
+        .into_iter()
+        .map(|record| record.tag)
+        .collect();
+    let resource = Note {
+        id: record.id,
+        title: record.title,
+        summary: record.summary,
+        publication_date: Date::from_str(&record.publication_date)?,
+        author: record.author_id,
+        tags,
+        draft: record.draft,
+        body: record.body,
+    };
+
+    Ok(resource)
 }
 
 #[derive(Clone, Debug)]
@@ -149,7 +182,7 @@ impl ReadCache for NoteSet {
 
     fn find(tx: &Transaction, id: &str) -> Result<Option<Self::Item>> {
         if let Some(record) = NoteRecord::select(tx, id)? {
-            let resource = Note::try_from(record)?;
+            let resource = from_record(tx, record)?;
 
             Ok(Some(resource))
         } else {
@@ -161,7 +194,7 @@ impl ReadCache for NoteSet {
         let records = NoteRecordSet::select(tx)?;
         let resources = records
             .into_iter()
-            .map(Note::try_from)
+            .map(|record| from_record(tx, record))
             .collect::<Result<Vec<Note>>>()?;
 
         Ok(Self::new(resources))
@@ -172,8 +205,22 @@ impl WriteCache for NoteSet {
     type Item = Note;
 
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
+        // Tags removed from the note would otherwise linger.
+        TagRecordSet::delete(tx, TagTable::Note, &resource.id)?;
+
+        for tag in &resource.tags {
+            let record = TagRecord {
+                table: TagTable::Note,
+                resource_id: resource.id.clone(),
+                tag: tag.clone(),
+            };
+
+            record.insert(tx)?;
+        }
+
         let record = NoteRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "note").insert(tx)?;
 
         Ok(())
     }
@@ -181,6 +228,19 @@ impl WriteCache for NoteSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         NoteRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match NoteRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "note").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for NoteSet {}
@@ -210,6 +270,18 @@ impl FromIterator<Note> for NoteSet {
 mod tests {
     use super::*;
     use crate::cache::Cache;
+    use crate::source::{Person, PersonSet};
+
+    fn person() -> Result<Person> {
+        let raw = r#"type = "person"
+id = "arnau"
+name = "Arnau Siches"
+guest = false
+accounts = []
+"#;
+
+        Person::from_str(raw)
+    }
 
     #[test]
     fn full_cycle() -> Result<()> {
@@ -218,6 +290,9 @@ type: note
 id: a-note
 publication_date: 2021-07-07
 author: arnau
+tags:
+- csv
+- rdf
 ---
 # A simple note
 
@@ -231,6 +306,7 @@ From here onwards it's the body of the note so the first H1 will become the _tit
         let tx = cache.transaction()?;
         let resource = Note::from_str(raw)?;
 
+        PersonSet::add(&tx, person()?)?;
         NoteSet::add(&tx, resource.clone())?;
 
         let cached = NoteSet::find(&tx, &resource.id)?.expect("note to be cached");
@@ -242,4 +318,75 @@ From here onwards it's the body of the note so the first H1 will become the _tit
 
         Ok(())
     }
+
+    #[test]
+    fn touch_unchanged() -> Result<()> {
+        let raw = r#"---
+type: note
+id: a-note
+publication_date: 2021-07-07
+author: arnau
+---
+# A simple note
+
+A summary.
+
+<!-- body -->
+
+A body."#;
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        let resource = Note::from_str(raw)?;
+        let mut changed = resource.clone();
+        changed.body = "Another body.".to_string();
+
+        assert!(!NoteSet::touch(&tx, &resource)?);
+
+        PersonSet::add(&tx, person()?)?;
+        NoteSet::add(&tx, resource.clone())?;
+
+        assert!(NoteSet::touch(&tx, &resource)?);
+        assert!(!NoteSet::touch(&tx, &changed)?);
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_tags() -> Result<()> {
+        let raw = r#"---
+type: note
+id: a-note
+publication_date: 2021-07-07
+author: arnau
+tags:
+- rdf
+- csv
+- rdf
+---
+# A simple note
+
+A summary.
+
+<!-- body -->
+
+A body."#;
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+        let resource = Note::from_str(raw)?;
+
+        assert_eq!(resource.tags(), ["rdf", "csv"]);
+
+        PersonSet::add(&tx, person()?)?;
+        NoteSet::add(&tx, resource.clone())?;
+
+        let cached = NoteSet::find(&tx, &resource.id)?.expect("note to be cached");
+
+        assert_eq!(cached.checksum(), resource.checksum());
+
+        tx.commit()?;
+
+        Ok(())
+    }
 }
@@ -34,6 +34,7 @@ impl Digest for Person {
     fn digest(&self, hasher: &mut Hasher) {
         self.id.digest(hasher);
         self.name.digest(hasher);
+        self.guest.digest(hasher);
         self.accounts.digest(hasher);
     }
 }
@@ -125,10 +126,12 @@ impl WriteCache for PersonSet {
         for account in &resource.accounts {
             let record = ServiceAccountRecord::from((resource.id.as_str(), account));
             record.insert(tx)?;
+            SessionTrailRecord::new(&record.checksum, "service_account").insert(tx)?;
         }
 
         let record = PersonRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "person").insert(tx)?;
 
         Ok(())
     }
@@ -136,6 +139,24 @@ impl WriteCache for PersonSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         PersonRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match PersonRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "person").insert(tx)?;
+
+                for account in &resource.accounts {
+                    let checksum = account.checksum().to_string();
+                    SessionTrailRecord::new(&checksum, "service_account").insert(tx)?;
+                }
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for PersonSet {}
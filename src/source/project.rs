@@ -1,8 +1,7 @@
 //! This module defines the project for the Source stage.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
 use std::fmt;
 use std::iter::FromIterator;
 use std::str::FromStr;
@@ -25,6 +24,14 @@ pub struct Project {
     start_date: Date,
     end_date: Option<Date>,
     source_url: Option<String>,
+    tags: Vec<String>,
+    draft: bool,
+}
+
+impl Project {
+    pub fn is_draft(&self) -> bool {
+        self.draft
+    }
 }
 
 impl Resource for Project {
@@ -45,6 +52,8 @@ impl Digest for Project {
         self.start_date.digest(hasher);
         self.end_date.digest(hasher);
         self.source_url.digest(hasher);
+        self.tags.digest(hasher);
+        self.draft.digest(hasher);
     }
 }
 
@@ -68,12 +77,14 @@ impl FromStr for Project {
         Ok(Self {
             id: metadata.id,
             name: title,
-            summary: summary.expect("projects must have a summary"),
+            summary: summary.ok_or_else(|| anyhow!("projects must have a summary"))?,
             body,
             status: metadata.status,
             start_date: Date::from_str(&metadata.start_date)?,
             end_date,
             source_url: metadata.source_url,
+            tags: metadata.tags,
+            draft: metadata.draft,
         })
     }
 }
@@ -104,6 +115,15 @@ struct Metadata {
     end_date: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     source_url: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "super::de_unique",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    tags: Vec<String>,
+    /// Drafts are kept out of the output unless drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    draft: bool,
 }
 
 impl From<&Project> for Metadata {
@@ -115,6 +135,8 @@ impl From<&Project> for Metadata {
             start_date: resource.start_date.to_string(),
             end_date: resource.end_date.map(|x| x.to_string()),
             source_url: resource.source_url.clone(),
+            tags: resource.tags.clone(),
+            draft: resource.draft,
         }
     }
 }
@@ -131,32 +153,35 @@ impl From<Project> for ProjectRecord {
             end_date: resource.end_date.map(|s| s.to_string()),
             source_url: resource.source_url,
             body: resource.body,
+            draft: resource.draft,
         }
     }
 }
 
-impl TryFrom<ProjectRecord> for Project {
-    type Error = anyhow::Error;
-
-    fn try_from(record: ProjectRecord) -> Result<Self> {
-        let end_date = if let Some(date) = record.end_date {
-            Some(Date::from_str(&date)?)
-        } else {
-            None
-        };
-        let resource = Self {
-            id: record.id,
-            name: record.name,
-            summary: record.summary,
-            status: record.status,
-            start_date: Date::from_str(&record.start_date)?,
-            end_date,
-            source_url: record.source_url,
-            body: record.body,
-        };
-
-        Ok(resource)
-    }
+fn from_record(tx: &Transaction, record: ProjectRecord) -> Result<Project> {
+    let end_date = if let Some(date) = record.end_date {
+        Some(Date::from_str(&date)?)
+    } else {
+        None
+    };
+    let tags: Vec<String> = TagRecordSet::select(tx, (TagTable::Project, record.id.clone()))?
+        .into_iter()
+        .map(|record| record.tag)
+        .collect();
+    let resource = Project {
+        id: record.id,
+        name: record.name,
+        summary: record.summary,
+        status: record.status,
+        start_date: Date::from_str(&record.start_date)?,
+        end_date,
+        source_url: record.source_url,
+        tags,
+        draft: record.draft,
+        body: record.body,
+    };
+
+    Ok(resource)
 }
 
 #[derive(Clone, Debug)]
@@ -175,7 +200,7 @@ impl ReadCache for ProjectSet {
 
     fn find(tx: &Transaction, id: &str) -> Result<Option<Self::Item>> {
         if let Some(record) = ProjectRecord::select(tx, id)? {
-            let resource = Self::Item::try_from(record)?;
+            let resource = from_record(tx, record)?;
 
             Ok(Some(resource))
         } else {
@@ -187,7 +212,7 @@ impl ReadCache for ProjectSet {
         let records = ProjectRecordSet::select(tx)?;
         let resources = records
             .into_iter()
-            .map(Self::Item::try_from)
+            .map(|record| from_record(tx, record))
             .collect::<Result<Vec<_>>>()?;
 
         Ok(Self::new(resources))
@@ -198,8 +223,22 @@ impl WriteCache for ProjectSet {
     type Item = Project;
 
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
+        // Tags removed from the project would otherwise linger.
+        TagRecordSet::delete(tx, TagTable::Project, &resource.id)?;
+
+        for tag in &resource.tags {
+            let record = TagRecord {
+                table: TagTable::Project,
+                resource_id: resource.id.clone(),
+                tag: tag.clone(),
+            };
+
+            record.insert(tx)?;
+        }
+
         let record = ProjectRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "project").insert(tx)?;
 
         Ok(())
     }
@@ -207,6 +246,19 @@ impl WriteCache for ProjectSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         ProjectRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match ProjectRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "project").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for ProjectSet {}
@@ -244,6 +296,8 @@ type: project
 id: acme
 status: ongoing
 start_date: 2021-07-07
+tags:
+- rust
 ---
 # Acme
 
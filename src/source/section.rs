@@ -160,6 +160,7 @@ impl WriteCache for SectionSet {
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
         let record = SectionRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "section").insert(tx)?;
 
         Ok(())
     }
@@ -167,6 +168,19 @@ impl WriteCache for SectionSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         SectionRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match SectionRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "section").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for SectionSet {}
@@ -144,6 +144,7 @@ impl WriteCache for SettingsSet {
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
         let record = SettingsRecord::try_from(resource)?;
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "settings").insert(tx)?;
 
         Ok(())
     }
@@ -151,6 +152,19 @@ impl WriteCache for SettingsSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         SettingsRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match SettingsRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "settings").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for SettingsSet {}
@@ -2,7 +2,6 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
 use std::fmt;
 use std::iter::FromIterator;
 use std::str::FromStr;
@@ -10,6 +9,7 @@ use std::str::FromStr;
 use crate::cache::records::*;
 use crate::cache::{ReadCache, Transaction, WriteCache};
 use crate::checksum::{Digest, Hasher};
+use crate::stamp::Date;
 use crate::{Resource, ResourceSet};
 
 /// A sketch resource.
@@ -21,10 +21,37 @@ pub struct Sketch {
     title: String,
     asset: String,
     author: String,
-    publication_date: String,
+    publication_date: Date,
     tools: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "super::de_unique",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    tags: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     summary: Option<String>,
+    /// Drafts are kept out of the output unless drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    draft: bool,
+}
+
+impl Sketch {
+    pub fn asset(&self) -> &str {
+        &self.asset
+    }
+
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    pub fn tools(&self) -> &[String] {
+        &self.tools
+    }
+
+    pub fn is_draft(&self) -> bool {
+        self.draft
+    }
 }
 
 impl Resource for Sketch {
@@ -44,6 +71,8 @@ impl Digest for Sketch {
         self.publication_date.digest(hasher);
         self.summary.digest(hasher);
         self.tools.digest(hasher);
+        self.tags.digest(hasher);
+        self.draft.digest(hasher);
     }
 }
 
@@ -75,6 +104,7 @@ impl From<Sketch> for SketchRecord {
             author_id: resource.author,
             publication_date: resource.publication_date.to_string(),
             summary: resource.summary,
+            draft: resource.draft,
         }
     }
 }
@@ -84,6 +114,10 @@ fn from_record(tx: &Transaction, record: SketchRecord) -> Result<Sketch> {
         .into_iter()
         .map(|record| record.tool_id)
         .collect();
+    let tags: Vec<String> = TagRecordSet::select(tx, (TagTable::Sketch, record.id.clone()))?
+        .into_iter()
+        .map(|record| record.tag)
+        .collect();
 
     let resource = Sketch {
         _type: "sketch".to_string(),
@@ -91,9 +125,11 @@ fn from_record(tx: &Transaction, record: SketchRecord) -> Result<Sketch> {
         title: record.title,
         asset: record.asset_id,
         author: record.author_id,
-        publication_date: record.publication_date,
+        publication_date: Date::from_str(&record.publication_date)?,
         summary: record.summary,
         tools,
+        tags,
+        draft: record.draft,
     };
 
     Ok(resource)
@@ -138,6 +174,9 @@ impl WriteCache for SketchSet {
     type Item = Sketch;
 
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
+        // Tools removed from the sketch would otherwise linger.
+        SketchToolRecordSet::delete(tx, &resource.id)?;
+
         for tool_id in &resource.tools {
             let record = SketchToolRecord {
                 tool_id: tool_id.clone(),
@@ -147,8 +186,21 @@ impl WriteCache for SketchSet {
             record.insert(tx)?;
         }
 
-        let record = SketchRecord::try_from(resource)?;
+        TagRecordSet::delete(tx, TagTable::Sketch, &resource.id)?;
+
+        for tag in &resource.tags {
+            let record = TagRecord {
+                table: TagTable::Sketch,
+                resource_id: resource.id.clone(),
+                tag: tag.clone(),
+            };
+
+            record.insert(tx)?;
+        }
+
+        let record = SketchRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "sketch").insert(tx)?;
 
         Ok(())
     }
@@ -156,6 +208,19 @@ impl WriteCache for SketchSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         SketchRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match SketchRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "sketch").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for SketchSet {}
@@ -185,7 +250,7 @@ impl FromIterator<Sketch> for SketchSet {
 mod tests {
     use super::*;
     use crate::cache::Cache;
-    use crate::source::{Tool, ToolSet};
+    use crate::source::{Asset, AssetSet, Person, PersonSet, Tool, ToolSet};
 
     #[test]
     fn full_cycle() -> Result<()> {
@@ -194,8 +259,9 @@ id = "calm-dragon"
 title = "Calm dragon"
 asset = "calm-dragon.png"
 author = "arnau"
-publication_date = "2017-09-29"
+publication_date = 2017-09-29
 tools = ["ipadpro"]
+tags = ["dragon"]
 summary = "Dragon head drawn with SketchBook's fountain pen  and colored with Sketches Pro's watercolor"
 "#;
         let raw_tool = r#"---
@@ -203,6 +269,12 @@ type: tool
 id: ipadpro
 name: iPad Pro
 ---"#;
+        let raw_person = r#"type = "person"
+id = "arnau"
+name = "Arnau Siches"
+guest = false
+accounts = []
+"#;
         let mut cache = Cache::connect(":memory:")?;
         let tx = cache.transaction()?;
         let resource = Sketch::from_str(raw)?;
@@ -210,6 +282,11 @@ name: iPad Pro
 
         SketchSet::add(&tx, resource.clone())?;
         ToolSet::add(&tx, tool)?;
+        PersonSet::add(&tx, Person::from_str(raw_person)?)?;
+        AssetSet::add(
+            &tx,
+            Asset::new("calm-dragon.png".into(), "png".into(), vec![]),
+        )?;
 
         let cached = SketchSet::find(&tx, &resource.id)?.expect("resource to be cached");
 
@@ -159,6 +159,7 @@ impl WriteCache for ToolSet {
     fn add(tx: &Transaction, resource: Self::Item) -> Result<()> {
         let record = ToolRecord::from(resource);
         record.insert(tx)?;
+        SessionTrailRecord::new(&record.checksum, "tool").insert(tx)?;
 
         Ok(())
     }
@@ -166,6 +167,19 @@ impl WriteCache for ToolSet {
     fn remove(tx: &Transaction, id: &str) -> Result<()> {
         ToolRecord::delete(tx, id)
     }
+
+    fn touch(tx: &Transaction, resource: &Self::Item) -> Result<bool> {
+        let checksum = resource.checksum().to_string();
+
+        match ToolRecord::select(tx, resource.id())? {
+            Some(record) if record.checksum == checksum => {
+                SessionTrailRecord::new(&checksum, "tool").insert(tx)?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl ResourceSet for ToolSet {}
@@ -5,6 +5,9 @@
 -- according to those terms.
 
 
+-- Foreign keys are deferred until commit (see `Cache::transaction`) so resources can be inserted in any order. They
+-- never cascade on delete because `INSERT OR REPLACE` deletes the row it replaces.
+
 -- Support set to prune the cache from unseen resources.
 CREATE TABLE IF NOT EXISTS session_trail (
   resource_checksum text     NOT NULL,
@@ -14,6 +17,19 @@ CREATE TABLE IF NOT EXISTS session_trail (
   UNIQUE (resource_checksum, resource_type, timestamp)
 );
 
+-- The current session. Only lives as long as the connection.
+CREATE TEMP TABLE IF NOT EXISTS session (
+  timestamp datetime NOT NULL
+);
+
+-- What the current session publishes. Only lives as long as the connection.
+--
+-- Anything dated after `as_of` is scheduled. Drafts and scheduled resources are only published when `drafts` is set.
+CREATE TEMP TABLE IF NOT EXISTS publication (
+  as_of  date    NOT NULL,
+  drafts boolean NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS settings (
   id       text NOT NULL PRIMARY KEY,
   checksum text NOT NULL,
@@ -23,12 +39,10 @@ CREATE TABLE IF NOT EXISTS settings (
 -- TODO: Lacks the ability to express multiple representations of the same asset.
 -- For example, a diagram and generated png/svg, etc.
 CREATE TABLE IF NOT EXISTS asset (
-  id            text NOT NULL,
+  id            text NOT NULL PRIMARY KEY,
   checksum      text NOT NULL,
   content_type  text NOT NULL,
-  content       blob NOT NULL,
-
-  UNIQUE (id, content_type)
+  content       blob NOT NULL
 );
 
 
@@ -49,7 +63,7 @@ CREATE TABLE IF NOT EXISTS service_account (
   url       text NOT NULL,
 
   UNIQUE (id, person_id),
-  FOREIGN KEY (person_id) REFERENCES person (id) ON DELETE CASCADE
+  FOREIGN KEY (person_id) REFERENCES person (id)
 );
 
 CREATE TABLE IF NOT EXISTS person (
@@ -67,17 +81,26 @@ CREATE TABLE IF NOT EXISTS note (
   publication_date date NOT NULL,
   author_id        text NOT NULL,
   body             text NOT NULL,
+  draft            boolean NOT NULL DEFAULT false,
 
   FOREIGN KEY (author_id) REFERENCES person (id)
 );
 
+CREATE TABLE IF NOT EXISTS note_tag (
+  note_id text NOT NULL,
+  tag     text NOT NULL,
+
+  UNIQUE (note_id, tag),
+  FOREIGN KEY (note_id) REFERENCES note (id)
+);
+
 CREATE TABLE IF NOT EXISTS sketch_tool (
   sketch_id text NOT NULL,
   tool_id   text NOT NULL,
 
   UNIQUE (sketch_id, tool_id),
-  FOREIGN KEY (tool_id) REFERENCES tool (id) ON DELETE CASCADE,
-  FOREIGN KEY (sketch_id) REFERENCES sketch (id) ON DELETE CASCADE
+  FOREIGN KEY (tool_id) REFERENCES tool (id),
+  FOREIGN KEY (sketch_id) REFERENCES sketch (id)
 );
 
 CREATE TABLE IF NOT EXISTS sketch (
@@ -88,8 +111,18 @@ CREATE TABLE IF NOT EXISTS sketch (
   author_id        text NOT NULL,
   publication_date date NOT NULL,
   summary          text,
+  draft            boolean NOT NULL DEFAULT false,
 
-  FOREIGN KEY (asset_id) REFERENCES asset (id) ON DELETE CASCADE
+  FOREIGN KEY (asset_id) REFERENCES asset (id),
+  FOREIGN KEY (author_id) REFERENCES person (id)
+);
+
+CREATE TABLE IF NOT EXISTS sketch_tag (
+  sketch_id text NOT NULL,
+  tag       text NOT NULL,
+
+  UNIQUE (sketch_id, tag),
+  FOREIGN KEY (sketch_id) REFERENCES sketch (id)
 );
 
 
@@ -97,7 +130,8 @@ CREATE TABLE IF NOT EXISTS bulletin_issue (
   id               text NOT NULL PRIMARY KEY,
   checksum         text NOT NULL,
   summary          text NOT NULL,
-  publication_date date NOT NULL
+  publication_date date NOT NULL,
+  draft            boolean NOT NULL DEFAULT false
 );
 
 CREATE TABLE IF NOT EXISTS bulletin_entry (
@@ -107,6 +141,8 @@ CREATE TABLE IF NOT EXISTS bulletin_entry (
   summary      text NOT NULL,
   content_type text NOT NULL,
   issue_id     text,
+  -- Only for timed content such as video, audio and podcasts.
+  duration     text,
 
   FOREIGN KEY (issue_id) REFERENCES bulletin_issue (id)
 );
@@ -116,7 +152,24 @@ CREATE TABLE IF NOT EXISTS bulletin_mention (
   entry_url   text NOT NULL,
 
   UNIQUE (mention_url, entry_url),
-  FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url) ON DELETE CASCADE
+  FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url)
+);
+
+-- Only for papers.
+CREATE TABLE IF NOT EXISTS bulletin_entry_author (
+  entry_url text NOT NULL,
+  name      text NOT NULL,
+
+  UNIQUE (entry_url, name),
+  FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url)
+);
+
+CREATE TABLE IF NOT EXISTS bulletin_entry_tag (
+  entry_url text NOT NULL,
+  tag       text NOT NULL,
+
+  UNIQUE (entry_url, tag),
+  FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url)
 );
 
 CREATE TABLE IF NOT EXISTS project (
@@ -130,7 +183,16 @@ CREATE TABLE IF NOT EXISTS project (
   start_date date NOT NULL,
   end_date   date,
 
-  source_url text
+  source_url text,
+  draft      boolean NOT NULL DEFAULT false
+);
+
+CREATE TABLE IF NOT EXISTS project_tag (
+  project_id text NOT NULL,
+  tag        text NOT NULL,
+
+  UNIQUE (project_id, tag),
+  FOREIGN KEY (project_id) REFERENCES project (id)
 );
 
 CREATE TABLE IF NOT EXISTS section (
@@ -147,8 +209,58 @@ CREATE TABLE IF NOT EXISTS entrance (
   body     text
 );
 
+-- The notes, sketches, projects and bulletins that are drafts or scheduled after the publication date.
+CREATE TEMP VIEW IF NOT EXISTS unpublished AS
+  SELECT
+    'note' AS resource_type,
+    id
+  FROM
+    note
+  WHERE
+    draft OR publication_date > (SELECT as_of FROM publication)
+
+  UNION ALL
+
+  SELECT
+    'sketch' AS resource_type,
+    id
+  FROM
+    sketch
+  WHERE
+    draft OR publication_date > (SELECT as_of FROM publication)
+
+  UNION ALL
+
+  SELECT
+    'bulletin' AS resource_type,
+    id
+  FROM
+    bulletin_issue
+  WHERE
+    draft OR publication_date > (SELECT as_of FROM publication)
+
+  UNION ALL
+
+  SELECT
+    'project' AS resource_type,
+    id
+  FROM
+    project
+  WHERE
+    draft OR start_date > (SELECT as_of FROM publication);
+
+-- The unpublished resources kept out of the output for the current session.
+CREATE TEMP VIEW IF NOT EXISTS excluded AS
+  SELECT
+    resource_type,
+    id
+  FROM
+    unpublished
+  WHERE
+    NOT (SELECT drafts FROM publication);
+
 -- The set of recent news across all relevant resources.
-CREATE VIEW IF NOT EXISTS news AS
+CREATE TEMP VIEW IF NOT EXISTS news AS
   SELECT * FROM (
     SELECT
       id,
@@ -158,6 +270,8 @@ CREATE VIEW IF NOT EXISTS news AS
       publication_date AS date
     FROM
       note
+    WHERE
+      id NOT IN (SELECT id FROM excluded WHERE resource_type = 'note')
     ORDER BY
       publication_date DESC
     LIMIT 3
@@ -174,6 +288,8 @@ CREATE VIEW IF NOT EXISTS news AS
       publication_date AS date
     FROM
       sketch
+    WHERE
+      id NOT IN (SELECT id FROM excluded WHERE resource_type = 'sketch')
     ORDER BY
       publication_date DESC
     LIMIT 1
@@ -190,6 +306,8 @@ CREATE VIEW IF NOT EXISTS news AS
       publication_date AS date
     FROM
       bulletin_issue
+    WHERE
+      id NOT IN (SELECT id FROM excluded WHERE resource_type = 'bulletin')
     ORDER BY
       publication_date DESC
     LIMIT 1
@@ -206,6 +324,8 @@ CREATE VIEW IF NOT EXISTS news AS
       start_date AS date
     FROM
       project
+    WHERE
+      id NOT IN (SELECT id FROM excluded WHERE resource_type = 'project')
     ORDER BY
       start_date DESC
     LIMIT 1
@@ -214,15 +334,21 @@ ORDER BY date DESC;
 
 
 -- Like news but with equal weigth for all sources.
-CREATE VIEW IF NOT EXISTS feed AS
+--
+-- The section is the conventional one for the resource type, for when no section holds that type.
+CREATE TEMP VIEW IF NOT EXISTS feed AS
   SELECT
     id,
     title,
     summary,
+    'note' AS resource_type,
     'notes' AS section,
+    NULL AS year,
     publication_date AS date
   FROM
     note
+  WHERE
+    id NOT IN (SELECT id FROM excluded WHERE resource_type = 'note')
 
   UNION
 
@@ -230,10 +356,14 @@ CREATE VIEW IF NOT EXISTS feed AS
     id,
     title,
     summary,
+    'sketch' AS resource_type,
     'sketches' AS section,
+    NULL AS year,
     publication_date AS date
   FROM
     sketch
+  WHERE
+    id NOT IN (SELECT id FROM excluded WHERE resource_type = 'sketch')
 
   UNION
 
@@ -241,10 +371,15 @@ CREATE VIEW IF NOT EXISTS feed AS
     id,
     id AS title,
     summary,
-    'bulletins/' || strftime('%Y', publication_date) AS section, -- TODO: Compose year.
+    'bulletin' AS resource_type,
+    'bulletins' AS section,
+    -- Bulletin ids are ISO weeks (YYYY-Www) so the year is the ISO week-year.
+    substr(id, 1, 4) AS year,
     publication_date AS date
   FROM
     bulletin_issue
+  WHERE
+    id NOT IN (SELECT id FROM excluded WHERE resource_type = 'bulletin')
 
   UNION
 
@@ -252,9 +387,13 @@ CREATE VIEW IF NOT EXISTS feed AS
     id,
     name AS title,
     summary,
+    'project' AS resource_type,
     'projects' AS section,
+    NULL AS year,
     start_date AS date
   FROM
     project
+  WHERE
+    id NOT IN (SELECT id FROM excluded WHERE resource_type = 'project')
 
 ORDER BY date DESC;
@@ -0,0 +1,79 @@
+-- Copyright 2021 Arnau Siches
+--
+-- Licensed under the MIT license <LICENCE or http://opensource.org/licenses/MIT>.
+-- This file may not be copied, modified, or distributed except
+-- according to those terms.
+
+
+-- From an unversioned cache to version 1.
+--
+-- Assets are keyed by id, sketch authors reference people and foreign keys no longer cascade on delete. SQLite cannot
+-- alter constraints so each affected table is rebuilt.
+
+-- Views would break the table renames. The bootstrap recreates them.
+DROP VIEW IF EXISTS news;
+DROP VIEW IF EXISTS feed;
+
+CREATE TABLE asset_v1 (
+  id            text NOT NULL PRIMARY KEY,
+  checksum      text NOT NULL,
+  content_type  text NOT NULL,
+  content       blob NOT NULL
+);
+INSERT OR REPLACE INTO asset_v1 SELECT id, checksum, content_type, content FROM asset;
+DROP TABLE asset;
+ALTER TABLE asset_v1 RENAME TO asset;
+
+CREATE TABLE service_account_v1 (
+  id        text NOT NULL,
+  person_id text NOT NULL,
+  checksum  text NOT NULL,
+  name      text NOT NULL,
+  username  text NOT NULL,
+  url       text NOT NULL,
+
+  UNIQUE (id, person_id),
+  FOREIGN KEY (person_id) REFERENCES person (id)
+);
+INSERT INTO service_account_v1 SELECT id, person_id, checksum, name, username, url FROM service_account;
+DROP TABLE service_account;
+ALTER TABLE service_account_v1 RENAME TO service_account;
+
+CREATE TABLE sketch_tool_v1 (
+  sketch_id text NOT NULL,
+  tool_id   text NOT NULL,
+
+  UNIQUE (sketch_id, tool_id),
+  FOREIGN KEY (tool_id) REFERENCES tool (id),
+  FOREIGN KEY (sketch_id) REFERENCES sketch (id)
+);
+INSERT INTO sketch_tool_v1 SELECT sketch_id, tool_id FROM sketch_tool;
+DROP TABLE sketch_tool;
+ALTER TABLE sketch_tool_v1 RENAME TO sketch_tool;
+
+CREATE TABLE sketch_v1 (
+  id               text NOT NULL PRIMARY KEY,
+  checksum         text NOT NULL,
+  title            text NOT NULL,
+  asset_id         text NOT NULL,
+  author_id        text NOT NULL,
+  publication_date date NOT NULL,
+  summary          text,
+
+  FOREIGN KEY (asset_id) REFERENCES asset (id),
+  FOREIGN KEY (author_id) REFERENCES person (id)
+);
+INSERT INTO sketch_v1 SELECT id, checksum, title, asset_id, author_id, publication_date, summary FROM sketch;
+DROP TABLE sketch;
+ALTER TABLE sketch_v1 RENAME TO sketch;
+
+CREATE TABLE bulletin_mention_v1 (
+  mention_url text NOT NULL,
+  entry_url   text NOT NULL,
+
+  UNIQUE (mention_url, entry_url),
+  FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url)
+);
+INSERT INTO bulletin_mention_v1 SELECT mention_url, entry_url FROM bulletin_mention;
+DROP TABLE bulletin_mention;
+ALTER TABLE bulletin_mention_v1 RENAME TO bulletin_mention;
@@ -0,0 +1,42 @@
+-- Copyright 2021 Arnau Siches
+--
+-- Licensed under the MIT license <LICENCE or http://opensource.org/licenses/MIT>.
+-- This file may not be copied, modified, or distributed except
+-- according to those terms.
+
+
+-- From version 1 to version 2.
+--
+-- Notes, sketches, projects and bulletin entries gain tags.
+
+CREATE TABLE IF NOT EXISTS note_tag (
+  note_id text NOT NULL,
+  tag     text NOT NULL,
+
+  UNIQUE (note_id, tag),
+  FOREIGN KEY (note_id) REFERENCES note (id)
+);
+
+CREATE TABLE IF NOT EXISTS sketch_tag (
+  sketch_id text NOT NULL,
+  tag       text NOT NULL,
+
+  UNIQUE (sketch_id, tag),
+  FOREIGN KEY (sketch_id) REFERENCES sketch (id)
+);
+
+CREATE TABLE IF NOT EXISTS project_tag (
+  project_id text NOT NULL,
+  tag        text NOT NULL,
+
+  UNIQUE (project_id, tag),
+  FOREIGN KEY (project_id) REFERENCES project (id)
+);
+
+CREATE TABLE IF NOT EXISTS bulletin_entry_tag (
+  entry_url text NOT NULL,
+  tag       text NOT NULL,
+
+  UNIQUE (entry_url, tag),
+  FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url)
+);
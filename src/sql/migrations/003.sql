@@ -0,0 +1,13 @@
+-- Copyright 2021 Arnau Siches
+--
+-- Licensed under the MIT license <LICENCE or http://opensource.org/licenses/MIT>.
+-- This file may not be copied, modified, or distributed except
+-- according to those terms.
+
+
+-- From version 2 to version 3.
+--
+-- Bulletins are grouped by the ISO week-year in their id instead of the calendar year of their publication date.
+-- The bootstrap recreates the view.
+
+DROP VIEW IF EXISTS feed;
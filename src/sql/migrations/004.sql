@@ -0,0 +1,20 @@
+-- Copyright 2021 Arnau Siches
+--
+-- Licensed under the MIT license <LICENCE or http://opensource.org/licenses/MIT>.
+-- This file may not be copied, modified, or distributed except
+-- according to those terms.
+
+
+-- From version 3 to version 4.
+--
+-- Bulletin entries gain a duration for timed content and authors for papers.
+
+ALTER TABLE bulletin_entry ADD COLUMN duration text;
+
+CREATE TABLE IF NOT EXISTS bulletin_entry_author (
+  entry_url text NOT NULL,
+  name      text NOT NULL,
+
+  UNIQUE (entry_url, name),
+  FOREIGN KEY (entry_url) REFERENCES bulletin_entry (url)
+);
@@ -0,0 +1,19 @@
+-- Copyright 2021 Arnau Siches
+--
+-- Licensed under the MIT license <LICENCE or http://opensource.org/licenses/MIT>.
+-- This file may not be copied, modified, or distributed except
+-- according to those terms.
+
+
+-- From version 4 to version 5.
+--
+-- Notes, sketches, projects and bulletins can be drafts. The news and feed views depend on what the session publishes
+-- so they become temporary views. The bootstrap recreates them.
+
+DROP VIEW IF EXISTS news;
+DROP VIEW IF EXISTS feed;
+
+ALTER TABLE note ADD COLUMN draft boolean NOT NULL DEFAULT false;
+ALTER TABLE sketch ADD COLUMN draft boolean NOT NULL DEFAULT false;
+ALTER TABLE bulletin_issue ADD COLUMN draft boolean NOT NULL DEFAULT false;
+ALTER TABLE project ADD COLUMN draft boolean NOT NULL DEFAULT false;
@@ -1,7 +1,8 @@
 //! This module defines the stamps for dates and time.
 
 use crate::checksum::{Digest, Hasher, Tag};
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use chrono::Datelike;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -12,14 +13,107 @@ pub type DateTime = chrono::DateTime<chrono::Utc>;
 pub struct Date(chrono::NaiveDate);
 
 impl Date {
+    /// Today's date in UTC.
+    pub fn today() -> Self {
+        Self(chrono::Utc::now().date_naive())
+    }
+
     pub fn year(&self) -> String {
         self.0.format("%Y").to_string()
     }
+
+    /// The ISO week the date belongs to, e.g. `2021-W01`.
+    pub fn iso_week(&self) -> IsoWeek {
+        let week = self.0.iso_week();
+
+        IsoWeek {
+            year: week.year(),
+            week: week.week(),
+        }
+    }
+
+    /// The Sunday closing the ISO week the date belongs to.
+    pub fn week_sunday(&self) -> Self {
+        self.iso_week().sunday()
+    }
+}
+
+/// An [ISO week date] week, written as `YYYY-Www`.
+///
+/// The year is the ISO week-year which differs from the calendar year for the days around the new year. For example,
+/// 2021-01-02 belongs to `2020-W53`.
+///
+/// [ISO week date]: https://en.wikipedia.org/wiki/ISO_week_date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IsoWeek {
+    year: i32,
+    week: u32,
+}
+
+impl IsoWeek {
+    /// The ISO week-year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn week(&self) -> u32 {
+        self.week
+    }
+
+    /// The Monday opening the week.
+    pub fn monday(&self) -> Date {
+        self.weekday(chrono::Weekday::Mon)
+    }
+
+    /// The Sunday closing the week.
+    pub fn sunday(&self) -> Date {
+        self.weekday(chrono::Weekday::Sun)
+    }
+
+    /// Whether the given date falls within the week.
+    pub fn contains(&self, date: &Date) -> bool {
+        &date.iso_week() == self
+    }
+
+    fn weekday(&self, weekday: chrono::Weekday) -> Date {
+        let date = chrono::NaiveDate::from_isoywd_opt(self.year, self.week, weekday)
+            .expect("a parsed ISO week to exist");
+
+        Date(date)
+    }
+}
+
+impl fmt::Display for IsoWeek {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-W{:02}", self.year, self.week)
+    }
+}
+
+impl FromStr for IsoWeek {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow!("'{}' is not an ISO week. Expected the form YYYY-Www", s);
+        let (year, week) = s.split_once("-W").ok_or_else(invalid)?;
+
+        if year.len() != 4 || week.len() != 2 {
+            return Err(invalid());
+        }
+
+        let year: i32 = year.parse().map_err(|_| invalid())?;
+        let week: u32 = week.parse().map_err(|_| invalid())?;
+
+        if chrono::NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon).is_none() {
+            bail!("'{}' is not an ISO week. {} has no week {}", s, year, week);
+        }
+
+        Ok(Self { year, week })
+    }
 }
 
 impl fmt::Display for Date {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0.format("%Y-%m-%d").to_string())
+        write!(f, "{}", self.0.format("%Y-%m-%d"))
     }
 }
 
@@ -73,7 +167,10 @@ impl<'de> Visitor<'de> for DateVisitor {
     where
         V: de::MapAccess<'de>,
     {
-        let date = Date::from_str(visitor.next_value()?).map_err(de::Error::custom)?;
+        // TOML datetimes come as a single entry map holding the datetime as a string.
+        let _key: Option<String> = visitor.next_key()?;
+        let value: String = visitor.next_value()?;
+        let date = Date::from_str(&value).map_err(de::Error::custom)?;
 
         Ok(date)
     }
@@ -124,6 +221,49 @@ date = "2021-01-02"
         Ok(())
     }
 
+    #[test]
+    fn des_toml_datetime() -> Result<()> {
+        let raw = r#"title = "Test"
+date = 2021-01-02
+"#;
+        let test: Test = toml::from_str(raw)?;
+
+        assert_eq!(&test.date.to_string(), "2021-01-02");
+
+        Ok(())
+    }
+
+    #[test]
+    fn iso_week() -> Result<()> {
+        let date = Date::from_str("2021-01-02")?;
+
+        assert_eq!(&date.iso_week().to_string(), "2020-W53");
+        assert_eq!(&date.week_sunday().to_string(), "2021-01-03");
+
+        let date = Date::from_str("2021-01-04")?;
+
+        assert_eq!(&date.iso_week().to_string(), "2021-W01");
+        assert_eq!(&date.week_sunday().to_string(), "2021-01-10");
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_iso_week() -> Result<()> {
+        let week = IsoWeek::from_str("2021-W52")?;
+
+        assert_eq!(week.year(), 2021);
+        assert_eq!(&week.monday().to_string(), "2021-12-27");
+        assert!(week.contains(&Date::from_str("2022-01-02")?));
+        assert!(!week.contains(&Date::from_str("2022-01-03")?));
+        assert_eq!(IsoWeek::from_str("2020-W53")?.to_string(), "2020-W53");
+        assert!(IsoWeek::from_str("2021-W53").is_err());
+        assert!(IsoWeek::from_str("2021-W1").is_err());
+        assert!(IsoWeek::from_str("2021-01").is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn ser_toml() -> Result<()> {
         let test = Test {
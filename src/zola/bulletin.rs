@@ -6,7 +6,11 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
 
+use super::taxonomies::{self, Taxonomies};
 use super::ZolaResource;
+use crate::cache::records::{
+    AuxRecordSet, BulletinEntryAuthorRecordSet, BulletinMentionRecordSet, TagTable,
+};
 use crate::cache::{params, Row, Transaction};
 use crate::markdown::strip;
 use crate::resource_type::ResourceType;
@@ -48,9 +52,14 @@ pub struct Metadata {
     pub(crate) title: String,
     pub(crate) description: String,
     pub(crate) date: Date,
+    /// Scheduled resources are drafts too. Only present when drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) draft: bool,
     pub(crate) slug: String,
     pub(crate) template: String,
     pub(crate) in_search_index: bool,
+    #[serde(default, skip_serializing_if = "Taxonomies::is_empty")]
+    pub(crate) taxonomies: Taxonomies,
     pub(crate) extra: Extra,
 }
 
@@ -67,6 +76,17 @@ pub struct Entry {
     pub(crate) title: String,
     pub(crate) summary: String,
     pub(crate) content_type: String,
+    /// Only for video, audio and podcasts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) duration: Option<String>,
+    /// Only for papers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<String>,
+    /// Where the link was found.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) mentions: Vec<String>,
 }
 
 impl TryFrom<&Row<'_>> for Entry {
@@ -80,6 +100,10 @@ impl TryFrom<&Row<'_>> for Entry {
             title,
             summary: row.get(2)?,
             content_type: row.get(3)?,
+            duration: row.get(4)?,
+            authors: Vec::new(),
+            tags: Vec::new(),
+            mentions: Vec::new(),
         };
 
         Ok(resource)
@@ -95,6 +119,7 @@ impl TryFrom<&Row<'_>> for Bulletin {
         let description = strip(&body);
         let raw_date: String = row.get(2)?;
         let date = Date::from_str(&raw_date)?;
+        let draft: bool = row.get(3)?;
 
         let extra = Extra {
             id: id.clone(),
@@ -107,6 +132,8 @@ impl TryFrom<&Row<'_>> for Bulletin {
             date,
             template: "bulletin.html".to_owned(),
             in_search_index: true,
+            taxonomies: Taxonomies::default(),
+            draft,
             extra,
         };
         let resource = Self { metadata, body };
@@ -115,6 +142,7 @@ impl TryFrom<&Row<'_>> for Bulletin {
     }
 }
 
+/// Selects the entries for the given issue in the order they were given.
 fn select_entries(tx: &Transaction, issue_id: &str) -> Result<Vec<Entry>> {
     let mut set = Vec::new();
     let mut stmt = tx.prepare(
@@ -123,23 +151,37 @@ fn select_entries(tx: &Transaction, issue_id: &str) -> Result<Vec<Entry>> {
             url,
             title,
             summary,
-            content_type
+            content_type,
+            duration
         FROM
             bulletin_entry
         WHERE
             issue_id = ?
+        ORDER BY
+            rowid
         "#,
     )?;
     let mut rows = stmt.query(params![issue_id])?;
 
     while let Some(row) = rows.next()? {
-        let record = Entry::try_from(row)?;
+        let mut record = Entry::try_from(row)?;
+        record.tags = taxonomies::select_tags(tx, TagTable::BulletinEntry, &record.url)?;
+        record.authors = BulletinEntryAuthorRecordSet::select(tx, record.url.clone())?
+            .into_iter()
+            .map(|author| author.name)
+            .collect();
+        record.mentions = BulletinMentionRecordSet::select(tx, record.url.clone())?
+            .into_iter()
+            .map(|mention| mention.mention_url)
+            .collect();
+
         set.push(record);
     }
 
     Ok(set)
 }
 
+/// Amasses the bulletins for the given ISO week-year.
 pub fn amass(tx: &Transaction, year: &str) -> Result<Vec<Bulletin>> {
     let mut set = Vec::new();
     let mut stmt = tx.prepare(
@@ -147,11 +189,14 @@ pub fn amass(tx: &Transaction, year: &str) -> Result<Vec<Bulletin>> {
         SELECT
             id,
             summary,
-            publication_date
+            publication_date,
+            id IN (SELECT id FROM unpublished WHERE resource_type = 'bulletin') AS draft
         FROM
             bulletin_issue
         WHERE
-            strftime('%Y', publication_date) = ?
+            substr(id, 1, 4) = ?
+        AND
+            id NOT IN (SELECT id FROM excluded WHERE resource_type = 'bulletin')
         "#,
     )?;
     let mut rows = stmt.query(params![year])?;
@@ -159,6 +204,14 @@ pub fn amass(tx: &Transaction, year: &str) -> Result<Vec<Bulletin>> {
     while let Some(row) = rows.next()? {
         let mut resource = Bulletin::try_from(row)?;
         let entries = select_entries(tx, resource.id())?;
+        // An issue is tagged with every tag from its entries.
+        let mut tags: Vec<String> = entries
+            .iter()
+            .flat_map(|entry| entry.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        resource.metadata.taxonomies = Taxonomies::new(tags);
         resource.metadata.extra.entries = entries;
 
         set.push(resource);
@@ -76,14 +76,18 @@ impl TryFrom<&Row<'_>> for BulletinYear {
     }
 }
 
+/// Amasses the years with bulletins. Issues belong to the ISO week-year of their id, not the calendar year they were
+/// published in.
 pub fn amass(tx: &Transaction) -> Result<Vec<BulletinYear>> {
     let mut set = Vec::new();
     let mut stmt = tx.prepare(
         r#"
         SELECT DISTINCT
-            strftime('%Y', publication_date) as id
+            substr(id, 1, 4) as id
         FROM
             bulletin_issue
+        WHERE
+            id NOT IN (SELECT id FROM excluded WHERE resource_type = 'bulletin')
         "#,
     )?;
     let mut rows = stmt.query([])?;
@@ -96,3 +100,36 @@ pub fn amass(tx: &Transaction) -> Result<Vec<BulletinYear>> {
 
     Ok(set)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::records::{BulletinRecord, Record};
+    use crate::cache::Cache;
+    use crate::zola::bulletin;
+
+    #[test]
+    fn iso_week_year() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+
+        BulletinRecord {
+            id: "2020-W53".into(),
+            checksum: "2020-W53".into(),
+            summary: "".into(),
+            publication_date: "2021-01-03".into(),
+            draft: false,
+        }
+        .insert(&tx)?;
+
+        let years: Vec<String> = amass(&tx)?
+            .iter()
+            .map(|year| year.id().to_string())
+            .collect();
+
+        assert_eq!(years, vec!["2020"]);
+        assert_eq!(bulletin::amass(&tx, "2020")?.len(), 1);
+
+        Ok(())
+    }
+}
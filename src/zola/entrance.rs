@@ -9,7 +9,7 @@ use super::ZolaResource;
 use crate::cache::records::*;
 use crate::cache::Transaction;
 use crate::resource_type::ResourceType;
-use crate::stamp::Date;
+use crate::stamp::{Date, IsoWeek};
 
 #[derive(Debug, Clone)]
 pub struct Entrance {
@@ -82,7 +82,9 @@ impl TryFrom<UpdateRecord> for Update {
     fn try_from(record: UpdateRecord) -> Result<Self> {
         let date = Date::from_str(&record.date)?;
         let path = if &record.section == "bulletins" {
-            format!("/{}/{}/{}", &record.section, date.year(), &record.id)
+            let week = IsoWeek::from_str(&record.id)?;
+
+            format!("/{}/{}/{}", &record.section, week.year(), &record.id)
         } else {
             format!("/{}/{}", &record.section, &record.id)
         };
@@ -1,21 +1,27 @@
 use anyhow::Result;
-use log::{info, warn};
+use log::{debug, info, warn};
+use std::collections::HashSet;
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 use crate::resource_type::ResourceType;
-use crate::Cache;
+use crate::{Cache, Mode};
 
 pub mod author;
 pub mod bulletin;
 pub mod bulletin_year;
 pub mod entrance;
 pub mod note;
+pub mod person;
 pub mod project;
 pub mod section;
 pub mod settings;
 pub mod sketch;
+pub mod taxonomies;
+pub mod thumbnail;
+pub mod tool;
 
 // TODO: Review convergence with `Resource`.
 pub trait ZolaResource: fmt::Display {
@@ -26,66 +32,93 @@ pub trait ZolaResource: fmt::Display {
     fn resource_type(&self) -> Option<&ResourceType>;
 }
 
-pub fn write(sink_dir: &Path, cache: &mut Cache) -> Result<()> {
+/// Writes the Zola content for every cached resource.
+///
+/// In [`Mode::Incremental`] the previous build is kept, files are only rewritten when their content changes and any
+/// file not produced by this build is removed.
+///
+/// With `highlight`, code blocks in a supported language are highlighted at build time. Otherwise they are left for
+/// Zola to highlight.
+///
+/// Returns the files written or removed, in the order it happened.
+pub fn write(
+    sink_dir: &Path,
+    cache: &mut Cache,
+    mode: Mode,
+    highlight: bool,
+) -> Result<Vec<PathBuf>> {
     let tx = cache.transaction()?;
+    let mut sink = Sink::new(mode);
 
     // Agressively clean previous build.
-    if sink_dir.exists() {
+    if mode == Mode::Full && sink_dir.exists() {
         fs::remove_dir_all(sink_dir)?;
     }
-    fs::create_dir(sink_dir)?;
+    sink.create_dir(sink_dir)?;
 
+    let mut settings = settings::find(&tx, "main")?.expect("settings to exist");
+    settings.accounts = person::select_own_accounts(&tx)?;
+    write_resource(&mut sink, sink_dir, Box::new(settings))?;
     write_resource(
-        sink_dir,
-        Box::new(settings::find(&tx, "main")?.expect("settings to exist")),
-    )?;
-    write_resource(
+        &mut sink,
         sink_dir,
         Box::new(entrance::find(&tx)?.expect("entrance to exist")),
     )?;
 
     let sections = section::amass(&tx)?;
     for section in sections {
-        let section_path = sink_dir.join(&section.path());
+        let section_path = sink_dir.join(section.path());
         let resource_type = section.resource_type();
 
-        fs::create_dir(&section_path)?;
-        fs::write(&section_path.join("_index.md"), &section.to_string())?;
+        sink.create_dir(&section_path)?;
+        sink.write(&section_path.join("_index.md"), section.to_string())?;
         info!("zola(section): {}", section.id());
 
         match resource_type {
             Some(ResourceType::Note) => {
-                let resources = note::amass(&tx)?;
+                let resources = note::amass(&tx, highlight)?;
                 for resource in resources {
-                    write_resource(&section_path, Box::new(resource))?;
+                    write_resource(&mut sink, &section_path, Box::new(resource))?;
                 }
             }
             Some(ResourceType::Project) => {
                 let resources = project::amass(&tx)?;
                 for resource in resources {
-                    write_resource(&section_path, Box::new(resource))?;
+                    write_resource(&mut sink, &section_path, Box::new(resource))?;
                 }
             }
             Some(ResourceType::Sketch) => {
                 let resources = sketch::amass(&tx)?;
                 for (resource, asset) in resources {
                     let resource_path = section_path.join(resource.id());
-                    fs::create_dir(&resource_path)?;
-                    write_resource(&resource_path, Box::new(resource))?;
-                    write_asset(&resource_path, asset)?;
+                    sink.create_dir(&resource_path)?;
+                    write_resource(&mut sink, &resource_path, Box::new(resource))?;
+                    write_asset(&mut sink, &resource_path, asset)?;
+                }
+            }
+            Some(ResourceType::Person) => {
+                let resources = person::amass(&tx)?;
+                for resource in resources {
+                    write_resource(&mut sink, &section_path, Box::new(resource))?;
+                }
+            }
+            Some(ResourceType::Tool) => {
+                let resources = tool::amass(&tx)?;
+                for resource in resources {
+                    write_resource(&mut sink, &section_path, Box::new(resource))?;
                 }
             }
             Some(ResourceType::Bulletin) => {
                 let resources = bulletin_year::amass(&tx)?;
                 for resource in resources {
-                    let year_path = section_path.join(&resource.path());
-                    fs::create_dir(&year_path)?;
-                    fs::write(&year_path.join("_index.md"), &resource.to_string())?;
+                    let year_path = section_path.join(resource.path());
+                    sink.create_dir(&year_path)?;
+                    sink.write(&year_path.join("_index.md"), resource.to_string())?;
                     info!("zola(section): {}", resource.id());
 
                     let bulletins = bulletin::amass(&tx, resource.id())?;
                     for bulletin in bulletins {
-                        write_resource(&year_path, Box::new(bulletin))?;
+                        write_resource(&mut sink, &year_path, Box::new(bulletin))?;
                     }
                 }
             }
@@ -96,13 +129,17 @@ pub fn write(sink_dir: &Path, cache: &mut Cache) -> Result<()> {
         }
     }
 
+    if mode == Mode::Incremental {
+        sink.sweep(sink_dir)?;
+    }
+
     tx.commit()?;
 
-    Ok(())
+    Ok(sink.changed)
 }
 
-fn write_resource(sink_dir: &Path, resource: Box<dyn ZolaResource>) -> Result<()> {
-    fs::write(sink_dir.join(&resource.path()), &resource.to_string())?;
+fn write_resource(sink: &mut Sink, sink_dir: &Path, resource: Box<dyn ZolaResource>) -> Result<()> {
+    sink.write(&sink_dir.join(resource.path()), resource.to_string())?;
     info!(
         "zola({}): {}",
         resource
@@ -114,9 +151,75 @@ fn write_resource(sink_dir: &Path, resource: Box<dyn ZolaResource>) -> Result<()
     Ok(())
 }
 
-fn write_asset(path: &Path, asset: sketch::Asset) -> Result<()> {
-    fs::write(path.join(asset.id()), asset.blob())?;
+fn write_asset(sink: &mut Sink, path: &Path, asset: sketch::Asset) -> Result<()> {
+    sink.write(&path.join(asset.id()), asset.blob())?;
     info!("zola(asset): {}", asset.id());
 
     Ok(())
 }
+
+/// Keeps track of the files written by a build.
+#[derive(Debug)]
+struct Sink {
+    mode: Mode,
+    written: HashSet<PathBuf>,
+    changed: Vec<PathBuf>,
+}
+
+impl Sink {
+    fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            written: HashSet::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            fs::create_dir(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the file unless an incremental build finds it with the exact same content.
+    fn write<C: AsRef<[u8]>>(&mut self, path: &Path, contents: C) -> Result<()> {
+        let contents = contents.as_ref();
+        let unchanged = self.mode == Mode::Incremental
+            && fs::read(path)
+                .map(|current| current == contents)
+                .unwrap_or(false);
+
+        if unchanged {
+            debug!("zola: unchanged {}", path.display());
+        } else {
+            fs::write(path, contents)?;
+            self.changed.push(path.to_path_buf());
+        }
+
+        self.written.insert(path.to_path_buf());
+
+        Ok(())
+    }
+
+    /// Removes any file and directory left behind by previous builds.
+    fn sweep(&mut self, sink_dir: &Path) -> Result<()> {
+        for result in WalkDir::new(sink_dir).min_depth(1).contents_first(true) {
+            let entry = result?;
+            let path = entry.path();
+
+            if entry.file_type().is_dir() {
+                if fs::read_dir(path)?.next().is_none() {
+                    fs::remove_dir(path)?;
+                }
+            } else if !self.written.contains(path) {
+                fs::remove_file(path)?;
+                self.changed.push(path.to_path_buf());
+                info!("zola(stale): {}", path.display());
+            }
+        }
+
+        Ok(())
+    }
+}
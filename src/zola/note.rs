@@ -1,16 +1,19 @@
 //! This module covers the [Zola page] for a note.
 //!
 //! [Zola page]: https://www.getzola.org/documentation/content/page/
-use anyhow::Result;
+use anyhow::{Context, Result};
+use log::warn;
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
 
 use super::author::Author;
+use super::taxonomies::{self, Taxonomies};
 use super::ZolaResource;
+use crate::cache::records::TagTable;
 use crate::cache::{Row, Transaction};
-use crate::markdown;
+use crate::markdown::{self, Heading, Toc};
 use crate::resource_type::ResourceType;
 use crate::stamp::Date;
 
@@ -50,8 +53,13 @@ pub struct Metadata {
     pub(crate) title: String,
     pub(crate) description: String,
     pub(crate) date: Date,
+    /// Scheduled resources are drafts too. Only present when drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) draft: bool,
     pub(crate) template: String,
     pub(crate) in_search_index: bool,
+    #[serde(default, skip_serializing_if = "Taxonomies::is_empty")]
+    pub(crate) taxonomies: Taxonomies,
     pub(crate) extra: Extra,
 }
 
@@ -61,6 +69,8 @@ pub struct Extra {
     pub(crate) title: String,
     pub(crate) summary: String,
     pub(crate) author: Author,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) toc: Vec<Heading>,
 }
 
 impl TryFrom<&Row<'_>> for Note {
@@ -72,17 +82,24 @@ impl TryFrom<&Row<'_>> for Note {
         let summary: String = row.get(2)?;
         let date: String = row.get(3)?;
         let body: String = row.get(5)?;
+        let draft: bool = row.get(8)?;
 
         let author = Author {
             id: row.get(4)?,
             name: row.get(6)?,
             guest: row.get(7)?,
         };
+        let toc = Toc::new(&body);
+        for warning in &toc.warnings {
+            warn!("note '{}': {}", &id, warning);
+        }
+
         let extra = Extra {
             id,
             title: title.clone(),
             summary: summary.clone(),
             author,
+            toc: toc.headings,
         };
         let metadata = Metadata {
             title: markdown::strip(&title),
@@ -90,18 +107,18 @@ impl TryFrom<&Row<'_>> for Note {
             date: Date::from_str(&date)?,
             template: "note.html".to_owned(),
             in_search_index: true,
+            taxonomies: Taxonomies::default(),
+            draft,
             extra,
         };
-        let resource = Self {
-            metadata,
-            body: markdown::enrich(&body)?,
-        };
+        let resource = Self { metadata, body };
 
         Ok(resource)
     }
 }
 
-pub fn amass(tx: &Transaction) -> Result<Vec<Note>> {
+/// Reads the published notes. With `highlight`, code blocks are highlighted at build time instead of by Zola.
+pub fn amass(tx: &Transaction, highlight: bool) -> Result<Vec<Note>> {
     let mut set = Vec::new();
     let mut stmt = tx.prepare(
         r#"
@@ -113,21 +130,182 @@ pub fn amass(tx: &Transaction) -> Result<Vec<Note>> {
             note.author_id,
             note.body,
             person.name,
-            person.guest
+            person.guest,
+            note.id IN (SELECT id FROM unpublished WHERE resource_type = 'note') AS draft
         FROM
             note
         INNER JOIN
             person
         ON
             note.author_id = person.id
+        WHERE
+            note.id NOT IN (SELECT id FROM excluded WHERE resource_type = 'note')
             "#,
     )?;
     let mut rows = stmt.query([])?;
 
     while let Some(row) = rows.next()? {
-        let record = Note::try_from(row)?;
+        let mut record = Note::try_from(row)?;
+        record.body = markdown::enrich(&record.body, highlight)
+            .with_context(|| format!("failed to render the body of note '{}'", record.id()))?;
+        let tags = taxonomies::select_tags(tx, TagTable::Note, record.id())?;
+        record.metadata.taxonomies = Taxonomies::new(tags);
+
         set.push(record);
     }
 
     Ok(set)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::records::{NoteRecord, PersonRecord, Record};
+    use crate::cache::Cache;
+    use crate::Publication;
+
+    fn ids(tx: &Transaction) -> Result<Vec<String>> {
+        let mut ids: Vec<String> = amass(tx, false)?
+            .iter()
+            .map(|note| note.id().to_string())
+            .collect();
+        ids.sort();
+
+        Ok(ids)
+    }
+
+    #[test]
+    fn drafts_and_scheduled() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        cache.publish(Publication {
+            as_of: Date::from_str("2021-07-07")?,
+            drafts: false,
+        })?;
+        let tx = cache.transaction()?;
+
+        PersonRecord {
+            id: "arnau".into(),
+            checksum: "arnau".into(),
+            name: "Arnau Siches".into(),
+            guest: false,
+        }
+        .insert(&tx)?;
+
+        for (id, date, draft) in &[
+            ("due", "2021-07-07", false),
+            ("draft", "2021-07-01", true),
+            ("scheduled", "2021-07-08", false),
+        ] {
+            NoteRecord {
+                id: id.to_string(),
+                checksum: id.to_string(),
+                title: id.to_string(),
+                summary: "A summary.".into(),
+                publication_date: date.to_string(),
+                author_id: "arnau".into(),
+                body: "".into(),
+                draft: *draft,
+            }
+            .insert(&tx)?;
+        }
+        tx.commit()?;
+
+        let tx = cache.transaction()?;
+        assert_eq!(ids(&tx)?, vec!["due"]);
+        tx.commit()?;
+
+        cache.publish(Publication {
+            as_of: Date::from_str("2021-07-08")?,
+            drafts: false,
+        })?;
+        let tx = cache.transaction()?;
+        assert_eq!(ids(&tx)?, vec!["due", "scheduled"]);
+        tx.commit()?;
+
+        cache.publish(Publication {
+            as_of: Date::from_str("2021-07-07")?,
+            drafts: true,
+        })?;
+        let tx = cache.transaction()?;
+        let notes = amass(&tx, false)?;
+        let scheduled = notes
+            .iter()
+            .find(|note| note.id() == "scheduled")
+            .expect("scheduled to be published");
+
+        assert_eq!(notes.len(), 3);
+        assert!(scheduled.to_string().contains("draft = true"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_table() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+
+        PersonRecord {
+            id: "arnau".into(),
+            checksum: "arnau".into(),
+            name: "Arnau Siches".into(),
+            guest: false,
+        }
+        .insert(&tx)?;
+        NoteRecord {
+            id: "tabular".into(),
+            checksum: "tabular".into(),
+            title: "Tabular".into(),
+            summary: "A summary.".into(),
+            publication_date: "2021-07-07".into(),
+            author_id: "arnau".into(),
+            body: "```csv target=table\na,b\nc\n```".into(),
+            draft: false,
+        }
+        .insert(&tx)?;
+
+        let err = amass(&tx, false).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "failed to render the body of note 'tabular'"
+        );
+        assert!(format!("{:#}", err).contains("malformed CSV table"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn table_of_contents() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+
+        PersonRecord {
+            id: "arnau".into(),
+            checksum: "arnau".into(),
+            name: "Arnau Siches".into(),
+            guest: false,
+        }
+        .insert(&tx)?;
+        NoteRecord {
+            id: "outlined".into(),
+            checksum: "outlined".into(),
+            title: "Outlined".into(),
+            summary: "A summary.".into(),
+            publication_date: "2021-07-07".into(),
+            author_id: "arnau".into(),
+            body: "Skip to the [end](#closing-thoughts).\n\n## Closing thoughts\n".into(),
+            draft: false,
+        }
+        .insert(&tx)?;
+
+        let notes = amass(&tx, false)?;
+        let page = notes[0].to_string();
+
+        assert_eq!(notes[0].metadata.extra.toc[0].id, "closing-thoughts");
+        assert!(page.contains(
+            "[[extra.toc]]\nlevel = 2\ntitle = \"Closing thoughts\"\nid = \"closing-thoughts\"\n"
+        ));
+
+        Ok(())
+    }
+}
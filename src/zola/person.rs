@@ -0,0 +1,344 @@
+//! This module covers the Zola page for a person.
+//!
+//! Projects have no author so they are left out of person pages.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use super::thumbnail::Thumbnail;
+use super::ZolaResource;
+use crate::cache::{params, Row, Transaction};
+use crate::resource_type::ResourceType;
+use crate::stamp::Date;
+
+#[derive(Debug, Clone)]
+pub struct Person {
+    pub metadata: Metadata,
+}
+
+impl ZolaResource for Person {
+    fn id(&self) -> &str {
+        &self.metadata.extra.id
+    }
+
+    fn path(&self) -> String {
+        format!("{}.md", self.id())
+    }
+
+    fn resource_type(&self) -> Option<&ResourceType> {
+        Some(&ResourceType::Person)
+    }
+}
+
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let metadata = toml::to_string(&self.metadata).expect("metadata to serialize as TOML");
+
+        writeln!(f, "+++")?;
+        write!(f, "{}", &metadata)?;
+        writeln!(f, "+++")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Metadata {
+    pub(crate) title: String,
+    pub(crate) slug: String,
+    /// Guests use their own template.
+    pub(crate) template: String,
+    pub(crate) in_search_index: bool,
+    pub(crate) extra: Extra,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Extra {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) guest: bool,
+    pub(crate) accounts: Vec<Account>,
+    /// Newest first.
+    pub(crate) notes: Vec<Work>,
+    /// Newest first.
+    pub(crate) sketches: Vec<Thumbnail>,
+}
+
+/// A service account, such as Github or Mastodon.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Account {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) username: String,
+    pub(crate) url: String,
+}
+
+impl TryFrom<&Row<'_>> for Account {
+    type Error = anyhow::Error;
+
+    fn try_from(row: &Row) -> Result<Self> {
+        let resource = Self {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            username: row.get(2)?,
+            url: row.get(3)?,
+        };
+
+        Ok(resource)
+    }
+}
+
+/// A note by the person.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Work {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) date: Date,
+}
+
+impl TryFrom<&Row<'_>> for Work {
+    type Error = anyhow::Error;
+
+    fn try_from(row: &Row) -> Result<Self> {
+        let date: String = row.get(2)?;
+        let resource = Self {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            date: Date::from_str(&date)?,
+        };
+
+        Ok(resource)
+    }
+}
+
+impl TryFrom<&Row<'_>> for Person {
+    type Error = anyhow::Error;
+
+    fn try_from(row: &Row) -> Result<Self> {
+        let id: String = row.get(0)?;
+        let name: String = row.get(1)?;
+        let guest: bool = row.get(2)?;
+        let template = if guest { "guest.html" } else { "person.html" };
+
+        let extra = Extra {
+            id: id.clone(),
+            name: name.clone(),
+            guest,
+            accounts: Vec::new(),
+            notes: Vec::new(),
+            sketches: Vec::new(),
+        };
+        let metadata = Metadata {
+            title: name,
+            slug: id,
+            template: template.to_owned(),
+            in_search_index: true,
+            extra,
+        };
+
+        Ok(Self { metadata })
+    }
+}
+
+fn select<T>(tx: &Transaction, query: &str, id: &str) -> Result<Vec<T>>
+where
+    T: for<'a, 'b> TryFrom<&'a Row<'b>, Error = anyhow::Error>,
+{
+    let mut set = Vec::new();
+    let mut stmt = tx.prepare(query)?;
+    let mut rows = stmt.query(params![id])?;
+
+    while let Some(row) = rows.next()? {
+        set.push(T::try_from(row)?);
+    }
+
+    Ok(set)
+}
+
+/// Selects the service accounts for the given person.
+pub fn select_accounts(tx: &Transaction, person_id: &str) -> Result<Vec<Account>> {
+    select(
+        tx,
+        r#"
+        SELECT
+            id,
+            name,
+            username,
+            url
+        FROM
+            service_account
+        WHERE
+            person_id = ?
+        ORDER BY
+            id
+        "#,
+        person_id,
+    )
+}
+
+/// Selects the service accounts for every person who is not a guest.
+///
+/// These are the accounts the site can claim with `rel="me"`.
+pub fn select_own_accounts(tx: &Transaction) -> Result<Vec<Account>> {
+    let mut set = Vec::new();
+    let mut stmt = tx.prepare(
+        r#"
+        SELECT
+            service_account.id,
+            service_account.name,
+            service_account.username,
+            service_account.url
+        FROM
+            service_account
+        INNER JOIN
+            person
+        ON
+            service_account.person_id = person.id
+        WHERE
+            person.guest = false
+        ORDER BY
+            person.id,
+            service_account.id
+        "#,
+    )?;
+    let mut rows = stmt.query([])?;
+
+    while let Some(row) = rows.next()? {
+        set.push(Account::try_from(row)?);
+    }
+
+    Ok(set)
+}
+
+fn select_notes(tx: &Transaction, person_id: &str) -> Result<Vec<Work>> {
+    select(
+        tx,
+        r#"
+        SELECT
+            id,
+            title,
+            publication_date
+        FROM
+            note
+        WHERE
+            author_id = ?
+        AND
+            id NOT IN (SELECT id FROM excluded WHERE resource_type = 'note')
+        ORDER BY
+            publication_date DESC,
+            id
+        "#,
+        person_id,
+    )
+}
+
+fn select_sketches(tx: &Transaction, person_id: &str) -> Result<Vec<Thumbnail>> {
+    select(
+        tx,
+        r#"
+        SELECT
+            id,
+            title,
+            publication_date,
+            asset_id
+        FROM
+            sketch
+        WHERE
+            author_id = ?
+        AND
+            id NOT IN (SELECT id FROM excluded WHERE resource_type = 'sketch')
+        ORDER BY
+            publication_date DESC,
+            id
+        "#,
+        person_id,
+    )
+}
+
+pub fn amass(tx: &Transaction) -> Result<Vec<Person>> {
+    let mut set = Vec::new();
+    let mut stmt = tx.prepare(
+        r#"
+        SELECT
+            id,
+            name,
+            guest
+        FROM
+            person
+        "#,
+    )?;
+    let mut rows = stmt.query([])?;
+
+    while let Some(row) = rows.next()? {
+        let mut resource = Person::try_from(row)?;
+        let id = resource.id().to_string();
+        let extra = &mut resource.metadata.extra;
+        extra.accounts = select_accounts(tx, &id)?;
+        extra.notes = select_notes(tx, &id)?;
+        extra.sketches = select_sketches(tx, &id)?;
+
+        set.push(resource);
+    }
+
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::records::{
+        AuxRecord, NoteRecord, PersonRecord, Record, ServiceAccountRecord,
+    };
+    use crate::cache::Cache;
+
+    #[test]
+    fn guests_apart() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+
+        for (id, guest) in &[("arnau", false), ("bob", true)] {
+            PersonRecord {
+                id: id.to_string(),
+                checksum: id.to_string(),
+                name: id.to_string(),
+                guest: *guest,
+            }
+            .insert(&tx)?;
+            ServiceAccountRecord {
+                id: "github".into(),
+                person_id: id.to_string(),
+                checksum: id.to_string(),
+                name: "Github".into(),
+                username: format!("@{}", id),
+                url: format!("https://github.com/{}", id),
+            }
+            .insert(&tx)?;
+        }
+        NoteRecord {
+            id: "a-note".into(),
+            checksum: "a-note".into(),
+            title: "A note".into(),
+            summary: "A summary.".into(),
+            publication_date: "2021-07-07".into(),
+            author_id: "bob".into(),
+            body: "".into(),
+            draft: false,
+        }
+        .insert(&tx)?;
+
+        let people = amass(&tx)?;
+        let bob = people
+            .iter()
+            .find(|person| person.id() == "bob")
+            .expect("bob to exist");
+        let own_accounts = select_own_accounts(&tx)?;
+
+        assert_eq!(&bob.metadata.template, "guest.html");
+        assert_eq!(bob.metadata.extra.notes[0].id, "a-note");
+        assert_eq!(own_accounts.len(), 1);
+        assert_eq!(own_accounts[0].url, "https://github.com/arnau");
+
+        Ok(())
+    }
+}
@@ -2,14 +2,17 @@
 //!
 //! [Zola page]: https://www.getzola.org/documentation/content/page/
 use anyhow::Result;
+use log::warn;
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
 
+use super::taxonomies::{self, Taxonomies};
 use super::ZolaResource;
+use crate::cache::records::TagTable;
 use crate::cache::{Row, Transaction};
-use crate::markdown::strip;
+use crate::markdown::{strip, Heading, Toc};
 use crate::resource_type::ResourceType;
 use crate::stamp::Date;
 
@@ -49,8 +52,13 @@ pub struct Metadata {
     pub(crate) title: String,
     pub(crate) description: String,
     pub(crate) date: Date,
+    /// Scheduled resources are drafts too. Only present when drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) draft: bool,
     pub(crate) template: String,
     pub(crate) in_search_index: bool,
+    #[serde(default, skip_serializing_if = "Taxonomies::is_empty")]
+    pub(crate) taxonomies: Taxonomies,
     pub(crate) extra: Extra,
 }
 
@@ -65,6 +73,8 @@ pub struct Extra {
     pub(crate) end_date: Option<Date>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) source_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) toc: Vec<Heading>,
 }
 
 impl TryFrom<&Row<'_>> for Project {
@@ -79,6 +89,7 @@ impl TryFrom<&Row<'_>> for Project {
         let raw_start_date: String = row.get(5)?;
         let raw_end_date: Option<String> = row.get(6)?;
         let source_url: Option<String> = row.get(7)?;
+        let draft: bool = row.get(8)?;
 
         let start_date = Date::from_str(&raw_start_date)?;
         let end_date = if let Some(end_date) = raw_end_date {
@@ -88,6 +99,10 @@ impl TryFrom<&Row<'_>> for Project {
         };
         let clean_title = strip(&title);
         let clean_description = strip(&summary);
+        let toc = Toc::new(&body);
+        for warning in &toc.warnings {
+            warn!("project '{}': {}", &id, warning);
+        }
 
         let extra = Extra {
             id,
@@ -97,6 +112,7 @@ impl TryFrom<&Row<'_>> for Project {
             start_date,
             end_date,
             source_url,
+            toc: toc.headings,
         };
         let metadata = Metadata {
             title: clean_title,
@@ -104,6 +120,8 @@ impl TryFrom<&Row<'_>> for Project {
             date: start_date,
             template: "project.html".to_owned(),
             in_search_index: true,
+            taxonomies: Taxonomies::default(),
+            draft,
             extra,
         };
         let resource = Self { metadata, body };
@@ -124,15 +142,21 @@ pub fn amass(tx: &Transaction) -> Result<Vec<Project>> {
             status,
             start_date,
             end_date,
-            source_url
+            source_url,
+            id IN (SELECT id FROM unpublished WHERE resource_type = 'project') AS draft
         FROM
             project
+        WHERE
+            id NOT IN (SELECT id FROM excluded WHERE resource_type = 'project')
         "#,
     )?;
     let mut rows = stmt.query([])?;
 
     while let Some(row) = rows.next()? {
-        let record = Project::try_from(row)?;
+        let mut record = Project::try_from(row)?;
+        let tags = taxonomies::select_tags(tx, TagTable::Project, record.id())?;
+        record.metadata.taxonomies = Taxonomies::new(tags);
+
         set.push(record);
     }
 
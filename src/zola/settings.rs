@@ -6,6 +6,7 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
 
+use super::person::Account;
 use super::ZolaResource;
 use crate::cache::records::*;
 use crate::cache::Transaction;
@@ -22,7 +23,10 @@ pub struct Settings {
     pub url: String,
     pub copyright: String,
     pub navigation: Vec<String>,
-    licence: Licence,
+    pub licence: Licence,
+    /// The accounts owned by the site people, for `rel="me"` links.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<Account>,
 }
 
 impl ZolaResource for Settings {
@@ -58,9 +62,9 @@ impl fmt::Display for Settings {
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-struct Licence {
-    url: String,
-    name: String,
+pub struct Licence {
+    pub url: String,
+    pub name: String,
 }
 
 impl TryFrom<SettingsRecord> for Settings {
@@ -7,7 +7,9 @@ use std::fmt;
 use std::str::FromStr;
 
 use super::author::Author;
+use super::taxonomies::{self, Taxonomies};
 use super::ZolaResource;
+use crate::cache::records::TagTable;
 use crate::cache::{params, Row, Transaction};
 use crate::markdown::strip;
 use crate::resource_type::ResourceType;
@@ -74,9 +76,14 @@ pub struct Metadata {
     pub(crate) title: String,
     pub(crate) description: Option<String>,
     pub(crate) date: Date,
+    /// Scheduled resources are drafts too. Only present when drafts are published.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) draft: bool,
     pub(crate) slug: String,
     pub(crate) template: String,
     pub(crate) in_search_index: bool,
+    #[serde(default, skip_serializing_if = "Taxonomies::is_empty")]
+    pub(crate) taxonomies: Taxonomies,
     pub(crate) extra: Extra,
 }
 
@@ -119,6 +126,7 @@ impl TryFrom<&Row<'_>> for SketchBundle {
         let date: String = row.get(3)?;
         let asset_id: String = row.get(5)?;
         let asset: Vec<u8> = row.get(8)?;
+        let draft: bool = row.get(9)?;
         let author = Author {
             id: row.get(4)?,
             name: row.get(6)?,
@@ -138,6 +146,8 @@ impl TryFrom<&Row<'_>> for SketchBundle {
             date: Date::from_str(&date)?,
             template: "sketch.html".to_owned(),
             in_search_index: true,
+            taxonomies: Taxonomies::default(),
+            draft,
             extra,
         };
         let resource = Sketch { metadata, body };
@@ -194,7 +204,8 @@ pub fn amass(tx: &Transaction) -> Result<Vec<(Sketch, Asset)>> {
             sketch.asset_id,
             person.name,
             person.guest,
-            asset.content
+            asset.content,
+            sketch.id IN (SELECT id FROM unpublished WHERE resource_type = 'sketch') AS draft
         FROM
             sketch
         INNER JOIN
@@ -205,6 +216,8 @@ pub fn amass(tx: &Transaction) -> Result<Vec<(Sketch, Asset)>> {
             asset
         ON
             sketch.asset_id = asset.id
+        WHERE
+            sketch.id NOT IN (SELECT id FROM excluded WHERE resource_type = 'sketch')
             "#,
     )?;
     let mut rows = stmt.query([])?;
@@ -213,6 +226,8 @@ pub fn amass(tx: &Transaction) -> Result<Vec<(Sketch, Asset)>> {
         let SketchBundle { mut sketch, asset } = SketchBundle::try_from(row)?;
         let tools = select_tools(tx, sketch.id())?;
         sketch.metadata.extra.tools = tools;
+        let tags = taxonomies::select_tags(tx, TagTable::Sketch, sketch.id())?;
+        sketch.metadata.taxonomies = Taxonomies::new(tags);
 
         set.push((sketch, asset));
     }
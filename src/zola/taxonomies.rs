@@ -0,0 +1,50 @@
+//! This module covers the [Zola taxonomies] for a page.
+//!
+//! [Zola taxonomies]: https://www.getzola.org/documentation/content/taxonomies/
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::records::TagTable;
+use crate::cache::{params, Transaction};
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Taxonomies {
+    pub(crate) tags: Vec<String>,
+}
+
+impl Taxonomies {
+    pub fn new(tags: Vec<String>) -> Self {
+        Self { tags }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+/// Selects the tags from the given tag table for the resource identified by `id`.
+pub fn select_tags(tx: &Transaction, table: TagTable, id: &str) -> Result<Vec<String>> {
+    let mut set = Vec::new();
+    let mut stmt = tx.prepare(&format!(
+        r#"
+        SELECT
+            tag
+        FROM
+            {}
+        WHERE
+            {} = ?
+        ORDER BY
+            tag
+        "#,
+        table.table(),
+        table.column()
+    ))?;
+    let mut rows = stmt.query(params![id])?;
+
+    while let Some(row) = rows.next()? {
+        set.push(row.get(0)?);
+    }
+
+    Ok(set)
+}
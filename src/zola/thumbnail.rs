@@ -0,0 +1,34 @@
+//! This module covers the sketch thumbnail listed in the Zola pages of other resources, such as tools and people.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use crate::cache::Row;
+use crate::stamp::Date;
+
+/// A sketch as listed in another page, with enough to link to it and show its asset.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Thumbnail {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) date: Date,
+    pub(crate) asset_id: String,
+}
+
+impl TryFrom<&Row<'_>> for Thumbnail {
+    type Error = anyhow::Error;
+
+    fn try_from(row: &Row) -> Result<Self> {
+        let date: String = row.get(2)?;
+        let resource = Self {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            date: Date::from_str(&date)?,
+            asset_id: row.get(3)?,
+        };
+
+        Ok(resource)
+    }
+}
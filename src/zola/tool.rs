@@ -0,0 +1,244 @@
+//! This module covers the Zola page for a tool.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::thumbnail::Thumbnail;
+use super::ZolaResource;
+use crate::cache::{params, Row, Transaction};
+use crate::markdown::strip;
+use crate::resource_type::ResourceType;
+use crate::stamp::Date;
+
+#[derive(Debug, Clone)]
+pub struct Tool {
+    pub metadata: Metadata,
+    pub body: Option<String>,
+}
+
+impl ZolaResource for Tool {
+    fn id(&self) -> &str {
+        &self.metadata.extra.id
+    }
+
+    fn path(&self) -> String {
+        format!("{}.md", self.id())
+    }
+
+    fn resource_type(&self) -> Option<&ResourceType> {
+        Some(&ResourceType::Tool)
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let metadata = toml::to_string(&self.metadata).expect("metadata to serialize as TOML");
+
+        writeln!(f, "+++")?;
+        write!(f, "{}", &metadata)?;
+        writeln!(f, "+++")?;
+        if let Some(body) = &self.body {
+            write!(f, "{}", body)?;
+        }
+
+        write!(f, "")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Metadata {
+    pub(crate) title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) description: Option<String>,
+    /// The date of the most recent sketch made with the tool, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) date: Option<Date>,
+    pub(crate) slug: String,
+    pub(crate) template: String,
+    pub(crate) in_search_index: bool,
+    pub(crate) extra: Extra,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Extra {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    /// The sketches made with the tool, newest first.
+    pub(crate) sketches: Vec<Thumbnail>,
+}
+
+impl TryFrom<&Row<'_>> for Tool {
+    type Error = anyhow::Error;
+
+    fn try_from(row: &Row) -> Result<Self> {
+        let id: String = row.get(0)?;
+        let name: String = row.get(1)?;
+        let summary: Option<String> = row.get(2)?;
+        let url: Option<String> = row.get(3)?;
+
+        let extra = Extra {
+            id: id.clone(),
+            name: name.clone(),
+            summary: summary.clone(),
+            url,
+            sketches: Vec::new(),
+        };
+        let metadata = Metadata {
+            title: strip(&name),
+            description: summary.as_ref().map(|s| strip(s)),
+            date: None,
+            slug: id,
+            template: "tool.html".to_owned(),
+            in_search_index: true,
+            extra,
+        };
+        let resource = Self {
+            metadata,
+            body: summary,
+        };
+
+        Ok(resource)
+    }
+}
+
+pub fn select_sketches(tx: &Transaction, tool_id: &str) -> Result<Vec<Thumbnail>> {
+    let mut set = Vec::new();
+    let mut stmt = tx.prepare(
+        r#"
+        SELECT
+            sketch.id,
+            sketch.title,
+            sketch.publication_date,
+            sketch.asset_id
+        FROM
+            sketch
+        INNER JOIN
+            sketch_tool
+        ON
+            sketch.id = sketch_tool.sketch_id
+        WHERE
+            sketch_tool.tool_id = ?
+        AND
+            sketch.id NOT IN (SELECT id FROM excluded WHERE resource_type = 'sketch')
+        ORDER BY
+            sketch.publication_date DESC,
+            sketch.id
+        "#,
+    )?;
+    let mut rows = stmt.query(params![tool_id])?;
+
+    while let Some(row) = rows.next()? {
+        let resource = Thumbnail::try_from(row)?;
+
+        set.push(resource);
+    }
+
+    Ok(set)
+}
+
+pub fn amass(tx: &Transaction) -> Result<Vec<Tool>> {
+    let mut set = Vec::new();
+    let mut stmt = tx.prepare(
+        r#"
+        SELECT
+            id,
+            name,
+            summary,
+            url
+        FROM
+            tool
+        "#,
+    )?;
+    let mut rows = stmt.query([])?;
+
+    while let Some(row) = rows.next()? {
+        let mut resource = Tool::try_from(row)?;
+        let sketches = select_sketches(tx, resource.id())?;
+        resource.metadata.date = sketches.first().map(|sketch| sketch.date);
+        resource.metadata.extra.sketches = sketches;
+
+        set.push(resource);
+    }
+
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::records::{
+        AssetRecord, AuxRecord, PersonRecord, Record, SketchRecord, SketchToolRecord, ToolRecord,
+    };
+    use crate::cache::Cache;
+    use std::str::FromStr;
+
+    #[test]
+    fn newest_sketch_first() -> Result<()> {
+        let mut cache = Cache::connect(":memory:")?;
+        let tx = cache.transaction()?;
+
+        ToolRecord {
+            id: "ipadpro".into(),
+            checksum: "ipadpro".into(),
+            name: "iPad Pro".into(),
+            summary: Some("A tablet.".into()),
+            url: None,
+        }
+        .insert(&tx)?;
+        PersonRecord {
+            id: "arnau".into(),
+            checksum: "arnau".into(),
+            name: "Arnau Siches".into(),
+            guest: false,
+        }
+        .insert(&tx)?;
+
+        for (id, date) in &[("old-dragon", "2017-09-29"), ("new-dragon", "2021-01-10")] {
+            AssetRecord {
+                id: format!("{}.png", id),
+                checksum: id.to_string(),
+                content_type: "png".into(),
+                content: vec![],
+            }
+            .insert(&tx)?;
+            SketchRecord {
+                id: id.to_string(),
+                checksum: id.to_string(),
+                title: id.to_string(),
+                asset_id: format!("{}.png", id),
+                author_id: "arnau".into(),
+                publication_date: date.to_string(),
+                summary: None,
+                draft: false,
+            }
+            .insert(&tx)?;
+            SketchToolRecord {
+                sketch_id: id.to_string(),
+                tool_id: "ipadpro".into(),
+            }
+            .insert(&tx)?;
+        }
+
+        let tools = amass(&tx)?;
+        let tool = &tools[0];
+        let sketches: Vec<&str> = tool
+            .metadata
+            .extra
+            .sketches
+            .iter()
+            .map(|sketch| sketch.id.as_str())
+            .collect();
+
+        assert_eq!(sketches, vec!["new-dragon", "old-dragon"]);
+        assert_eq!(tool.metadata.date, Some(Date::from_str("2021-01-10")?));
+        assert!(tool.to_string().contains("template = \"tool.html\""));
+
+        Ok(())
+    }
+}